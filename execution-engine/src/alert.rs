@@ -0,0 +1,238 @@
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::Result;
+
+/// Severity of an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Info,
+    /// Something was refused, such as an order rejected before submission
+    Warning,
+    /// Something broke, such as an order the venue failed
+    Error,
+}
+
+/// Destination for operator alerts (chat, email, paging)
+///
+/// The engine queues alerts for a single background task and only logs a
+/// failed delivery, so a slow or broken sink never holds up or fails an
+/// order. Alerts raised while [`ALERT_QUEUE_CAPACITY`] are already waiting
+/// are dropped with a warning.
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn alert(&self, level: Level, message: String) -> Result<()>;
+}
+
+/// Alerts that may wait for delivery before new ones are dropped
+pub const ALERT_QUEUE_CAPACITY: usize = 1024;
+
+/// Bounded queue feeding alerts to a sink one at a time
+///
+/// The delivery task is started on the first alert, so building an engine
+/// needs no runtime; it ends once every clone of the queue is dropped.
+pub(crate) struct AlertQueue {
+    sink: Arc<dyn AlertSink>,
+    capacity: usize,
+    tx: OnceLock<mpsc::Sender<(Level, String)>>,
+}
+
+impl AlertQueue {
+    pub(crate) fn new(sink: Arc<dyn AlertSink>, capacity: usize) -> Self {
+        Self {
+            sink,
+            capacity,
+            tx: OnceLock::new(),
+        }
+    }
+
+    /// Queue an alert for delivery, dropping it if the queue is full
+    pub(crate) fn send(&self, level: Level, message: String) {
+        let tx = self.tx.get_or_init(|| {
+            let (tx, mut rx) = mpsc::channel::<(Level, String)>(self.capacity);
+            let sink = self.sink.clone();
+            tokio::spawn(async move {
+                while let Some((level, message)) = rx.recv().await {
+                    if let Err(e) = sink.alert(level, message).await {
+                        tracing::warn!("Failed to deliver alert: {}", e);
+                    }
+                }
+            });
+            tx
+        });
+        if let Err(e) = tx.try_send((level, message)) {
+            tracing::warn!("Dropping alert, delivery queue unavailable: {}", e);
+        }
+    }
+}
+
+/// Discards every alert; the engine's default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAlertSink;
+
+#[async_trait]
+impl AlertSink for NoopAlertSink {
+    async fn alert(&self, _level: Level, _message: String) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Time a webhook delivery may take before it fails, unless set with
+/// [`WebhookAlertSink::with_timeout`]
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Posts each alert as `{"level": ..., "message": ...}` JSON to a URL
+#[derive(Debug, Clone)]
+pub struct WebhookAlertSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookAlertSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_timeout(url, WEBHOOK_TIMEOUT)
+    }
+
+    /// Sink whose deliveries fail after `timeout`
+    ///
+    /// Panics, as [`reqwest::Client::new`] does, if the HTTP client cannot
+    /// be initialized.
+    pub fn with_timeout(url: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("failed to build the webhook HTTP client"),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    level: Level,
+    message: &'a str,
+}
+
+#[async_trait]
+impl AlertSink for WebhookAlertSink {
+    async fn alert(&self, level: Level, message: String) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(&WebhookPayload {
+                level,
+                message: &message,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_webhook_posts_json() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            // Read until the JSON body has arrived
+            while !request.ends_with(b"}") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let sink = WebhookAlertSink::new(url);
+        sink.alert(Level::Error, "order failed".to_string())
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /hook"));
+        assert!(request.ends_with(r#"{"level":"error","message":"order failed"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_times_out() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        // Accept the connection but never answer
+        let server = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+        let sink = WebhookAlertSink::with_timeout(url, Duration::from_millis(50));
+        let err = sink
+            .alert(Level::Error, "order failed".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::Http(e) if e.is_timeout()));
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_queue_delivers_in_order_and_drops_when_full() {
+        struct GatedSink {
+            gate: tokio::sync::Semaphore,
+            delivered: tokio::sync::mpsc::UnboundedSender<String>,
+        }
+
+        #[async_trait]
+        impl AlertSink for GatedSink {
+            async fn alert(&self, _level: Level, message: String) -> Result<()> {
+                self.gate.acquire().await.unwrap().forget();
+                self.delivered.send(message).unwrap();
+                Ok(())
+            }
+        }
+
+        let (tx, mut delivered) = tokio::sync::mpsc::unbounded_channel();
+        let sink = Arc::new(GatedSink {
+            gate: tokio::sync::Semaphore::new(0),
+            delivered: tx,
+        });
+        let queue = AlertQueue::new(sink.clone(), 2);
+
+        // The worker takes the first alert and blocks on it; two more fill
+        // the queue and the fourth is dropped
+        queue.send(Level::Info, "0".to_string());
+        tokio::task::yield_now().await;
+        for i in 1..4 {
+            queue.send(Level::Info, i.to_string());
+        }
+        sink.gate.add_permits(4);
+
+        let mut messages = Vec::new();
+        for _ in 0..3 {
+            messages.push(delivered.recv().await.unwrap());
+        }
+        assert_eq!(messages, ["0", "1", "2"]);
+        // Dropping the queue ends the delivery task, which drops the sink
+        drop(queue);
+        drop(sink);
+        assert!(delivered.recv().await.is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    crypto,
+    execution::{Order, SigningScheme},
+    jsonl, Error, Result,
+};
+
+/// One signature the engine produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub order_id: Uuid,
+    /// [`SigningKey::key_id`](crate::SigningKey::key_id) of the signer
+    pub key_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// SHA-256 of the exact bytes signed
+    #[serde(with = "hex::serde")]
+    pub canonical_hash: Vec<u8>,
+}
+
+impl AuditEvent {
+    /// Event for `order` signed by `key_id` over its `scheme` encoding
+    pub fn for_order(
+        order: &Order,
+        key_id: impl Into<String>,
+        scheme: SigningScheme,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Self> {
+        Ok(Self {
+            order_id: order.id,
+            key_id: key_id.into(),
+            timestamp,
+            canonical_hash: crypto::hash_data(&order.signing_bytes(scheme)?).to_vec(),
+        })
+    }
+}
+
+/// Destination for the record of every signature the engine produces
+///
+/// Recording must not fail the order, so sinks handle their own errors.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, event: AuditEvent);
+}
+
+/// An [`AuditEvent`] as written by [`FileAuditSink`], chained to the line
+/// before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AuditRecord {
+    #[serde(flatten)]
+    event: AuditEvent,
+    /// `hash` of the previous record, `None` for the first in the file
+    prev_hash: Option<String>,
+    /// Hex SHA-256 of `prev_hash` followed by the event's JSON
+    hash: String,
+}
+
+impl AuditRecord {
+    fn chain(event: AuditEvent, prev_hash: Option<String>) -> Result<Self> {
+        let hash = chain_hash(prev_hash.as_deref(), &event)?;
+        Ok(Self {
+            event,
+            prev_hash,
+            hash,
+        })
+    }
+}
+
+fn chain_hash(prev_hash: Option<&str>, event: &AuditEvent) -> Result<String> {
+    let mut data = prev_hash.unwrap_or_default().as_bytes().to_vec();
+    data.extend(serde_json::to_vec(event)?);
+    Ok(hex::encode(crypto::hash_data(&data)))
+}
+
+/// Appends audit events to a local file, one JSON object per line
+///
+/// Each line carries the hash of the one before it, so an edited, removed
+/// or reordered line breaks the chain checked by [`read_audit_log`].
+/// Appending to an existing file continues its chain.
+#[derive(Debug)]
+pub struct FileAuditSink {
+    path: PathBuf,
+    /// Hash of the file's last record, read from the file on the first
+    /// write; the lock also serializes appends
+    tail: Arc<Mutex<Option<Option<String>>>>,
+}
+
+impl FileAuditSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            tail: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn append(&self, event: AuditEvent) -> Result<()> {
+        let path = self.path.clone();
+        let tail = self.tail.clone();
+        jsonl::blocking(move || {
+            let mut tail = tail.lock().unwrap();
+            let prev_hash = match tail.as_ref() {
+                Some(hash) => hash.clone(),
+                None => last_hash(&path)?,
+            };
+            let record = AuditRecord::chain(event, prev_hash)?;
+            jsonl::append(&path, &record)?;
+            *tail = Some(Some(record.hash));
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Hash of the last record in `path`, `None` if the file is missing or empty
+fn last_hash(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let records: Vec<AuditRecord> = jsonl::read(path)?;
+    Ok(records.last().map(|record| record.hash.clone()))
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, event: AuditEvent) {
+        let order_id = event.order_id;
+        if let Err(e) = self.append(event).await {
+            tracing::error!("Failed to write audit event for order {}: {}", order_id, e);
+        }
+    }
+}
+
+/// Read every event from a file written by [`FileAuditSink`], checking the
+/// hash chain
+///
+/// A line that does not parse, or whose hashes do not link to the line
+/// before it, is an error.
+pub async fn read_audit_log(path: impl AsRef<Path>) -> Result<Vec<AuditEvent>> {
+    let path = path.as_ref().to_path_buf();
+    let records: Vec<AuditRecord> = jsonl::blocking(move || jsonl::read(&path)).await?;
+
+    let mut prev_hash: Option<String> = None;
+    let mut events = Vec::with_capacity(records.len());
+    for (i, record) in records.into_iter().enumerate() {
+        if record.prev_hash != prev_hash
+            || record.hash != chain_hash(prev_hash.as_deref(), &record.event)?
+        {
+            return Err(Error::Crypto(format!(
+                "audit log hash chain broken at record {}",
+                i + 1
+            )));
+        }
+        prev_hash = Some(record.hash);
+        events.push(record.event);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderSide, OrderType};
+    use crate::SigningKey;
+
+    fn signed_event(key: &SigningKey) -> AuditEvent {
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        order.sign(key).unwrap();
+        AuditEvent::for_order(&order, key.key_id(), SigningScheme::Binary, Utc::now()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_file_audit_sink_writes_a_hash_chain() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", Uuid::new_v4()));
+        let sink = FileAuditSink::new(&path);
+        let key = SigningKey::generate();
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            let event = signed_event(&key);
+            sink.record(event.clone()).await;
+            events.push(event);
+        }
+        // A new sink on the same file continues the chain
+        let event = signed_event(&key);
+        FileAuditSink::new(&path).record(event.clone()).await;
+        events.push(event);
+
+        let read = read_audit_log(&path).await.unwrap();
+        assert_eq!(read, events);
+        assert_eq!(read[0].canonical_hash.len(), 32);
+
+        // Dropping a line breaks the chain
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(matches!(read_audit_log(&path).await, Err(Error::Crypto(_))));
+
+        // So does editing one
+        let edited = lines[0].replace(&events[0].key_id, "forged");
+        std::fs::write(&path, format!("{}\n{}\n", edited, lines[1])).unwrap();
+        assert!(read_audit_log(&path).await.is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
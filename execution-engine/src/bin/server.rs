@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use execution_engine::server::{router, AppState};
+use execution_engine::{
+    Database, ExecutionEngine, SigningKey, SigningScheme, Store, VerificationKey,
+};
+
+/// Serve the execution engine over HTTP
+///
+/// Configuration comes from the environment:
+/// - `VERIFICATION_KEY`: hex public key submitted orders must be signed with
+/// - `CLIENT_SIGNING_SCHEME`: optional JSON [`SigningScheme`] clients sign
+///   with, e.g. `"Json"`; default `"Binary"`
+/// - `DATABASE_URL`: optional PostgreSQL URL backing `GET /orders`
+/// - `BIND_ADDR`: listen address, default `0.0.0.0:8080`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let key_hex = std::env::var("VERIFICATION_KEY")
+        .map_err(|_| anyhow::anyhow!("VERIFICATION_KEY must be set"))?;
+    let verification_key = VerificationKey::from_bytes(&hex::decode(key_hex.trim())?)?;
+    let client_scheme: SigningScheme = match std::env::var("CLIENT_SIGNING_SCHEME") {
+        Ok(scheme) => serde_json::from_str(&scheme)?,
+        Err(_) => SigningScheme::default(),
+    };
+
+    let store: Option<Arc<dyn Store>> = match std::env::var("DATABASE_URL") {
+        Ok(url) => {
+            let db = Database::connect(&url).await?;
+            db.initialize().await?;
+            Some(Arc::new(db))
+        }
+        Err(_) => {
+            tracing::warn!("DATABASE_URL not set; order history is disabled");
+            None
+        }
+    };
+
+    // In production, load the engine's signing key from secure storage
+    let mut engine = ExecutionEngine::new(SigningKey::generate());
+    if let Some(store) = &store {
+        engine = engine.with_store(store.clone());
+    }
+
+    let state = AppState {
+        engine,
+        verification_key,
+        client_scheme,
+        store,
+    };
+
+    let addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string());
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    tracing::info!("Execution engine server listening on {}", addr);
+    axum::serve(listener, router(state)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::execution::OrderSide;
+
+/// Resting quantity at one price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Depth snapshot for one symbol
+///
+/// Bids are kept best (highest) first and asks best (lowest) first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub symbol: String,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OrderBook {
+    /// Book from `(price, quantity)` levels in any order
+    ///
+    /// Levels without a positive, finite price and quantity are dropped.
+    pub fn new(
+        symbol: impl Into<String>,
+        bids: Vec<(f64, f64)>,
+        asks: Vec<(f64, f64)>,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let levels = |levels: Vec<(f64, f64)>| -> Vec<BookLevel> {
+            levels
+                .into_iter()
+                .filter(|(price, quantity)| {
+                    price.is_finite() && *price > 0.0 && quantity.is_finite() && *quantity > 0.0
+                })
+                .map(|(price, quantity)| BookLevel { price, quantity })
+                .collect()
+        };
+
+        let mut bids = levels(bids);
+        let mut asks = levels(asks);
+        bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+
+        Self {
+            symbol: symbol.into(),
+            bids,
+            asks,
+            timestamp,
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// Fill a market order for `quantity` against the opposite side
+    ///
+    /// Returns the volume-weighted fill price and the quantity filled, which
+    /// is less than `quantity` when the book is too thin, or `None` when
+    /// that side is empty. The book itself is not depleted.
+    pub fn sweep(&self, side: OrderSide, quantity: f64) -> Option<(f64, f64)> {
+        let levels = match side {
+            OrderSide::Buy => &self.asks,
+            OrderSide::Sell => &self.bids,
+        };
+
+        let mut remaining = quantity;
+        let mut notional = 0.0;
+        for level in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            let take = remaining.min(level.quantity);
+            notional += take * level.price;
+            remaining -= take;
+        }
+
+        let filled = quantity - remaining.max(0.0);
+        (filled > 0.0).then(|| (notional / filled, filled))
+    }
+
+    /// Fill a market order spending `notional` of the quote currency
+    ///
+    /// As [`sweep`](Self::sweep), but returns the volume-weighted fill
+    /// price, the base quantity filled and the part of `notional` left
+    /// unspent because the book is too thin.
+    pub fn sweep_notional(&self, side: OrderSide, notional: f64) -> Option<(f64, f64, f64)> {
+        let levels = match side {
+            OrderSide::Buy => &self.asks,
+            OrderSide::Sell => &self.bids,
+        };
+
+        let mut remaining = notional;
+        let mut filled = 0.0;
+        for level in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            let cost = level.quantity * level.price;
+            if cost >= remaining {
+                filled += remaining / level.price;
+                remaining = 0.0;
+            } else {
+                filled += level.quantity;
+                remaining -= cost;
+            }
+        }
+
+        (filled > 0.0).then(|| ((notional - remaining) / filled, filled, remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_walks_levels() {
+        let book = OrderBook::new(
+            "BTC/USD",
+            vec![(99.0, 1.0), (100.0, 2.0), (0.0, 5.0)],
+            vec![(102.0, 1.0), (101.0, 1.0)],
+            Utc::now(),
+        );
+        assert_eq!(book.best_bid(), Some(100.0));
+        assert_eq!(book.best_ask(), Some(101.0));
+        assert_eq!(book.bids.len(), 2);
+
+        // One and a half levels: (101 + 0.5 * 102) / 1.5
+        let (price, filled) = book.sweep(OrderSide::Buy, 1.5).unwrap();
+        assert_eq!(filled, 1.5);
+        assert!((price - 152.0 / 1.5).abs() < 1e-9);
+
+        // Deeper than the book: partial fill
+        assert_eq!(book.sweep(OrderSide::Sell, 5.0), Some((299.0 / 3.0, 3.0)));
+
+        let empty = OrderBook::new("BTC/USD", vec![], vec![], Utc::now());
+        assert_eq!(empty.sweep(OrderSide::Buy, 1.0), None);
+    }
+
+    #[test]
+    fn test_sweep_notional() {
+        let book = OrderBook::new(
+            "BTC/USD",
+            vec![(100.0, 2.0)],
+            vec![(100.0, 1.0), (200.0, 1.0)],
+            Utc::now(),
+        );
+
+        // All of the first level and a quarter of the second
+        let (price, filled, unspent) = book.sweep_notional(OrderSide::Buy, 150.0).unwrap();
+        assert_eq!(filled, 1.25);
+        assert_eq!(unspent, 0.0);
+        assert!((price - 120.0).abs() < 1e-9);
+
+        // More than the book holds
+        assert_eq!(
+            book.sweep_notional(OrderSide::Sell, 500.0),
+            Some((100.0, 2.0, 300.0))
+        );
+        let empty = OrderBook::new("BTC/USD", vec![], vec![], Utc::now());
+        assert_eq!(empty.sweep_notional(OrderSide::Buy, 100.0), None);
+    }
+}
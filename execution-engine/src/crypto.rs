@@ -1,152 +1,646 @@
+use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, DecodePublicKey, EncodePublicKey};
 use ed25519_dalek::{
     Signature as Ed25519Signature, Signer, SigningKey as Ed25519SigningKey, Verifier, VerifyingKey,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::{Error, Result};
 
-/// Wrapper around Ed25519 signing key
+/// A signing primitive: how a key signs bytes and how a signature is checked
+///
+/// Order canonical bytes do not depend on the algorithm; only the primitive
+/// applied to them does. Which bytes are signed is chosen separately, by
+/// [`SigningScheme`](crate::SigningScheme). [`SigningKey`], [`VerificationKey`] and
+/// [`Signature`] dispatch to [`Ed25519`] or, with the `secp256k1` feature,
+/// to `Secp256k1`, depending on the key.
+pub trait SignatureScheme {
+    /// Lowercase name, as in logs and key listings
+    const NAME: &'static str;
+
+    type SigningKey;
+    type VerifyingKey;
+    type Signature;
+
+    fn sign(key: &Self::SigningKey, data: &[u8]) -> Self::Signature;
+
+    fn verify(key: &Self::VerifyingKey, data: &[u8], signature: &Self::Signature) -> Result<()>;
+
+    /// Signature in the algorithm's standard byte encoding
+    fn to_bytes(signature: &Self::Signature) -> Vec<u8>;
+}
+
+/// Ed25519 (RFC 8032), the engine's default algorithm
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ed25519;
+
+impl SignatureScheme for Ed25519 {
+    const NAME: &'static str = "ed25519";
+
+    type SigningKey = Ed25519SigningKey;
+    type VerifyingKey = VerifyingKey;
+    type Signature = Ed25519Signature;
+
+    fn sign(key: &Ed25519SigningKey, data: &[u8]) -> Ed25519Signature {
+        key.sign(data)
+    }
+
+    fn verify(key: &VerifyingKey, data: &[u8], signature: &Ed25519Signature) -> Result<()> {
+        key.verify(data, signature)
+            .map_err(|e| Error::Crypto(format!("Signature verification failed: {}", e)))
+    }
+
+    fn to_bytes(signature: &Ed25519Signature) -> Vec<u8> {
+        signature.to_bytes().to_vec()
+    }
+}
+
+/// ECDSA over secp256k1 with SHA-256 and RFC 6979 nonces, as some venues
+/// require
+///
+/// Signatures are the 64-byte compact `r || s` form, normalized to low `s`.
+#[cfg(feature = "secp256k1")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Secp256k1;
+
+#[cfg(feature = "secp256k1")]
+impl SignatureScheme for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+
+    type SigningKey = k256::ecdsa::SigningKey;
+    type VerifyingKey = k256::ecdsa::VerifyingKey;
+    type Signature = k256::ecdsa::Signature;
+
+    fn sign(key: &k256::ecdsa::SigningKey, data: &[u8]) -> k256::ecdsa::Signature {
+        let signature: k256::ecdsa::Signature = key.sign(data);
+        signature.normalize_s().unwrap_or(signature)
+    }
+
+    fn verify(
+        key: &k256::ecdsa::VerifyingKey,
+        data: &[u8],
+        signature: &k256::ecdsa::Signature,
+    ) -> Result<()> {
+        key.verify(data, signature)
+            .map_err(|e| Error::Crypto(format!("Signature verification failed: {}", e)))
+    }
+
+    fn to_bytes(signature: &k256::ecdsa::Signature) -> Vec<u8> {
+        signature.to_bytes().to_vec()
+    }
+}
+
+#[derive(Clone)]
+enum SigningInner {
+    Ed25519(Ed25519SigningKey),
+    #[cfg(feature = "secp256k1")]
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+/// Private signing key for either supported algorithm
 #[derive(Clone)]
 pub struct SigningKey {
-    inner: Ed25519SigningKey,
+    inner: SigningInner,
 }
 
 impl SigningKey {
-    /// Generate a new random signing key
+    /// Generate a new random Ed25519 signing key
     pub fn generate() -> Self {
         use rand::RngCore;
         let mut csprng = rand::rngs::OsRng;
         let mut secret_bytes = [0u8; 32];
         csprng.fill_bytes(&mut secret_bytes);
         Self {
-            inner: Ed25519SigningKey::from_bytes(&secret_bytes),
+            inner: SigningInner::Ed25519(Ed25519SigningKey::from_bytes(&secret_bytes)),
+        }
+    }
+
+    /// Generate a new random secp256k1 signing key
+    #[cfg(feature = "secp256k1")]
+    pub fn generate_secp256k1() -> Self {
+        Self {
+            inner: SigningInner::Secp256k1(k256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng)),
         }
     }
 
-    /// Create from bytes
+    /// Create an Ed25519 key from its 32-byte secret
+    ///
+    /// Use [`from_bytes_with`](Self::from_bytes_with) for keys of other
+    /// algorithms, whose secrets are also 32 bytes.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         let inner = Ed25519SigningKey::from_bytes(
             bytes
                 .try_into()
                 .map_err(|_| Error::Crypto("Invalid key length".to_string()))?,
         );
-        Ok(Self { inner })
+        Ok(Self {
+            inner: SigningInner::Ed25519(inner),
+        })
+    }
+
+    /// Create a secp256k1 key from its 32-byte secret scalar
+    #[cfg(feature = "secp256k1")]
+    pub fn from_secp256k1_bytes(bytes: &[u8]) -> Result<Self> {
+        let inner = k256::ecdsa::SigningKey::from_slice(bytes)
+            .map_err(|e| Error::Crypto(format!("Invalid secp256k1 key: {}", e)))?;
+        Ok(Self {
+            inner: SigningInner::Secp256k1(inner),
+        })
+    }
+
+    /// Create a key from its 32-byte secret for the algorithm named by
+    /// [`algorithm`](Self::algorithm), e.g. `"secp256k1"`
+    pub fn from_bytes_with(algorithm: &str, bytes: &[u8]) -> Result<Self> {
+        match algorithm {
+            Ed25519::NAME => Self::from_bytes(bytes),
+            #[cfg(feature = "secp256k1")]
+            Secp256k1::NAME => Self::from_secp256k1_bytes(bytes),
+            other => Err(Error::Crypto(format!(
+                "Unsupported signature algorithm {}",
+                other
+            ))),
+        }
+    }
+
+    /// [`SignatureScheme::NAME`] of this key's algorithm
+    pub fn algorithm(&self) -> &'static str {
+        match &self.inner {
+            SigningInner::Ed25519(_) => Ed25519::NAME,
+            #[cfg(feature = "secp256k1")]
+            SigningInner::Secp256k1(_) => Secp256k1::NAME,
+        }
     }
 
     /// Get the verification key
     pub fn verification_key(&self) -> VerificationKey {
-        VerificationKey {
-            inner: self.inner.verifying_key(),
-        }
+        let inner = match &self.inner {
+            SigningInner::Ed25519(key) => VerifyingInner::Ed25519(key.verifying_key()),
+            #[cfg(feature = "secp256k1")]
+            SigningInner::Secp256k1(key) => VerifyingInner::Secp256k1(*key.verifying_key()),
+        };
+        VerificationKey { inner }
+    }
+
+    /// Identifier of this key's verification key
+    pub fn key_id(&self) -> String {
+        self.verification_key().key_id()
     }
 
     /// Sign data
     pub fn sign(&self, data: &[u8]) -> Signature {
-        let signature = self.inner.sign(data);
-        Signature { inner: signature }
+        match &self.inner {
+            SigningInner::Ed25519(key) => Signature::of::<Ed25519>(&Ed25519::sign(key, data)),
+            #[cfg(feature = "secp256k1")]
+            SigningInner::Secp256k1(key) => Signature::of::<Secp256k1>(&Secp256k1::sign(key, data)),
+        }
     }
 
-    /// Export as bytes
+    /// Export the 32-byte secret, which
+    /// [`from_bytes_with`](Self::from_bytes_with) turns back into this key
+    /// given its [`algorithm`](Self::algorithm)
     pub fn to_bytes(&self) -> [u8; 32] {
-        self.inner.to_bytes()
+        match &self.inner {
+            SigningInner::Ed25519(key) => key.to_bytes(),
+            #[cfg(feature = "secp256k1")]
+            SigningInner::Secp256k1(key) => key.to_bytes().into(),
+        }
     }
 }
 
-/// Wrapper around Ed25519 verification key
+#[derive(Clone)]
+enum VerifyingInner {
+    Ed25519(VerifyingKey),
+    #[cfg(feature = "secp256k1")]
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+/// Public verification key for either supported algorithm
+///
+/// Serialized as its [`to_bytes`](Self::to_bytes) encoding, whose length
+/// tells the algorithms apart.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct VerificationKey {
     #[serde(
         serialize_with = "serialize_bytes",
         deserialize_with = "deserialize_bytes"
     )]
-    inner: VerifyingKey,
+    inner: VerifyingInner,
 }
 
-fn serialize_bytes<S>(key: &VerifyingKey, serializer: S) -> std::result::Result<S::Ok, S::Error>
+/// Hex string in human-readable formats (JSON), raw bytes in binary ones
+fn serialize_hex<S>(bytes: &[u8], serializer: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    serializer.serialize_str(&hex::encode(key.to_bytes()))
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
 }
 
-fn deserialize_bytes<'de, D>(deserializer: D) -> std::result::Result<VerifyingKey, D::Error>
+fn deserialize_hex<'de, D>(deserializer: D) -> std::result::Result<Vec<u8>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    let bytes = hex::decode(&s).map_err(serde::de::Error::custom)?;
-    let bytes: [u8; 32] = bytes
-        .try_into()
-        .map_err(|_| serde::de::Error::custom("Invalid key length"))?;
-    VerifyingKey::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    if deserializer.is_human_readable() {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(&s).map_err(serde::de::Error::custom)
+    } else {
+        Vec::<u8>::deserialize(deserializer)
+    }
+}
+
+fn serialize_bytes<S>(key: &VerifyingInner, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_hex(
+        &VerificationKey { inner: key.clone() }.to_bytes(),
+        serializer,
+    )
+}
+
+fn deserialize_bytes<'de, D>(deserializer: D) -> std::result::Result<VerifyingInner, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let bytes = deserialize_hex(deserializer)?;
+    VerificationKey::from_bytes(&bytes)
+        .map(|key| key.inner)
+        .map_err(serde::de::Error::custom)
 }
 
 impl VerificationKey {
     /// Verify a signature
     pub fn verify(&self, data: &[u8], signature: &Signature) -> Result<()> {
-        self.inner
-            .verify(data, &signature.inner)
-            .map_err(|e| Error::Crypto(format!("Signature verification failed: {}", e)))
+        match &self.inner {
+            VerifyingInner::Ed25519(key) => {
+                Ed25519::verify(key, data, &Ed25519Signature::from_bytes(&signature.bytes))
+            }
+            #[cfg(feature = "secp256k1")]
+            VerifyingInner::Secp256k1(key) => {
+                let signature = k256::ecdsa::Signature::from_slice(&signature.bytes)
+                    .map_err(|e| Error::Crypto(format!("Invalid secp256k1 signature: {}", e)))?;
+                Secp256k1::verify(key, data, &signature)
+            }
+        }
     }
 
-    /// Export as bytes
-    pub fn to_bytes(&self) -> [u8; 32] {
-        self.inner.to_bytes()
+    /// [`SignatureScheme::NAME`] of this key's algorithm
+    pub fn algorithm(&self) -> &'static str {
+        match &self.inner {
+            VerifyingInner::Ed25519(_) => Ed25519::NAME,
+            #[cfg(feature = "secp256k1")]
+            VerifyingInner::Secp256k1(_) => Secp256k1::NAME,
+        }
     }
 
-    /// Import from bytes
+    /// Short stable identifier: hex of the first 8 bytes of the key's SHA-256
+    pub fn key_id(&self) -> String {
+        hex::encode(&hash_data(&self.to_bytes())[..8])
+    }
+
+    /// Export as bytes: 32 for Ed25519, 33 (compressed SEC1) for secp256k1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match &self.inner {
+            VerifyingInner::Ed25519(key) => key.to_bytes().to_vec(),
+            #[cfg(feature = "secp256k1")]
+            VerifyingInner::Secp256k1(key) => key.to_encoded_point(true).as_bytes().to_vec(),
+        }
+    }
+
+    /// Import from bytes, as written by [`to_bytes`](Self::to_bytes)
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        let inner = VerifyingKey::from_bytes(
-            bytes
-                .try_into()
-                .map_err(|_| Error::Crypto("Invalid key length".to_string()))?,
-        )
-        .map_err(|e| Error::Crypto(format!("Invalid verification key: {}", e)))?;
+        let inner = match bytes.len() {
+            32 => VerifyingInner::Ed25519(
+                VerifyingKey::from_bytes(bytes.try_into().expect("length checked"))
+                    .map_err(|e| Error::Crypto(format!("Invalid verification key: {}", e)))?,
+            ),
+            #[cfg(feature = "secp256k1")]
+            33 | 65 => VerifyingInner::Secp256k1(
+                k256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                    .map_err(|e| Error::Crypto(format!("Invalid verification key: {}", e)))?,
+            ),
+            _ => return Err(Error::Crypto("Invalid key length".to_string())),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Export as a `PUBLIC KEY` (SubjectPublicKeyInfo) PEM, as written by
+    /// `openssl pkey -pubout`
+    pub fn to_pem(&self) -> String {
+        match &self.inner {
+            VerifyingInner::Ed25519(key) => key.to_public_key_pem(LineEnding::LF),
+            #[cfg(feature = "secp256k1")]
+            VerifyingInner::Secp256k1(key) => key.to_public_key_pem(LineEnding::LF),
+        }
+        .expect("public keys always encode")
+    }
+
+    /// Import a `PUBLIC KEY` PEM holding an Ed25519 (or secp256k1) key
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        let inner = VerifyingKey::from_public_key_pem(pem).map(VerifyingInner::Ed25519);
+        #[cfg(feature = "secp256k1")]
+        let inner = inner.or_else(|e| {
+            k256::ecdsa::VerifyingKey::from_public_key_pem(pem)
+                .map(VerifyingInner::Secp256k1)
+                .map_err(|_| e)
+        });
+        let inner = inner.map_err(|e| Error::Crypto(format!("Invalid public key PEM: {}", e)))?;
+        Ok(Self { inner })
+    }
+
+    /// Export as DER-encoded SubjectPublicKeyInfo
+    pub fn to_der(&self) -> Vec<u8> {
+        match &self.inner {
+            VerifyingInner::Ed25519(key) => key.to_public_key_der(),
+            #[cfg(feature = "secp256k1")]
+            VerifyingInner::Secp256k1(key) => key.to_public_key_der(),
+        }
+        .expect("public keys always encode")
+        .into_vec()
+    }
+
+    /// Import DER-encoded SubjectPublicKeyInfo holding an Ed25519 (or
+    /// secp256k1) key
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let inner = VerifyingKey::from_public_key_der(der).map(VerifyingInner::Ed25519);
+        #[cfg(feature = "secp256k1")]
+        let inner = inner.or_else(|e| {
+            k256::ecdsa::VerifyingKey::from_public_key_der(der)
+                .map(VerifyingInner::Secp256k1)
+                .map_err(|_| e)
+        });
+        let inner = inner.map_err(|e| Error::Crypto(format!("Invalid public key DER: {}", e)))?;
         Ok(Self { inner })
     }
 }
 
-/// Wrapper around Ed25519 signature
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A 64-byte signature: `R || s` for Ed25519, compact `r || s` for
+/// secp256k1
+///
+/// The bytes alone do not say which; the verification key does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Signature {
     #[serde(serialize_with = "serialize_sig", deserialize_with = "deserialize_sig")]
-    inner: Ed25519Signature,
+    bytes: [u8; 64],
 }
 
-fn serialize_sig<S>(sig: &Ed25519Signature, serializer: S) -> std::result::Result<S::Ok, S::Error>
+fn serialize_sig<S>(bytes: &[u8; 64], serializer: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    serializer.serialize_str(&hex::encode(sig.to_bytes()))
+    serialize_hex(bytes, serializer)
 }
 
-fn deserialize_sig<'de, D>(deserializer: D) -> std::result::Result<Ed25519Signature, D::Error>
+fn deserialize_sig<'de, D>(deserializer: D) -> std::result::Result<[u8; 64], D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    let bytes = hex::decode(&s).map_err(serde::de::Error::custom)?;
-    let bytes: [u8; 64] = bytes
+    deserialize_hex(deserializer)?
         .try_into()
-        .map_err(|_| serde::de::Error::custom("Invalid signature length"))?;
-    Ok(Ed25519Signature::from_bytes(&bytes))
+        .map_err(|_| serde::de::Error::custom("Invalid signature length"))
 }
 
 impl Signature {
+    fn of<A: SignatureScheme>(signature: &A::Signature) -> Self {
+        Self {
+            bytes: A::to_bytes(signature)
+                .try_into()
+                .expect("supported algorithms have 64-byte signatures"),
+        }
+    }
+
     /// Export as bytes
     pub fn to_bytes(&self) -> [u8; 64] {
-        self.inner.to_bytes()
+        self.bytes
     }
 
     /// Import from bytes
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        let inner = Ed25519Signature::from_bytes(
-            bytes
-                .try_into()
-                .map_err(|_| Error::Crypto("Invalid signature length".to_string()))?,
-        );
-        Ok(Self { inner })
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| Error::Crypto("Invalid signature length".to_string()))?;
+        Ok(Self { bytes })
+    }
+
+    /// Verify this signature over `data` with `key`
+    ///
+    /// Same as [`VerificationKey::verify`], with the signature first.
+    pub fn verify(&self, key: &VerificationKey, data: &[u8]) -> Result<()> {
+        key.verify(data, self)
+    }
+}
+
+/// Set of trusted verification keys, indexed by key id
+#[derive(Clone, Default)]
+pub struct KeyRing {
+    keys: HashMap<String, VerificationKey>,
+    /// Human-readable names by key id, for error messages and export
+    names: HashMap<String, String>,
+}
+
+impl KeyRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key, returning its key id
+    pub fn add(&mut self, key: VerificationKey) -> String {
+        let key_id = key.key_id();
+        self.keys.insert(key_id.clone(), key);
+        key_id
+    }
+
+    /// Add a key under a human-readable name, returning its key id
+    pub fn add_named(&mut self, name: impl Into<String>, key: VerificationKey) -> String {
+        let key_id = self.add(key);
+        self.names.insert(key_id.clone(), name.into());
+        key_id
+    }
+
+    /// Load keys from a JSON object mapping names to hex verification keys
+    pub fn from_json(s: &str) -> Result<Self> {
+        let entries: BTreeMap<String, String> = serde_json::from_str(s)?;
+        let mut ring = Self::new();
+        for (name, key_hex) in entries {
+            let bytes = hex::decode(key_hex.trim())
+                .map_err(|e| Error::Crypto(format!("Invalid hex for key '{}': {}", name, e)))?;
+            let key = VerificationKey::from_bytes(&bytes)
+                .map_err(|_| Error::Crypto(format!("Invalid verification key '{}'", name)))?;
+            ring.add_named(name, key);
+        }
+        Ok(ring)
+    }
+
+    /// Export as a JSON object mapping names to hex verification keys
+    ///
+    /// Keys added without a name are exported under their key id.
+    pub fn to_json(&self) -> Result<String> {
+        let entries: BTreeMap<&str, String> = self
+            .iter()
+            .map(|(id, key)| (self.display_name(id), hex::encode(key.to_bytes())))
+            .collect();
+        Ok(serde_json::to_string_pretty(&entries)?)
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&VerificationKey> {
+        self.keys.get(key_id)
+    }
+
+    /// Name a key was added under, if any
+    pub fn name(&self, key_id: &str) -> Option<&str> {
+        self.names.get(key_id).map(String::as_str)
+    }
+
+    /// Verify a signature from a specific key in the ring
+    ///
+    /// Errors name the key (by its human-readable name when known).
+    pub fn verify(&self, key_id: &str, data: &[u8], signature: &Signature) -> Result<()> {
+        let key = self
+            .get(key_id)
+            .ok_or_else(|| Error::Crypto(format!("Unknown key '{}'", key_id)))?;
+        key.verify(data, signature).map_err(|_| {
+            Error::Crypto(format!(
+                "signature from '{}' failed",
+                self.display_name(key_id)
+            ))
+        })
+    }
+
+    fn display_name<'a>(&'a self, key_id: &'a str) -> &'a str {
+        self.name(key_id).unwrap_or(key_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterate over `(key_id, key)` pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VerificationKey)> {
+        self.keys.iter().map(|(id, key)| (id.as_str(), key))
+    }
+
+    /// Check that at least `threshold` distinct keys in the ring produced a
+    /// valid signature over `data`
+    ///
+    /// Signatures from unknown keys, invalid signatures and repeated key ids
+    /// do not count toward the threshold.
+    pub fn verify_threshold(
+        &self,
+        data: &[u8],
+        signatures: &[(String, Signature)],
+        threshold: usize,
+    ) -> Result<()> {
+        let mut signers = HashSet::new();
+
+        for (key_id, signature) in signatures {
+            if signers.contains(key_id.as_str()) {
+                continue;
+            }
+            let Some(key) = self.get(key_id) else {
+                continue;
+            };
+            if key.verify(data, signature).is_ok() {
+                signers.insert(key_id.as_str());
+            }
+        }
+
+        if signers.len() < threshold {
+            return Err(Error::Crypto(format!(
+                "multisig threshold not met: {} of {} valid signatures",
+                signers.len(),
+                threshold
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Serialize a JSON value in RFC 8785 (JCS) canonical form: object keys
+/// sorted by UTF-16 code units, no insignificant whitespace, and numbers
+/// written as ECMAScript's `Number.prototype.toString` would.
+///
+/// As in JCS, every number is treated as an IEEE 754 double, so integers
+/// beyond 2^53 lose precision; send those as strings.
+pub fn canonical_json(value: &serde_json::Value) -> Vec<u8> {
+    let mut out = String::new();
+    write_canonical_json(value, &mut out);
+    out.into_bytes()
+}
+
+fn write_canonical_json(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(n) => out.push_str(&es_number(n.as_f64().unwrap_or_default())),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Format a finite double as ECMAScript does (RFC 8785 section 3.2.2.3)
+fn es_number(f: f64) -> String {
+    if f == 0.0 {
+        return "0".to_string();
+    }
+    // `{:e}` gives the shortest round-trip digits, e.g. "-1.25e-7"
+    let formatted = format!("{:e}", f.abs());
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n > 0 { "+" } else { "-" };
+        let fraction = if k > 1 {
+            format!(".{}", &digits[1..])
+        } else {
+            String::new()
+        };
+        format!("{}{}e{}{}", &digits[..1], fraction, sign, (n - 1).abs())
+    };
+    if f < 0.0 {
+        format!("-{}", body)
+    } else {
+        body
     }
 }
 
@@ -157,6 +651,45 @@ pub fn hash_data(data: &[u8]) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+/// Hash function used for proof hashes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Tag stored alongside hashes so verification picks the same function
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+}
+
+impl std::str::FromStr for HashAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            other => Err(Error::Crypto(format!("Unknown hash algorithm: {}", other))),
+        }
+    }
+}
+
+/// Hash data with the chosen algorithm
+pub fn hash_data_with(algorithm: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    match algorithm {
+        HashAlgorithm::Sha256 => hash_data(data).to_vec(),
+        HashAlgorithm::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +705,82 @@ mod tests {
         assert!(verification_key.verify(data, &signature).is_ok());
     }
 
+    /// Sign and check through the trait alone, as code generic over the
+    /// algorithm would
+    fn algorithm_round_trip<A: SignatureScheme>(key: &A::SigningKey, verifier: &A::VerifyingKey) {
+        let signature = A::sign(key, b"canonical order bytes");
+        A::verify(verifier, b"canonical order bytes", &signature).unwrap();
+        assert!(A::verify(verifier, b"other bytes", &signature).is_err());
+        assert_eq!(A::to_bytes(&signature).len(), 64);
+    }
+
+    #[test]
+    fn test_ed25519_algorithm() {
+        let secret = [7u8; 32];
+        let raw = Ed25519SigningKey::from_bytes(&secret);
+        algorithm_round_trip::<Ed25519>(&raw, &raw.verifying_key());
+
+        // The key wrappers sign through the algorithm
+        let key = SigningKey::from_bytes(&secret).unwrap();
+        let signature = key.sign(b"data");
+        assert_eq!(
+            signature.to_bytes().to_vec(),
+            Ed25519::to_bytes(&Ed25519::sign(&raw, b"data"))
+        );
+        assert_eq!(key.algorithm(), Ed25519::NAME);
+        assert_eq!(key.verification_key().to_bytes().len(), 32);
+
+        let restored = SigningKey::from_bytes_with(key.algorithm(), &key.to_bytes()).unwrap();
+        assert_eq!(restored.key_id(), key.key_id());
+        assert!(SigningKey::from_bytes_with("rsa", &secret).is_err());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_secp256k1_algorithm() {
+        let raw = k256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng);
+        algorithm_round_trip::<Secp256k1>(&raw, raw.verifying_key());
+
+        let key = SigningKey::generate_secp256k1();
+        assert_eq!(key.algorithm(), "secp256k1");
+        let verification_key = key.verification_key();
+        let signature = key.sign(b"order");
+        verification_key.verify(b"order", &signature).unwrap();
+        assert!(verification_key.verify(b"other", &signature).is_err());
+
+        // An Ed25519 key does not accept the secp256k1 signature, nor the
+        // other way round
+        let ed = SigningKey::generate();
+        assert!(ed.verification_key().verify(b"order", &signature).is_err());
+        assert!(verification_key
+            .verify(b"order", &ed.sign(b"order"))
+            .is_err());
+
+        // Keys round-trip through bytes, JSON, PEM and DER as secp256k1
+        let bytes = verification_key.to_bytes();
+        assert_eq!(bytes.len(), 33);
+        let imported = VerificationKey::from_bytes(&bytes).unwrap();
+        assert_eq!(imported.algorithm(), "secp256k1");
+        imported.verify(b"order", &signature).unwrap();
+
+        let json = serde_json::to_string(&verification_key).unwrap();
+        let parsed: VerificationKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.key_id(), verification_key.key_id());
+
+        let pem = VerificationKey::from_pem(&verification_key.to_pem()).unwrap();
+        assert_eq!(pem.to_bytes(), bytes);
+        let der = VerificationKey::from_der(&verification_key.to_der()).unwrap();
+        assert_eq!(der.to_bytes(), bytes);
+
+        let restored = SigningKey::from_bytes_with(key.algorithm(), &key.to_bytes()).unwrap();
+        assert_eq!(restored.algorithm(), "secp256k1");
+        assert_eq!(restored.key_id(), key.key_id());
+        assert_eq!(restored.sign(b"order"), signature);
+        // Read as Ed25519, the same secret is a different key
+        let ed = SigningKey::from_bytes(&key.to_bytes()).unwrap();
+        assert_ne!(ed.key_id(), key.key_id());
+    }
+
     #[test]
     fn test_verify_fails_with_wrong_data() {
         let key = SigningKey::generate();
@@ -184,6 +793,23 @@ mod tests {
         assert!(verification_key.verify(wrong_data, &signature).is_err());
     }
 
+    #[test]
+    fn test_signature_verify_matches_key_verify() {
+        let key = SigningKey::generate();
+        let other = SigningKey::generate().verification_key();
+        let verification_key = key.verification_key();
+        let signature = key.sign(b"blob");
+
+        for (vk, data, ok) in [
+            (&verification_key, &b"blob"[..], true),
+            (&verification_key, &b"other blob"[..], false),
+            (&other, &b"blob"[..], false),
+        ] {
+            assert_eq!(signature.verify(vk, data).is_ok(), ok);
+            assert_eq!(vk.verify(data, &signature).is_ok(), ok);
+        }
+    }
+
     #[test]
     fn test_hash_consistency() {
         let data = b"test data";
@@ -192,4 +818,160 @@ mod tests {
 
         assert_eq!(hash1, hash2);
     }
+
+    #[test]
+    fn test_hash_known_answers() {
+        assert_eq!(
+            hex::encode(hash_data_with(HashAlgorithm::Sha256, b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex::encode(hash_data_with(HashAlgorithm::Blake3, b"abc")),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        assert_eq!(
+            hash_data_with(HashAlgorithm::default(), b"abc"),
+            hash_data(b"abc").to_vec()
+        );
+
+        for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+            assert_eq!(
+                algorithm.as_str().parse::<HashAlgorithm>().unwrap(),
+                algorithm
+            );
+        }
+        assert!("md5".parse::<HashAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let value = serde_json::json!({
+            "b": [1.0, 2.5, "x"],
+            "a": {"z": null, "y": true},
+            "c": 50000.0,
+        });
+        assert_eq!(
+            canonical_json(&value),
+            br#"{"a":{"y":true,"z":null},"b":[1,2.5,"x"],"c":50000}"#.to_vec()
+        );
+
+        // Number cases from RFC 8785 appendix B
+        for (number, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (-9007199254740992.0, "-9007199254740992"),
+            (295147905179352830000.0, "295147905179352830000"),
+            (1e21, "1e+21"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.25e-7, "-1.25e-7"),
+            (333333333.3333333, "333333333.3333333"),
+            (1e23, "1e+23"),
+        ] {
+            assert_eq!(es_number(number), expected);
+        }
+        assert_eq!(
+            canonical_json(&serde_json::json!(u64::MAX)),
+            b"18446744073709552000".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_keyring_json_roundtrip() {
+        let risk = SigningKey::generate();
+        let strategy = SigningKey::generate();
+        let json = serde_json::json!({
+            "risk-service": hex::encode(risk.verification_key().to_bytes()),
+            "strategy": hex::encode(strategy.verification_key().to_bytes()),
+        })
+        .to_string();
+
+        let ring = KeyRing::from_json(&json).unwrap();
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.name(&risk.key_id()), Some("risk-service"));
+
+        let data = b"decision";
+        ring.verify(&risk.key_id(), data, &risk.sign(data)).unwrap();
+        let err = ring
+            .verify(&risk.key_id(), data, &strategy.sign(data))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("signature from 'risk-service' failed"));
+
+        let exported = KeyRing::from_json(&ring.to_json().unwrap()).unwrap();
+        assert_eq!(exported.name(&strategy.key_id()), Some("strategy"));
+    }
+
+    #[test]
+    fn test_keyring_json_names_bad_keys() {
+        let err = KeyRing::from_json(r#"{"risk-service": "zz"}"#)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("'risk-service'"));
+
+        let err = KeyRing::from_json(r#"{"short": "abcd"}"#).err().unwrap();
+        assert!(err.to_string().contains("'short'"));
+    }
+
+    #[test]
+    fn test_threshold_ignores_duplicate_signers() {
+        let keys: Vec<SigningKey> = (0..3).map(|_| SigningKey::generate()).collect();
+        let mut keyring = KeyRing::new();
+        for key in &keys {
+            keyring.add(key.verification_key());
+        }
+
+        let data = b"approve";
+        let first = (keys[0].key_id(), keys[0].sign(data));
+        let second = (keys[1].key_id(), keys[1].sign(data));
+
+        // The same signer twice is still one approval
+        let duplicated = vec![first.clone(), first.clone()];
+        assert!(keyring.verify_threshold(data, &duplicated, 2).is_err());
+
+        let distinct = vec![first, second];
+        assert!(keyring.verify_threshold(data, &distinct, 2).is_ok());
+        assert!(keyring.verify_threshold(b"tampered", &distinct, 1).is_err());
+    }
+
+    // Generated with `openssl genpkey -algorithm ed25519`, then
+    // `openssl pkey -pubout` and `openssl pkeyutl -sign -rawin`
+    const OPENSSL_PUBLIC_KEY_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAMw/r0Fv70nd4iKTVE4XI4hhDulgcVFUFF6cp+1BEOXY=
+-----END PUBLIC KEY-----
+";
+    const OPENSSL_PUBLIC_KEY_DER: &str =
+        "302a300506032b6570032100330febd05bfbd2777888a4d51385c8e21843ba581c54550517a729fb50443976";
+    const OPENSSL_MESSAGE: &[u8] = b"tinywindow fixture";
+    const OPENSSL_SIGNATURE: &str = "13098fb70183db13af018219afcebc33ae8d27fed984e4b3836399c7ee474c04\
+                                     2bbbdd4530e30f25a01d950b7310860cfeb1352317e8667045708be2baef260d";
+
+    #[test]
+    fn test_public_key_pem_and_der_match_openssl() {
+        let key = VerificationKey::from_pem(OPENSSL_PUBLIC_KEY_PEM).unwrap();
+        let signature = Signature::from_bytes(&hex::decode(OPENSSL_SIGNATURE).unwrap()).unwrap();
+        key.verify(OPENSSL_MESSAGE, &signature).unwrap();
+
+        assert_eq!(key.to_pem(), OPENSSL_PUBLIC_KEY_PEM);
+        let der = hex::decode(OPENSSL_PUBLIC_KEY_DER).unwrap();
+        assert_eq!(key.to_der(), der);
+        assert_eq!(
+            VerificationKey::from_der(&der).unwrap().to_bytes(),
+            key.to_bytes()
+        );
+
+        let generated = SigningKey::generate().verification_key();
+        let imported = VerificationKey::from_pem(&generated.to_pem()).unwrap();
+        assert_eq!(imported.to_bytes(), generated.to_bytes());
+
+        assert!(VerificationKey::from_pem(
+            "-----BEGIN PUBLIC KEY-----\n-----END PUBLIC KEY-----\n"
+        )
+        .is_err());
+        assert!(VerificationKey::from_der(&der[..20]).is_err());
+    }
 }
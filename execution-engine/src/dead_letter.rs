@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{
+    execution::{Order, OrderResult},
+    jsonl, Result,
+};
+
+/// An executed order whose store write failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub order: Order,
+    pub result: OrderResult,
+}
+
+/// Fallback destination for orders the [`Store`](crate::Store) could not persist
+#[async_trait]
+pub trait DeadLetterSink: Send + Sync {
+    async fn write(&self, order: &Order, result: &OrderResult) -> Result<()>;
+}
+
+/// Appends dead letters to a local file, one JSON object per line
+#[derive(Debug)]
+pub struct FileDeadLetterSink {
+    path: PathBuf,
+    // Serializes appends so concurrent writes never interleave within a line
+    lock: Mutex<()>,
+}
+
+impl FileDeadLetterSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl DeadLetterSink for FileDeadLetterSink {
+    async fn write(&self, order: &Order, result: &OrderResult) -> Result<()> {
+        let letter = DeadLetter {
+            order: order.clone(),
+            result: result.clone(),
+        };
+        let path = self.path.clone();
+
+        let _guard = self.lock.lock().await;
+        // The store write already failed; the append syncs so this copy
+        // survives a crash
+        jsonl::blocking(move || jsonl::append(&path, &letter)).await
+    }
+}
+
+/// Read every dead letter from a file written by [`FileDeadLetterSink`]
+///
+/// Blank lines are skipped; a line that does not parse is an error, so a
+/// corrupt file is noticed rather than partially replayed.
+pub async fn read_dead_letters(path: impl AsRef<Path>) -> Result<Vec<DeadLetter>> {
+    let path = path.as_ref().to_path_buf();
+    jsonl::blocking(move || jsonl::read(&path)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderSide, OrderStatus, OrderType};
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_file_sink_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("dead-letters-{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = FileDeadLetterSink::new(&path);
+
+        let mut written = Vec::new();
+        for quantity in [0.1, 0.2] {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                quantity,
+            );
+            let result = OrderResult {
+                order_id: order.id,
+                status: OrderStatus::Executed,
+                execution_price: Some(50000.0),
+                executed_quantity: Some(quantity),
+                timestamp: Utc::now(),
+                message: None,
+                timings: None,
+                book_snapshot: None,
+                exchange_order_id: None,
+                fee: None,
+            };
+            sink.write(&order, &result).await.unwrap();
+            written.push(order);
+        }
+
+        let letters = read_dead_letters(sink.path()).await.unwrap();
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].order, written[0]);
+        assert_eq!(letters[1].result.executed_quantity, Some(0.2));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+        assert!(read_dead_letters(&path).await.is_err());
+    }
+}
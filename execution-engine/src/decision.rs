@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    crypto::{self, HashAlgorithm, Signature, SigningKey, VerificationKey},
+    Error, Result,
+};
+
+/// The reasoning behind an order, signed and bound to that order's id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub decision_id: Uuid,
+    pub order_id: Uuid,
+    pub decision_data: serde_json::Value,
+    pub signature: Option<Signature>,
+}
+
+impl Decision {
+    pub fn new(order_id: Uuid, decision_data: serde_json::Value) -> Self {
+        Self {
+            decision_id: Uuid::new_v4(),
+            order_id,
+            decision_data,
+            signature: None,
+        }
+    }
+
+    /// RFC 8785 canonical JSON of the decision id, order id and data
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical_bytes(self.decision_id, self.order_id, &self.decision_data)
+    }
+
+    /// SHA-256 of the canonical encoding
+    pub fn proof_hash(&self) -> [u8; 32] {
+        crypto::hash_data(&self.canonical_bytes())
+    }
+
+    /// Hash of the canonical encoding with the chosen algorithm
+    pub fn proof_hash_with(&self, algorithm: HashAlgorithm) -> Vec<u8> {
+        crypto::hash_data_with(algorithm, &self.canonical_bytes())
+    }
+
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.signature = Some(key.sign(&self.canonical_bytes()));
+    }
+
+    pub fn verify(&self, key: &VerificationKey) -> Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("Decision is not signed".to_string()))?;
+        key.verify(&self.canonical_bytes(), signature)
+    }
+}
+
+/// Canonical encoding shared with stored decision records
+pub(crate) fn canonical_bytes(
+    decision_id: Uuid,
+    order_id: Uuid,
+    decision_data: &serde_json::Value,
+) -> Vec<u8> {
+    crypto::canonical_json(&serde_json::json!({
+        "decision_id": decision_id.to_string(),
+        "order_id": order_id.to_string(),
+        "decision_data": decision_data,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decision_signature_covers_order_id() {
+        let key = SigningKey::generate();
+        let mut decision = Decision::new(Uuid::new_v4(), serde_json::json!({"action": "buy"}));
+        assert!(decision.verify(&key.verification_key()).is_err());
+
+        decision.sign(&key);
+        decision.verify(&key.verification_key()).unwrap();
+        assert_eq!(
+            decision.proof_hash().to_vec(),
+            decision.proof_hash_with(HashAlgorithm::Sha256)
+        );
+
+        // Re-pointing a signed decision at another order breaks the signature
+        let original_hash = decision.proof_hash();
+        decision.order_id = Uuid::new_v4();
+        assert!(decision.verify(&key.verification_key()).is_err());
+        assert_ne!(decision.proof_hash(), original_hash);
+    }
+}
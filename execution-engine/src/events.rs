@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Order lifecycle event published by the engine
+///
+/// Every event carries the order's
+/// [`correlation_id`](crate::Order::correlation_id), if it has one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OrderEvent {
+    Submitted {
+        id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+    Filled {
+        id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+    PartiallyFilled {
+        id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+    Cancelled {
+        id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+    Failed {
+        id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+    /// Refused before submission; `reason` is the error's
+    /// [`category`](crate::Error::category), such as `risk_limit`
+    Rejected {
+        id: Uuid,
+        reason: String,
+        timestamp: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+    },
+}
+
+impl OrderEvent {
+    pub fn order_id(&self) -> Uuid {
+        match self {
+            OrderEvent::Submitted { id, .. }
+            | OrderEvent::Filled { id, .. }
+            | OrderEvent::PartiallyFilled { id, .. }
+            | OrderEvent::Cancelled { id, .. }
+            | OrderEvent::Failed { id, .. }
+            | OrderEvent::Rejected { id, .. } => *id,
+        }
+    }
+
+    pub fn correlation_id(&self) -> Option<&str> {
+        match self {
+            OrderEvent::Submitted { correlation_id, .. }
+            | OrderEvent::Filled { correlation_id, .. }
+            | OrderEvent::PartiallyFilled { correlation_id, .. }
+            | OrderEvent::Cancelled { correlation_id, .. }
+            | OrderEvent::Failed { correlation_id, .. }
+            | OrderEvent::Rejected { correlation_id, .. } => correlation_id.as_deref(),
+        }
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            OrderEvent::Submitted { timestamp, .. }
+            | OrderEvent::Filled { timestamp, .. }
+            | OrderEvent::PartiallyFilled { timestamp, .. }
+            | OrderEvent::Cancelled { timestamp, .. }
+            | OrderEvent::Failed { timestamp, .. }
+            | OrderEvent::Rejected { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+/// Fan-out of [`OrderEvent`]s to any number of subscribers
+///
+/// Publishing never blocks: a subscriber that falls more than `capacity`
+/// events behind receives `RecvError::Lagged` and skips ahead.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<OrderEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, event: OrderEvent) {
+        // An error only means nobody is listening
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast::error::RecvError;
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_does_not_block() {
+        let bus = EventBus::new(2);
+        let mut receiver = bus.subscribe();
+
+        for _ in 0..5 {
+            bus.publish(OrderEvent::Submitted {
+                id: Uuid::new_v4(),
+                timestamp: Utc::now(),
+                correlation_id: None,
+            });
+        }
+
+        assert!(matches!(receiver.recv().await, Err(RecvError::Lagged(3))));
+        assert!(receiver.recv().await.is_ok());
+    }
+}
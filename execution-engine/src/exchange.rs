@@ -0,0 +1,602 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use uuid::Uuid;
+
+use crate::{
+    book::OrderBook,
+    clock::{Clock, SystemClock},
+    execution::{Order, OrderQuantity, OrderResult, OrderStatus},
+    pricing::PriceSource,
+    ExecutionError, Result,
+};
+
+/// A venue the engine can route signed orders to
+#[async_trait]
+pub trait ExchangeAdapter: Send + Sync {
+    /// Submit a signed order and return the venue's view of the result
+    async fn submit(&self, order: &Order) -> Result<OrderResult>;
+
+    /// Fetch the venue's current view of a previously submitted order
+    async fn query_status(&self, id: Uuid) -> Result<OrderResult>;
+
+    /// Cancel a resting order
+    async fn cancel(&self, id: Uuid) -> Result<OrderResult>;
+}
+
+/// How the simulator perturbs market orders that fill at the reference price
+///
+/// The randomness comes from the exchange's RNG, so runs are reproducible
+/// with [`SimulatedExchange::with_seed`]. Orders filled against an
+/// [`OrderBook`] and limit orders are not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FillModel {
+    /// Fill the whole quantity at exactly the reference price
+    #[default]
+    Exact,
+    /// Fill in full at the reference price moved up or down by up to
+    /// `max_bps` basis points, drawn uniformly
+    RandomSlippage { max_bps: f64 },
+    /// Fill in full with probability `fill_prob`; otherwise fill a uniformly
+    /// drawn fraction of the quantity and report `PartiallyFilled`
+    PartialFill { fill_prob: f64 },
+}
+
+/// In-process exchange that fills every order immediately
+///
+/// Market orders fill at the price source's price (or a fixed reference price
+/// when none is set) and limit orders fill at their limit price. A market
+/// order on a symbol with an [`OrderBook`] instead sweeps the book, possibly
+/// filling only partially, and its result carries the book. Used for
+/// paper trading and as the default adapter until a real venue integration
+/// is configured.
+///
+/// Submissions return immediately unless a latency is set with
+/// [`SimulatedExchange::with_latency`], and fills are exact unless a
+/// [`FillModel`] is set with [`SimulatedExchange::with_fill_model`].
+#[derive(Clone)]
+pub struct SimulatedExchange {
+    market_price: f64,
+    price_source: Option<Arc<dyn PriceSource>>,
+    results: Arc<Mutex<HashMap<Uuid, OrderResult>>>,
+    latency: Duration,
+    jitter: Duration,
+    rng: Arc<Mutex<StdRng>>,
+    books: Arc<Mutex<HashMap<String, OrderBook>>>,
+    fill_model: FillModel,
+    clock: Arc<dyn Clock>,
+}
+
+impl fmt::Debug for SimulatedExchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulatedExchange")
+            .field("market_price", &self.market_price)
+            .field("price_source", &self.price_source.is_some())
+            .field("latency", &self.latency)
+            .field("jitter", &self.jitter)
+            .field("fill_model", &self.fill_model)
+            .finish()
+    }
+}
+
+impl SimulatedExchange {
+    pub fn new() -> Self {
+        Self {
+            market_price: 50000.0, // Placeholder price
+            price_source: None,
+            results: Arc::new(Mutex::new(HashMap::new())),
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+            books: Arc::new(Mutex::new(HashMap::new())),
+            fill_model: FillModel::Exact,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// A copy with the same configuration and books that shares no state
+    /// with this one: it starts with no results, and its books and random
+    /// state are its own
+    pub(crate) fn detached(&self) -> Self {
+        Self {
+            results: Arc::new(Mutex::new(HashMap::new())),
+            rng: Arc::new(Mutex::new(self.rng.lock().unwrap().clone())),
+            books: Arc::new(Mutex::new(self.books.lock().unwrap().clone())),
+            ..self.clone()
+        }
+    }
+
+    /// Timestamp results from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Fill market orders on the book's symbol against `book`
+    pub fn with_order_book(self, book: OrderBook) -> Self {
+        self.set_order_book(book);
+        self
+    }
+
+    /// Replace the book for its symbol; clones of this exchange see it too
+    pub fn set_order_book(&self, book: OrderBook) {
+        self.books.lock().unwrap().insert(book.symbol.clone(), book);
+    }
+
+    /// Delay each submission by `mean` plus or minus up to `jitter`, drawn
+    /// uniformly, to model network and venue latency
+    ///
+    /// Delays never go below zero. A zero `mean` and `jitter` (the default)
+    /// submit without sleeping.
+    pub fn with_latency(mut self, mean: Duration, jitter: Duration) -> Self {
+        self.latency = mean;
+        self.jitter = jitter;
+        self
+    }
+
+    /// Perturb market order fills with `model`
+    pub fn with_fill_model(mut self, model: FillModel) -> Self {
+        self.fill_model = model;
+        self
+    }
+
+    /// Seed the latency jitter and fill model so a run is reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+        self
+    }
+
+    /// Set the price market orders fill at
+    pub fn with_market_price(mut self, price: f64) -> Self {
+        self.market_price = price;
+        self
+    }
+
+    /// Fill market orders at prices from `source`
+    pub fn with_price_source(mut self, source: Arc<dyn PriceSource>) -> Self {
+        self.price_source = Some(source);
+        self
+    }
+
+    /// Next simulated submission delay
+    fn sample_latency(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.latency;
+        }
+        let offset = self.rng.lock().unwrap().gen_range(-1.0..=1.0) * self.jitter.as_secs_f64();
+        Duration::from_secs_f64((self.latency.as_secs_f64() + offset).max(0.0))
+    }
+
+    /// Price and quantity for a market order of `quantity` at reference
+    /// `price`, after the fill model
+    fn sample_fill(&self, price: f64, quantity: f64) -> (f64, f64) {
+        match self.fill_model {
+            FillModel::Exact => (price, quantity),
+            FillModel::RandomSlippage { max_bps } => {
+                if !(max_bps.is_finite() && max_bps > 0.0) {
+                    return (price, quantity);
+                }
+                let bps = self.rng.lock().unwrap().gen_range(-max_bps..=max_bps);
+                (price * (1.0 + bps / 10_000.0), quantity)
+            }
+            FillModel::PartialFill { fill_prob } => {
+                let mut rng = self.rng.lock().unwrap();
+                if rng.gen::<f64>() < fill_prob {
+                    return (price, quantity);
+                }
+                // In (0, 1], so something always fills
+                let fraction = 1.0 - rng.gen::<f64>();
+                (price, quantity * fraction)
+            }
+        }
+    }
+
+    /// Price a market order on `symbol` would currently fill at
+    pub async fn market_price(&self, symbol: &str) -> Result<f64> {
+        match &self.price_source {
+            Some(source) => source.price(symbol).await,
+            None => Ok(self.market_price),
+        }
+    }
+}
+
+/// Sweep `book` for the order's amount, returning the fill price, the base
+/// quantity filled and whether the whole amount filled
+fn sweep(book: &OrderBook, order: &Order) -> Option<(f64, f64, bool)> {
+    match order.order_quantity() {
+        OrderQuantity::Base(quantity) => book
+            .sweep(order.side, quantity)
+            .map(|(price, filled)| (price, filled, filled >= quantity)),
+        OrderQuantity::Quote(amount) => book
+            .sweep_notional(order.side, amount)
+            .map(|(price, filled, unspent)| (price, filled, unspent <= 0.0)),
+    }
+}
+
+impl Default for SimulatedExchange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ExchangeAdapter for SimulatedExchange {
+    async fn submit(&self, order: &Order) -> Result<OrderResult> {
+        let latency = self.sample_latency();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        let book = match order.order_type.limit_price() {
+            Some(_) => None,
+            None => self.books.lock().unwrap().get(&order.symbol).cloned(),
+        };
+
+        let result = match book {
+            Some(book) => match sweep(&book, order) {
+                Some((price, filled, complete)) => OrderResult {
+                    order_id: order.id,
+                    status: if complete {
+                        OrderStatus::Executed
+                    } else {
+                        OrderStatus::PartiallyFilled
+                    },
+                    execution_price: Some(price),
+                    executed_quantity: Some(filled),
+                    timestamp: self.clock.now(),
+                    message: Some("Order filled against book".to_string()),
+                    timings: None,
+                    book_snapshot: Some(book),
+                    exchange_order_id: None,
+                    fee: None,
+                },
+                None => OrderResult {
+                    order_id: order.id,
+                    status: OrderStatus::Failed,
+                    execution_price: None,
+                    executed_quantity: None,
+                    timestamp: self.clock.now(),
+                    message: Some("No liquidity in book".to_string()),
+                    timings: None,
+                    book_snapshot: Some(book),
+                    exchange_order_id: None,
+                    fee: None,
+                },
+            },
+            None => {
+                let (price, filled) = match order.order_type.limit_price() {
+                    Some(price) => (price, order.quantity),
+                    None => {
+                        let price = self.market_price(&order.symbol).await?;
+                        self.sample_fill(price, order.quantity)
+                    }
+                };
+                let (status, message) = if filled < order.quantity {
+                    (OrderStatus::PartiallyFilled, "Order partially filled")
+                } else {
+                    (OrderStatus::Executed, "Order executed successfully")
+                };
+                // `filled` is in the order's unit; convert a quote amount
+                // to base at the fill price
+                let filled = match order.order_quantity() {
+                    OrderQuantity::Base(_) => filled,
+                    OrderQuantity::Quote(_) => filled / price,
+                };
+                OrderResult {
+                    order_id: order.id,
+                    status,
+                    execution_price: Some(price),
+                    executed_quantity: Some(filled),
+                    timestamp: self.clock.now(),
+                    message: Some(message.to_string()),
+                    timings: None,
+                    book_snapshot: None,
+                    exchange_order_id: None,
+                    fee: None,
+                }
+            }
+        };
+
+        self.results
+            .lock()
+            .unwrap()
+            .insert(order.id, result.clone());
+        Ok(result)
+    }
+
+    async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+        self.results
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| ExecutionError::UnknownOrder(id).into())
+    }
+
+    async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+        let mut results = self.results.lock().unwrap();
+        let result = results
+            .get_mut(&id)
+            .ok_or(ExecutionError::UnknownOrder(id))?;
+
+        match result.status {
+            OrderStatus::Pending | OrderStatus::PartiallyFilled => {
+                result.status = OrderStatus::Cancelled;
+                result.timestamp = self.clock.now();
+                result.message = Some("Order cancelled".to_string());
+                Ok(result.clone())
+            }
+            _ => Err(ExecutionError::VenueRejected {
+                code: None,
+                message: format!("order {} is not open", id),
+            }
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderSide, OrderType};
+    use crate::pricing::StaticPriceSource;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_simulated_fill_prices() {
+        let exchange = SimulatedExchange::new().with_market_price(42000.0);
+
+        let market = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.5,
+        );
+        let result = exchange.submit(&market).await.unwrap();
+        assert_eq!(result.execution_price, Some(42000.0));
+        assert_eq!(result.executed_quantity, Some(0.5));
+
+        let limit = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 43000.0 },
+            0.5,
+        );
+        let result = exchange.submit(&limit).await.unwrap();
+        assert_eq!(result.execution_price, Some(43000.0));
+
+        let status = exchange.query_status(limit.id).await.unwrap();
+        assert_eq!(status.status, OrderStatus::Executed);
+        assert_eq!(status.execution_price, Some(43000.0));
+        assert!(exchange.query_status(Uuid::new_v4()).await.is_err());
+
+        // Simulated orders fill immediately, so there is nothing to cancel
+        assert!(exchange.cancel(limit.id).await.is_err());
+    }
+
+    #[test]
+    fn test_latency_is_reproducible_with_seed() {
+        let mean = Duration::from_millis(20);
+        let jitter = Duration::from_millis(5);
+        let samples = |seed| {
+            let exchange = SimulatedExchange::new()
+                .with_latency(mean, jitter)
+                .with_seed(seed);
+            (0..50)
+                .map(|_| exchange.sample_latency())
+                .collect::<Vec<_>>()
+        };
+
+        let first = samples(7);
+        assert_eq!(first, samples(7));
+        assert_ne!(first, samples(8));
+        assert!(first
+            .iter()
+            .all(|d| *d >= mean - jitter && *d <= mean + jitter));
+
+        // Jitter larger than the mean is clamped at zero
+        let clamped = SimulatedExchange::new()
+            .with_latency(Duration::from_millis(1), Duration::from_millis(10))
+            .with_seed(1);
+        assert!((0..50).all(|_| clamped.sample_latency() <= Duration::from_millis(11)));
+        assert_eq!(SimulatedExchange::new().sample_latency(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_fill_model_is_reproducible_with_seed() {
+        let fills = |model, seed| async move {
+            let exchange = SimulatedExchange::new()
+                .with_market_price(100.0)
+                .with_fill_model(model)
+                .with_seed(seed);
+            let mut fills = Vec::new();
+            for _ in 0..50 {
+                let order = Order::new(
+                    "BTC/USD".to_string(),
+                    OrderSide::Buy,
+                    OrderType::Market,
+                    2.0,
+                );
+                let result = exchange.submit(&order).await.unwrap();
+                fills.push((
+                    result.status,
+                    result.execution_price.unwrap(),
+                    result.executed_quantity.unwrap(),
+                ));
+            }
+            fills
+        };
+
+        let slippage = FillModel::RandomSlippage { max_bps: 10.0 };
+        let first = fills(slippage, 7).await;
+        assert_eq!(first, fills(slippage, 7).await);
+        assert_ne!(first, fills(slippage, 8).await);
+        assert!(first.iter().all(|(status, price, quantity)| {
+            *status == OrderStatus::Executed && (99.9..=100.1).contains(price) && *quantity == 2.0
+        }));
+
+        let partial = FillModel::PartialFill { fill_prob: 0.5 };
+        let first = fills(partial, 7).await;
+        assert_eq!(first, fills(partial, 7).await);
+        let partials = first
+            .iter()
+            .filter(|(status, _, _)| *status == OrderStatus::PartiallyFilled)
+            .count();
+        assert!(partials > 0 && partials < first.len());
+        assert!(first.iter().all(|(status, price, quantity)| {
+            *price == 100.0
+                && *quantity > 0.0
+                && (*quantity < 2.0) == (*status == OrderStatus::PartiallyFilled)
+        }));
+
+        // Limit orders ignore the model
+        let exchange = SimulatedExchange::new()
+            .with_fill_model(FillModel::PartialFill { fill_prob: 0.0 })
+            .with_seed(1);
+        let limit = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 90.0 },
+            2.0,
+        );
+        let result = exchange.submit(&limit).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.executed_quantity, Some(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_submit_waits_for_latency() {
+        let exchange =
+            SimulatedExchange::new().with_latency(Duration::from_millis(30), Duration::ZERO);
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.5,
+        );
+
+        let started = std::time::Instant::now();
+        exchange.submit(&order).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn test_market_orders_sweep_order_book() {
+        let book = OrderBook::new(
+            "BTC/USD",
+            vec![(41900.0, 1.0)],
+            vec![(42000.0, 0.5), (42100.0, 0.5)],
+            Utc::now(),
+        );
+        let exchange = SimulatedExchange::new().with_order_book(book.clone());
+
+        let order =
+            |side, quantity| Order::new("BTC/USD".to_string(), side, OrderType::Market, quantity);
+        let result = exchange.submit(&order(OrderSide::Buy, 1.0)).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.execution_price, Some(42050.0));
+        assert_eq!(result.book_snapshot.as_ref(), Some(&book));
+
+        let result = exchange.submit(&order(OrderSide::Sell, 2.0)).await.unwrap();
+        assert_eq!(result.status, OrderStatus::PartiallyFilled);
+        assert_eq!(result.executed_quantity, Some(1.0));
+
+        // Limit orders and other symbols ignore the book
+        let limit = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 41000.0 },
+            1.0,
+        );
+        assert!(exchange
+            .submit(&limit)
+            .await
+            .unwrap()
+            .book_snapshot
+            .is_none());
+        let other = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        assert!(exchange
+            .submit(&other)
+            .await
+            .unwrap()
+            .book_snapshot
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quote_quantity_fills_in_base() {
+        let exchange = SimulatedExchange::new().with_market_price(40000.0);
+        let quote = |order_type, amount| {
+            let mut order = Order::new("BTC/USD".to_string(), OrderSide::Buy, order_type, 0.0);
+            order.set_order_quantity(OrderQuantity::Quote(amount));
+            order
+        };
+
+        // $100 at 40,000 buys 0.0025 BTC, and at a 50,000 limit 0.002
+        let result = exchange
+            .submit(&quote(OrderType::Market, 100.0))
+            .await
+            .unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.executed_quantity, Some(0.0025));
+        let result = exchange
+            .submit(&quote(OrderType::Limit { price: 50000.0 }, 100.0))
+            .await
+            .unwrap();
+        assert_eq!(result.executed_quantity, Some(0.002));
+
+        // Against a book, the amount is spent level by level
+        let book = OrderBook::new(
+            "BTC/USD",
+            vec![],
+            vec![(40000.0, 0.001), (50000.0, 1.0)],
+            Utc::now(),
+        );
+        let exchange = exchange.with_order_book(book);
+        let result = exchange
+            .submit(&quote(OrderType::Market, 90.0))
+            .await
+            .unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.executed_quantity, Some(0.002));
+        assert_eq!(result.execution_price, Some(45000.0));
+        let result = exchange
+            .submit(&quote(OrderType::Market, 1_000_000.0))
+            .await
+            .unwrap();
+        assert_eq!(result.status, OrderStatus::PartiallyFilled);
+        assert_eq!(result.executed_quantity, Some(1.001));
+    }
+
+    #[tokio::test]
+    async fn test_market_orders_use_price_source() {
+        let prices = StaticPriceSource::new().with_price("ETH/USD", 3000.0);
+        let exchange = SimulatedExchange::new().with_price_source(Arc::new(prices));
+
+        let order = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let result = exchange.submit(&order).await.unwrap();
+        assert_eq!(result.execution_price, Some(3000.0));
+
+        let unpriced = Order::new(
+            "SOL/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        assert!(exchange.submit(&unpriced).await.is_err());
+    }
+}
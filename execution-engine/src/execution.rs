@@ -1,199 +1,6837 @@
 use chrono::{DateTime, Utc};
+use futures_util::future;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Notify};
+use tracing::Instrument;
+
 use crate::{
-    crypto::{Signature, SigningKey},
-    Error, Result,
+    alert::{AlertQueue, AlertSink, Level, NoopAlertSink, ALERT_QUEUE_CAPACITY},
+    audit::{AuditEvent, AuditSink},
+    book::OrderBook,
+    clock::{Clock, SystemClock},
+    crypto::{self, KeyRing, Signature, SigningKey, VerificationKey},
+    dead_letter::DeadLetterSink,
+    decision::Decision,
+    events::{EventBus, OrderEvent},
+    exchange::{ExchangeAdapter, SimulatedExchange},
+    fees::FeeModel,
+    instrument::{round_to_lot, round_to_tick, RoundingMode, Symbol, SymbolRules},
+    lru::LruMap,
+    metrics::{EngineMetrics, EngineMetricsSnapshot},
+    order_id::{OrderIdGenerator, UuidGenerator},
+    position::Position,
+    pricing::PriceSource,
+    rate_limit::RateLimiter,
+    risk::{PriceBand, RiskLimits, RiskManager},
+    signals::{SignalManager, TradingSignal},
+    storage::{OrderRecord, Store},
+    validation::{
+        default_rules, NonEmptySymbol, PositiveLimitPrice, PositiveQuantity, ValidationRule,
+    },
+    verifier::Verifier,
+    Error, ExecutionError, Result,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How often [`ExecutionEngine::shutdown`] checks for in-flight orders
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Orders kept in memory by default; see
+/// [`ExecutionEngine::with_order_cache_capacity`]
+pub const DEFAULT_ORDER_CACHE_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderSide {
     Buy,
     Sell,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl OrderSide {
+    /// The side that closes a position opened by this side
+    pub fn opposite(&self) -> OrderSide {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+
+    /// Lowercase name, as stored in the `orders.side` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+}
+
+/// Not `Eq`/`Hash`: the limit price is an `f64`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderType {
     Market,
     Limit { price: f64 },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl OrderType {
+    pub fn is_limit(&self) -> bool {
+        matches!(self, OrderType::Limit { .. })
+    }
+
+    pub fn limit_price(&self) -> Option<f64> {
+        match self {
+            OrderType::Market => None,
+            OrderType::Limit { price } => Some(*price),
+        }
+    }
+
+    /// Lowercase name, as stored in the `orders.order_type` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "market",
+            OrderType::Limit { .. } => "limit",
+        }
+    }
+}
+
+/// How long an order stays working at the venue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeInForce {
+    #[default]
+    GoodTilCancelled,
+    ImmediateOrCancel,
+    FillOrKill,
+}
+
+impl TimeInForce {
+    /// Short name, as stored in the `orders.time_in_force` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeInForce::GoodTilCancelled => "gtc",
+            TimeInForce::ImmediateOrCancel => "ioc",
+            TimeInForce::FillOrKill => "fok",
+        }
+    }
+}
+
+/// How readily an order may be held back when the engine is busy
+///
+/// Honored wherever orders wait their turn: the
+/// [rate limiter](ExecutionEngine::with_rate_limiter) and the
+/// [pending cap](ExecutionEngine::with_max_pending). A waiting order is
+/// served ahead of every waiting order of lower urgency, so a steady stream
+/// of urgent orders can starve normal ones, and normal orders starve
+/// passive ones; orders of equal urgency are served in no fixed order.
+/// Urgency never bypasses a limit, only the queue for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Urgency {
+    /// Yields to any other waiting order
+    Passive,
+    #[default]
+    Normal,
+    /// Goes ahead of any other waiting order
+    Urgent,
+}
+
+impl Urgency {
+    /// Short name, as stored in the `orders.urgency` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Urgency::Passive => "passive",
+            Urgency::Normal => "normal",
+            Urgency::Urgent => "urgent",
+        }
+    }
+
+    /// 0 for passive up to 2 for urgent
+    pub(crate) fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+/// How much an order trades: an amount of the base asset (coins, for
+/// `BTC/USD`) or of the quote currency (dollars), for venues that accept
+/// orders such as "buy $100 of BTC"
+///
+/// Stored on [`Order`] as its `quantity` and `quote_quantity` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OrderQuantity {
+    Base(f64),
+    Quote(f64),
+}
+
+impl OrderQuantity {
+    /// The amount, in whichever unit it is given
+    pub fn amount(self) -> f64 {
+        match self {
+            OrderQuantity::Base(amount) | OrderQuantity::Quote(amount) => amount,
+        }
+    }
+
+    /// Base quantity when filled at `price`
+    pub fn base_at(self, price: f64) -> f64 {
+        match self {
+            OrderQuantity::Base(quantity) => quantity,
+            OrderQuantity::Quote(amount) => amount / price,
+        }
+    }
+
+    /// Quote-currency value when filled at `price`
+    pub fn notional_at(self, price: f64) -> f64 {
+        match self {
+            OrderQuantity::Base(quantity) => quantity * price,
+            OrderQuantity::Quote(amount) => amount,
+        }
+    }
+}
+
+/// Generation of the [`Order::canonical_bytes`] layout
+///
+/// [`SigningScheme::Binary`] signs with [`CanonicalVersion::CURRENT`], so
+/// existing verifiers keep working; signers opt into newer layouts with
+/// [`SigningScheme::BinaryV2`]. Every layout stays verifiable (see
+/// [`Order::verify_any_version`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CanonicalVersion {
+    /// Fields concatenated, with the symbol unprefixed. Symbols containing
+    /// control bytes are refused, since the side byte that follows the
+    /// symbol could otherwise be read as part of it.
+    V1,
+    /// A leading version byte and a length-prefixed symbol, so a symbol's
+    /// bytes can never be read as the fields after it
+    V2,
+    /// Layout of releases before nonces: id, symbol, side, price and
+    /// quantity followed by the timestamp in whole seconds, with no nonce,
+    /// time in force or optional fields
+    Legacy,
+}
+
+impl CanonicalVersion {
+    pub const CURRENT: CanonicalVersion = CanonicalVersion::V1;
+
+    /// Every version, newest first
+    pub const ALL: [CanonicalVersion; 3] = [
+        CanonicalVersion::V2,
+        CanonicalVersion::V1,
+        CanonicalVersion::Legacy,
+    ];
+}
+
+/// Which byte encoding of an order is signed
+///
+/// The two schemes produce different signatures for the same order, so the
+/// verifier must use the scheme the signer used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigningScheme {
+    /// Compact binary layout from [`Order::canonical_bytes`], in the
+    /// [`CanonicalVersion::CURRENT`] layout
+    #[default]
+    Binary,
+    /// Compact binary layout in [`CanonicalVersion::V2`]; opt in once every
+    /// verifier of the engine's signatures understands it
+    BinaryV2,
+    /// RFC 8785 canonical JSON from [`Order::canonical_json`], for verifiers
+    /// outside Rust
+    Json,
+    /// Binary layout with price and quantity as integers at the given
+    /// decimal scales, from [`Order::canonical_bytes_scaled`], for verifiers
+    /// that want the compact layout without sharing Rust's `f64` bytes
+    Scaled { price_scale: u32, qty_scale: u32 },
+}
+
+/// Largest supported decimal scale; `10^18` is the largest power of ten
+/// an `i64` holds
+pub const MAX_SCALE: u32 = 18;
+
+/// An order's price and quantity as integer multiples of `10^-scale`,
+/// as produced by [`Order::to_scaled`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScaledOrder {
+    pub id: Uuid,
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Limit price times `10^price_scale`; `None` for market orders
+    pub price: Option<i64>,
+    /// Quantity times `10^qty_scale`
+    pub quantity: i64,
+    pub price_scale: u32,
+    pub qty_scale: u32,
+}
+
+/// `value * 10^scale` as an integer, if it fits an `i64` and `value` has no
+/// digits beyond `scale`
+///
+/// The product is rounded to absorb float noise (1.1 * 100 is
+/// 110.00000000000001), then divided back: a value that does not come back
+/// unchanged had more digits than the scale holds.
+fn to_scaled_int(value: f64, scale: u32, field: &str) -> Result<i64> {
+    if scale > MAX_SCALE {
+        return Err(ExecutionError::Validation(format!(
+            "{} scale {} exceeds {}",
+            field, scale, MAX_SCALE
+        ))
+        .into());
+    }
+    let factor = 10f64.powi(scale as i32);
+    let scaled = (value * factor).round();
+    // i64::MAX as f64 rounds up to 2^63, which does not fit
+    if !scaled.is_finite() || scaled.abs() >= i64::MAX as f64 {
+        return Err(ExecutionError::Validation(format!(
+            "{} {} does not fit at scale {}",
+            field, value, scale
+        ))
+        .into());
+    }
+    if scaled / factor != value {
+        return Err(ExecutionError::Validation(format!(
+            "{} {} is not exact at scale {}",
+            field, value, scale
+        ))
+        .into());
+    }
+    Ok(scaled as i64)
+}
+
+/// Whether two symbols name the same instrument: equal once normalized by
+/// [`Symbol::parse`], or equal as written if either does not parse
+fn same_symbol(a: &str, b: &str) -> bool {
+    match (Symbol::parse(a), Symbol::parse(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+static LAST_NONCE: AtomicU64 = AtomicU64::new(0);
+
+/// Next nonce for this process: microseconds since the epoch as of `now`,
+/// bumped when needed so that it is strictly increasing (and so survives
+/// restarts)
+fn next_nonce(now: DateTime<Utc>) -> u64 {
+    let now = now.timestamp_micros().max(0) as u64;
+    let mut last = LAST_NONCE.load(Ordering::Relaxed);
+    loop {
+        let next = now.max(last + 1);
+        match LAST_NONCE.compare_exchange_weak(last, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return next,
+            Err(actual) => last = actual,
+        }
+    }
+}
+
+/// Equality compares every field, with the `f64`s compared as IEEE values,
+/// so an order with a NaN quantity or price is never equal to itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Order {
     pub id: Uuid,
     pub symbol: String,
     pub side: OrderSide,
     pub order_type: OrderType,
+    /// In the base asset, or the quote currency if `quote_quantity` is set
     pub quantity: f64,
     pub timestamp: DateTime<Utc>,
+    /// Monotonic per signer; the engine rejects a nonce it has already seen
+    pub nonce: u64,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// Signature applied by the executing engine
     pub signature: Option<Signature>,
+    /// Approval signatures `(key_id, signature)` for multi-signature orders
+    ///
+    /// These sign the same canonical bytes as `signature` but are collected
+    /// from independent approvers before submission; the engine's own
+    /// `signature` is added on top and does not count toward the threshold.
+    #[serde(default)]
+    pub signatures: Vec<(String, Signature)>,
+    /// Order this one was split from (see [`Order::child`])
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// When the signal this order was derived from was produced; the engine
+    /// rejects it once older than [`ExecutionEngine::with_max_signal_age`]
+    #[serde(default)]
+    pub source_timestamp: Option<DateTime<Utc>>,
+    /// The order may only shrink the engine's position in the symbol, never
+    /// open, grow or flip it
+    #[serde(default)]
+    pub reduce_only: bool,
+    /// Place in the engine's queues when it is busy
+    #[serde(default)]
+    pub urgency: Urgency,
+    /// `quantity` is an amount of the quote currency (see
+    /// [`OrderQuantity::Quote`]); the filled base quantity then depends on
+    /// the fill price
+    #[serde(default)]
+    pub quote_quantity: bool,
+    /// Caller-supplied id tying this order to a request in other services;
+    /// carried on the engine's log spans, events and stored row
+    ///
+    /// Not part of [`Order::canonical_bytes`], so it is not signed: it
+    /// describes how the order travelled rather than what it trades, and a
+    /// relay may set or rewrite it without invalidating the signature.
+    /// Nothing should be trusted on the strength of it.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Id the venue knows this order by, assigned by the engine's
+    /// [`OrderIdGenerator`] if not set beforehand
+    ///
+    /// Like `correlation_id`, it is not signed: `id` remains the order's
+    /// identity in [`Order::canonical_bytes`].
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    /// Free-form labels, such as the strategy that placed the order, for
+    /// attribution (see [`OrderQuery::tag`](crate::OrderQuery::tag))
+    ///
+    /// Not signed, like `correlation_id`.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Order {
     pub fn new(symbol: String, side: OrderSide, order_type: OrderType, quantity: f64) -> Self {
+        Self::new_at(symbol, side, order_type, quantity, Utc::now())
+    }
+
+    /// Create an order timestamped `now` rather than the system clock
+    pub fn new_at(
+        symbol: String,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: f64,
+        now: DateTime<Utc>,
+    ) -> Self {
         Self {
             id: Uuid::new_v4(),
             symbol,
             side,
             order_type,
             quantity,
-            timestamp: Utc::now(),
+            timestamp: now,
+            nonce: next_nonce(now),
+            time_in_force: TimeInForce::default(),
             signature: None,
+            signatures: Vec::new(),
+            parent_id: None,
+            source_timestamp: None,
+            reduce_only: false,
+            urgency: Urgency::default(),
+            quote_quantity: false,
+            correlation_id: None,
+            client_order_id: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Split off a child order for `quantity` of this order
+    ///
+    /// The child keeps the symbol, side, type, time in force, reduce-only
+    /// flag, quantity unit, correlation id, tags and source timestamp, and
+    /// gets a fresh id, timestamp and nonce. It is unsigned and records this
+    /// order as its parent. `quantity` must be positive and no more than
+    /// this order's.
+    pub fn child(&self, quantity: f64) -> Result<Order> {
+        if !(quantity.is_finite() && quantity > 0.0) {
+            return Err(ExecutionError::Validation(format!(
+                "child quantity {} must be positive",
+                quantity
+            ))
+            .into());
+        }
+        if quantity > self.quantity {
+            return Err(ExecutionError::Validation(format!(
+                "child quantity {} exceeds parent quantity {}",
+                quantity, self.quantity
+            ))
+            .into());
+        }
+
+        Ok(self.make_child(quantity))
+    }
+
+    /// Split into child orders of at most `max_qty` each
+    ///
+    /// All children but the last are exactly `max_qty`; the last takes the
+    /// remainder, so the child quantities sum exactly to this order's. An
+    /// order no larger than `max_qty` (or a `max_qty` that is not positive
+    /// and finite) yields a single child for the full quantity.
+    pub fn split_by_max(&self, max_qty: f64) -> Vec<Order> {
+        slice_quantities(self.quantity, max_qty)
+            .into_iter()
+            .map(|quantity| self.make_child(quantity))
+            .collect()
+    }
+
+    fn make_child(&self, quantity: f64) -> Order {
+        Order {
+            time_in_force: self.time_in_force,
+            reduce_only: self.reduce_only,
+            urgency: self.urgency,
+            quote_quantity: self.quote_quantity,
+            correlation_id: self.correlation_id.clone(),
+            tags: self.tags.clone(),
+            parent_id: Some(self.id),
+            source_timestamp: self.source_timestamp,
+            ..Order::new(
+                self.symbol.clone(),
+                self.side,
+                self.order_type.clone(),
+                quantity,
+            )
+        }
+    }
+
+    /// Market order for `quantity` in the direction of a trading signal
+    ///
+    /// `signal_type` must be "buy" or "sell" (any case); anything else, such
+    /// as "hold", is a validation error. The signal's timestamp (Unix
+    /// seconds) becomes the order's `source_timestamp`. Orders carry no
+    /// metadata, so the signal's `strength` and `metadata` are not kept; size
+    /// the order from them before calling this.
+    pub fn from_signal(signal: &TradingSignal, quantity: f64) -> Result<Order> {
+        let side = if signal.signal_type.eq_ignore_ascii_case("buy") {
+            OrderSide::Buy
+        } else if signal.signal_type.eq_ignore_ascii_case("sell") {
+            OrderSide::Sell
+        } else {
+            return Err(ExecutionError::Validation(format!(
+                "signal type '{}' does not map to an order side",
+                signal.signal_type
+            ))
+            .into());
+        };
+
+        let source_timestamp = DateTime::from_timestamp(signal.timestamp, 0).ok_or_else(|| {
+            ExecutionError::Validation(format!("invalid signal timestamp {}", signal.timestamp))
+        })?;
+
+        Ok(Order {
+            source_timestamp: Some(source_timestamp),
+            ..Order::new(signal.symbol.clone(), side, OrderType::Market, quantity)
+        })
+    }
+
+    /// Check the order's own fields: finite values and the built-in
+    /// [`ValidationRule`]s (a positive quantity and limit price, and a
+    /// symbol)
+    ///
+    /// An engine runs its own, configurable rule set instead; see
+    /// [`ExecutionEngine::validate_order`].
+    pub fn validate(&self) -> Result<()> {
+        self.ensure_finite()?;
+        PositiveQuantity.check(self)?;
+        NonEmptySymbol.check(self)?;
+        PositiveLimitPrice.check(self)
+    }
+
+    /// The quantity with its unit
+    pub fn order_quantity(&self) -> OrderQuantity {
+        if self.quote_quantity {
+            OrderQuantity::Quote(self.quantity)
+        } else {
+            OrderQuantity::Base(self.quantity)
         }
     }
 
-    /// Get canonical bytes for signing
+    pub fn set_order_quantity(&mut self, quantity: OrderQuantity) {
+        self.quantity = quantity.amount();
+        self.quote_quantity = matches!(quantity, OrderQuantity::Quote(_));
+    }
+
+    /// Parse and normalize the order's symbol
+    pub fn symbol_parsed(&self) -> Result<Symbol> {
+        Symbol::parse(&self.symbol)
+    }
+
+    /// Start building an order with optional fields
+    pub fn builder() -> OrderBuilder {
+        OrderBuilder::default()
+    }
+
+    /// Get canonical bytes for signing, in the [`CanonicalVersion::CURRENT`]
+    /// layout
     pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        self.canonical_bytes_versioned(CanonicalVersion::CURRENT)
+    }
+
+    /// Canonical bytes in the layout of `version`, for checking signatures
+    /// made by older releases
+    pub fn canonical_bytes_versioned(&self, version: CanonicalVersion) -> Result<Vec<u8>> {
+        self.ensure_finite()?;
+
         let mut data = Vec::new();
-        data.extend_from_slice(self.id.as_bytes());
-        data.extend_from_slice(self.symbol.as_bytes());
+        match version {
+            CanonicalVersion::V1 => {
+                if self.symbol.bytes().any(|b| b.is_ascii_control()) {
+                    return Err(Error::Crypto(format!(
+                        "symbol {:?} has control bytes, which the v1 layout cannot encode",
+                        self.symbol
+                    )));
+                }
+                data.extend_from_slice(self.id.as_bytes());
+                data.extend_from_slice(self.symbol.as_bytes());
+            }
+            CanonicalVersion::Legacy => {
+                data.extend_from_slice(self.id.as_bytes());
+                data.extend_from_slice(self.symbol.as_bytes());
+            }
+            CanonicalVersion::V2 => {
+                data.push(2);
+                data.extend_from_slice(self.id.as_bytes());
+                data.extend_from_slice(&(self.symbol.len() as u32).to_le_bytes());
+                data.extend_from_slice(self.symbol.as_bytes());
+            }
+        }
 
         match self.side {
             OrderSide::Buy => data.push(0),
             OrderSide::Sell => data.push(1),
         }
 
-        match self.order_type {
-            OrderType::Market => data.push(0),
-            OrderType::Limit { price } => {
+        match self.order_type.limit_price() {
+            None => data.push(0),
+            Some(price) => {
                 data.push(1);
                 data.extend_from_slice(&price.to_le_bytes());
             }
         }
 
         data.extend_from_slice(&self.quantity.to_le_bytes());
-        data.extend_from_slice(&self.timestamp.timestamp().to_le_bytes());
+        if version == CanonicalVersion::Legacy {
+            data.extend_from_slice(&self.timestamp.timestamp().to_le_bytes());
+            return Ok(data);
+        }
+        self.push_canonical_tail(&mut data, version != CanonicalVersion::V1);
+        Ok(data)
+    }
+
+    /// Price and quantity as integers at the given decimal scales, e.g. a
+    /// price of 1.1 at scale 2 is 110
+    ///
+    /// Fails with [`ExecutionError::Validation`] for a scale above
+    /// [`MAX_SCALE`], or a value that is not finite, does not fit an `i64`
+    /// once scaled or has more decimal places than its scale (1.125 at
+    /// scale 2), since rounding it would sign a different price.
+    pub fn to_scaled(&self, price_scale: u32, qty_scale: u32) -> Result<ScaledOrder> {
+        Ok(ScaledOrder {
+            id: self.id,
+            symbol: self.symbol.clone(),
+            side: self.side,
+            price: self
+                .order_type
+                .limit_price()
+                .map(|price| to_scaled_int(price, price_scale, "price"))
+                .transpose()?,
+            quantity: to_scaled_int(self.quantity, qty_scale, "quantity")?,
+            price_scale,
+            qty_scale,
+        })
+    }
 
+    /// [`canonical_bytes`](Self::canonical_bytes) with price and quantity as
+    /// little-endian `i64`s from [`Order::to_scaled`]
+    ///
+    /// Its own leading version byte and the two scales precede the fields,
+    /// so a signature is only valid at the scales it was made with.
+    pub fn canonical_bytes_scaled(&self, price_scale: u32, qty_scale: u32) -> Result<Vec<u8>> {
+        let scaled = self.to_scaled(price_scale, qty_scale)?;
+
+        let mut data = vec![3];
+        data.extend_from_slice(&price_scale.to_le_bytes());
+        data.extend_from_slice(&qty_scale.to_le_bytes());
+        data.extend_from_slice(self.id.as_bytes());
+        data.extend_from_slice(&(self.symbol.len() as u32).to_le_bytes());
+        data.extend_from_slice(self.symbol.as_bytes());
+        match self.side {
+            OrderSide::Buy => data.push(0),
+            OrderSide::Sell => data.push(1),
+        }
+        match scaled.price {
+            None => data.push(0),
+            Some(price) => {
+                data.push(1);
+                data.extend_from_slice(&price.to_le_bytes());
+            }
+        }
+        data.extend_from_slice(&scaled.quantity.to_le_bytes());
+        self.push_canonical_tail(&mut data, true);
         Ok(data)
     }
 
+    /// The canonical fields after the quantity, shared by every layout
+    ///
+    /// Optional fields are only appended when set, so orders without them
+    /// keep their original encoding. With `tagged` (every layout after V1)
+    /// each starts with its own tag byte; V1 tells them apart by length.
+    fn push_canonical_tail(&self, data: &mut Vec<u8>, tagged: bool) {
+        data.extend_from_slice(&self.timestamp.timestamp().to_le_bytes());
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+
+        match self.time_in_force {
+            TimeInForce::GoodTilCancelled => data.push(0),
+            TimeInForce::ImmediateOrCancel => data.push(1),
+            TimeInForce::FillOrKill => data.push(2),
+        }
+
+        let optional = |data: &mut Vec<u8>, tag: u8, bytes: &[u8]| {
+            if tagged {
+                data.push(tag);
+            }
+            data.extend_from_slice(bytes);
+        };
+        // In V1 the lengths (16, 8 and 1 bytes) keep these distinguishable
+        if let Some(parent_id) = self.parent_id {
+            optional(data, b'p', parent_id.as_bytes());
+        }
+        if let Some(source) = self.source_timestamp {
+            optional(data, b's', &source.timestamp().to_le_bytes());
+        }
+        if self.reduce_only {
+            optional(data, b'r', &[1]);
+        }
+        // Tagged in every layout; two bytes, so in V1 every combination of
+        // tails still has its own length
+        match self.urgency {
+            Urgency::Normal => {}
+            Urgency::Passive => data.extend_from_slice(&[b'u', 0]),
+            Urgency::Urgent => data.extend_from_slice(&[b'u', 2]),
+        }
+        // Four bytes in V1, for the same reason
+        if self.quote_quantity {
+            if tagged {
+                data.push(b'q');
+            } else {
+                data.extend_from_slice(b"qquo");
+            }
+        }
+    }
+
+    /// Get RFC 8785 canonical JSON of the signed fields
+    ///
+    /// The timestamp is whole seconds (as in the binary layout) and the nonce
+    /// is a decimal string so that it survives JSON parsers that only have
+    /// 53-bit integers.
+    pub fn canonical_json(&self) -> Result<Vec<u8>> {
+        self.ensure_finite()?;
+
+        let order_type = match self.order_type.limit_price() {
+            None => serde_json::json!({ "type": "market" }),
+            Some(price) => serde_json::json!({ "type": "limit", "price": price }),
+        };
+        let time_in_force = serde_json::to_value(self.time_in_force)?;
+
+        let mut value = serde_json::json!({
+            "id": self.id.to_string(),
+            "symbol": self.symbol,
+            "side": self.side.as_str(),
+            "order_type": order_type,
+            "quantity": self.quantity,
+            "timestamp": self.timestamp.timestamp(),
+            "nonce": self.nonce.to_string(),
+            "time_in_force": time_in_force,
+        });
+        if let Some(parent_id) = self.parent_id {
+            value["parent_id"] = parent_id.to_string().into();
+        }
+        if let Some(source) = self.source_timestamp {
+            value["source_timestamp"] = source.timestamp().into();
+        }
+        if self.reduce_only {
+            value["reduce_only"] = true.into();
+        }
+        if self.urgency != Urgency::Normal {
+            value["urgency"] = self.urgency.as_str().into();
+        }
+        if self.quote_quantity {
+            value["quote_quantity"] = true.into();
+        }
+        Ok(crypto::canonical_json(&value))
+    }
+
+    /// Reject NaN and infinite quantities and prices, which have no
+    /// canonical encoding and must never be signed
+    fn ensure_finite(&self) -> Result<()> {
+        let bad_price = matches!(self.order_type.limit_price(), Some(p) if !p.is_finite());
+        if !self.quantity.is_finite() || bad_price {
+            return Err(ExecutionError::Validation("non-finite value".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Bytes signed under the given scheme
+    pub fn signing_bytes(&self, scheme: SigningScheme) -> Result<Vec<u8>> {
+        match scheme {
+            SigningScheme::Binary => self.canonical_bytes(),
+            SigningScheme::BinaryV2 => self.canonical_bytes_versioned(CanonicalVersion::V2),
+            SigningScheme::Json => self.canonical_json(),
+            SigningScheme::Scaled {
+                price_scale,
+                qty_scale,
+            } => self.canonical_bytes_scaled(price_scale, qty_scale),
+        }
+    }
+
     /// Sign the order
     pub fn sign(&mut self, key: &SigningKey) -> Result<()> {
-        let data = self.canonical_bytes()?;
+        self.sign_with(key, SigningScheme::Binary)
+    }
+
+    /// Sign the canonical JSON encoding of the order
+    pub fn sign_json(&mut self, key: &SigningKey) -> Result<()> {
+        self.sign_with(key, SigningScheme::Json)
+    }
+
+    pub fn sign_with(&mut self, key: &SigningKey, scheme: SigningScheme) -> Result<()> {
+        let data = self.signing_bytes(scheme)?;
         self.signature = Some(key.sign(&data));
         Ok(())
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum OrderStatus {
-    Pending,
-    Executed,
-    Failed,
-    Cancelled,
-}
+    /// Verify the order's signature over its binary encoding
+    pub fn verify(&self, key: &VerificationKey) -> Result<()> {
+        self.verify_with(key, SigningScheme::Binary)
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OrderResult {
-    pub order_id: Uuid,
-    pub status: OrderStatus,
-    pub execution_price: Option<f64>,
-    pub executed_quantity: Option<f64>,
-    pub timestamp: DateTime<Utc>,
-    pub message: Option<String>,
+    /// Verify the order's signature over its canonical JSON encoding
+    pub fn verify_json(&self, key: &VerificationKey) -> Result<()> {
+        self.verify_with(key, SigningScheme::Json)
+    }
+
+    pub fn verify_with(&self, key: &VerificationKey, scheme: SigningScheme) -> Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("Order is not signed".to_string()))?;
+        key.verify(&self.signing_bytes(scheme)?, signature)
+    }
+
+    /// Verify the order's binary signature under whichever
+    /// [`CanonicalVersion`] it was made with, returning that version
+    ///
+    /// Versions are tried newest first, skipping any that cannot encode the
+    /// order. Use this for stored orders, or
+    /// signers that may have opted into [`SigningScheme::BinaryV2`]; new
+    /// submissions from known signers should use [`Order::verify_with`].
+    /// Only the binary layout is versioned: JSON and scaled signatures have
+    /// a single layout each, so check those with [`Order::verify_with`].
+    pub fn verify_any_version(&self, key: &VerificationKey) -> Result<CanonicalVersion> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("Order is not signed".to_string()))?;
+        for version in CanonicalVersion::ALL {
+            let Ok(data) = self.canonical_bytes_versioned(version) else {
+                continue;
+            };
+            if key.verify(&data, signature).is_ok() {
+                return Ok(version);
+            }
+        }
+        Err(Error::Crypto(
+            "Signature verification failed for every canonical version".to_string(),
+        ))
+    }
+
+    /// Add an approval signature for multi-signature verification
+    pub fn add_approval(&mut self, key: &SigningKey) -> Result<()> {
+        let data = self.canonical_bytes()?;
+        self.signatures.push((key.key_id(), key.sign(&data)));
+        Ok(())
+    }
+
+    /// Check that at least `threshold` distinct keys from `keyring` approved
+    /// this order
+    pub fn verify_multisig(&self, keyring: &KeyRing, threshold: usize) -> Result<()> {
+        let data = self.canonical_bytes()?;
+        keyring.verify_threshold(&data, &self.signatures, threshold)
+    }
 }
 
-pub struct ExecutionEngine {
-    signing_key: SigningKey,
+/// Chainable constructor for [`Order`]
+///
+/// Symbol, side and quantity are required; the order type defaults to market
+/// and the nonce to the next value for this process.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBuilder {
+    symbol: Option<String>,
+    side: Option<OrderSide>,
+    order_type: Option<OrderType>,
+    quantity: Option<OrderQuantity>,
+    time_in_force: TimeInForce,
+    nonce: Option<u64>,
+    reduce_only: bool,
+    urgency: Urgency,
+    correlation_id: Option<String>,
+    client_order_id: Option<String>,
+    tags: Vec<String>,
 }
 
-impl ExecutionEngine {
-    pub fn new(signing_key: SigningKey) -> Self {
-        Self { signing_key }
+impl OrderBuilder {
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(symbol.into());
+        self
     }
 
-    /// Execute an order (placeholder implementation)
-    pub async fn execute_order(&self, mut order: Order) -> Result<OrderResult> {
-        // Sign the order
-        order.sign(&self.signing_key)?;
+    pub fn side(mut self, side: OrderSide) -> Self {
+        self.side = Some(side);
+        self
+    }
 
-        // In a real implementation, this would:
-        // 1. Validate the order
-        // 2. Submit to exchange via CCXT
-        // 3. Monitor execution
-        // 4. Return results
+    pub fn market(mut self) -> Self {
+        self.order_type = Some(OrderType::Market);
+        self
+    }
 
-        tracing::info!("Executing order: {:?}", order);
+    pub fn limit(mut self, price: f64) -> Self {
+        self.order_type = Some(OrderType::Limit { price });
+        self
+    }
 
-        // Placeholder: simulate successful execution
-        Ok(OrderResult {
-            order_id: order.id,
-            status: OrderStatus::Executed,
-            execution_price: match order.order_type {
-                OrderType::Market => Some(50000.0), // Placeholder price
-                OrderType::Limit { price } => Some(price),
-            },
-            executed_quantity: Some(order.quantity),
-            timestamp: Utc::now(),
-            message: Some("Order executed successfully".to_string()),
-        })
+    pub fn quantity(mut self, quantity: f64) -> Self {
+        self.quantity = Some(OrderQuantity::Base(quantity));
+        self
     }
 
-    /// Validate order parameters
-    pub fn validate_order(&self, order: &Order) -> Result<()> {
-        if order.quantity <= 0.0 {
-            return Err(Error::Execution("Quantity must be positive".to_string()));
-        }
+    /// Size the order as `amount` of the quote currency instead
+    pub fn quote_quantity(mut self, amount: f64) -> Self {
+        self.quantity = Some(OrderQuantity::Quote(amount));
+        self
+    }
 
-        if order.symbol.is_empty() {
-            return Err(Error::Execution("Symbol cannot be empty".to_string()));
-        }
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
 
-        if let OrderType::Limit { price } = order.order_type {
-            if price <= 0.0 {
-                return Err(Error::Execution("Limit price must be positive".to_string()));
-            }
-        }
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
 
-        Ok(())
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn urgency(mut self, urgency: Urgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
 
-    #[test]
-    fn test_order_creation() {
-        let order = Order::new(
-            "BTC/USD".to_string(),
-            OrderSide::Buy,
-            OrderType::Market,
-            0.1,
-        );
+    pub fn correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
 
-        assert_eq!(order.symbol, "BTC/USD");
-        assert_eq!(order.quantity, 0.1);
+    pub fn client_order_id(mut self, client_order_id: impl Into<String>) -> Self {
+        self.client_order_id = Some(client_order_id.into());
+        self
     }
 
-    #[test]
-    fn test_order_signing() {
-        let key = SigningKey::generate();
+    /// Add a tag; may be called repeatedly
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Order> {
+        let missing =
+            |field: &str| ExecutionError::Validation(format!("order {} is required", field));
+
         let mut order = Order::new(
-            "BTC/USD".to_string(),
-            OrderSide::Buy,
-            OrderType::Market,
-            0.1,
+            self.symbol.ok_or_else(|| missing("symbol"))?,
+            self.side.ok_or_else(|| missing("side"))?,
+            self.order_type.unwrap_or(OrderType::Market),
+            0.0,
         );
-
-        assert!(order.sign(&key).is_ok());
-        assert!(order.signature.is_some());
+        order.set_order_quantity(self.quantity.ok_or_else(|| missing("quantity"))?);
+        order.time_in_force = self.time_in_force;
+        order.reduce_only = self.reduce_only;
+        order.urgency = self.urgency;
+        order.correlation_id = self.correlation_id;
+        order.client_order_id = self.client_order_id;
+        order.tags = self.tags;
+        if let Some(nonce) = self.nonce {
+            order.nonce = nonce;
+        }
+        Ok(order)
     }
+}
 
-    #[tokio::test]
-    async fn test_execution_engine() {
-        let key = SigningKey::generate();
-        let engine = ExecutionEngine::new(key);
-
+/// Reusable order parameters for repeat trades
+///
+/// Templates carry no identity: each [`OrderTemplate::instantiate`] call
+/// yields a new order with a fresh id, timestamp and nonce. Store them with
+/// [`SignalManager::save_template`](crate::SignalManager::save_template).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderTemplate {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: f64,
+}
+
+impl OrderTemplate {
+    /// New unsigned order with this template's parameters
+    pub fn instantiate(&self) -> Order {
+        Order::new(
+            self.symbol.clone(),
+            self.side,
+            self.order_type.clone(),
+            self.quantity,
+        )
+    }
+
+    /// Check the parameters as [`Order::validate`] would for an instance
+    pub fn validate(&self) -> Result<()> {
+        self.instantiate().validate()
+    }
+}
+
+/// One-line summary for logs, such as `BUY 0.1 BTC/USD @ MARKET [id=...]`
+///
+/// A non-default time in force, urgency and the reduce-only and quote
+/// quantity flags are appended; signatures and other fields are left to
+/// `Debug`.
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} @ ",
+            self.side.as_str().to_uppercase(),
+            self.quantity,
+            self.symbol
+        )?;
+        match self.order_type {
+            OrderType::Market => f.write_str("MARKET")?,
+            OrderType::Limit { price } => write!(f, "LIMIT {}", price)?,
+        }
+        if self.time_in_force != TimeInForce::default() {
+            write!(f, " {}", self.time_in_force.as_str().to_uppercase())?;
+        }
+        if self.reduce_only {
+            f.write_str(" REDUCE-ONLY")?;
+        }
+        if self.urgency != Urgency::default() {
+            write!(f, " {}", self.urgency.as_str().to_uppercase())?;
+        }
+        if self.quote_quantity {
+            f.write_str(" QUOTE-QTY")?;
+        }
+        write!(f, " [id={}]", self.id)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl Order {
+    /// Encode in the compact binary wire format
+    ///
+    /// Signatures travel as raw bytes rather than hex. The encoding is for
+    /// transport only; signatures still cover [`Order::canonical_bytes`].
+    pub fn to_bincode(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decode from [`Order::to_bincode`] output
+    pub fn from_bincode(bytes: &[u8]) -> Result<Order> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// Convert an `f64` money field into a `Decimal`, using the shortest
+/// representation that round-trips (so `0.1` becomes exactly `0.1`).
+#[cfg(feature = "decimal")]
+fn to_decimal(value: f64, field: &str) -> Result<rust_decimal::Decimal> {
+    use rust_decimal::prelude::FromPrimitive;
+
+    rust_decimal::Decimal::from_f64(value)
+        .map(|d| d.normalize())
+        .ok_or_else(|| {
+            ExecutionError::Validation(format!("{} is not representable as a decimal", field))
+                .into()
+        })
+}
+
+/// Exact decimal views of the money-bearing fields.
+///
+/// The wire format and `canonical_bytes` still carry `f64` so signatures stay
+/// identical whether or not the `decimal` feature is enabled; use these
+/// helpers for any arithmetic that has to reconcile exactly with a venue.
+#[cfg(feature = "decimal")]
+impl Order {
+    /// Order quantity as a `Decimal`
+    pub fn quantity_decimal(&self) -> Result<rust_decimal::Decimal> {
+        to_decimal(self.quantity, "quantity")
+    }
+
+    /// Limit price as a `Decimal` (`None` for market orders)
+    pub fn limit_price_decimal(&self) -> Result<Option<rust_decimal::Decimal>> {
+        self.order_type
+            .limit_price()
+            .map(|price| to_decimal(price, "limit price"))
+            .transpose()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Pending,
+    PartiallyFilled,
+    Executed,
+    Failed,
+    Cancelled,
+    /// Never submitted: stopped by validation or risk checks
+    Rejected,
+}
+
+impl OrderStatus {
+    /// Lowercase name, as stored in the `orders.status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::PartiallyFilled => "partially_filled",
+            OrderStatus::Executed => "executed",
+            OrderStatus::Failed => "failed",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Rejected => "rejected",
+        }
+    }
+
+    /// Whether the order can no longer change: executed, failed, cancelled
+    /// or rejected
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, OrderStatus::Pending | OrderStatus::PartiallyFilled)
+    }
+
+    /// Whether an order in this status may move to `next`
+    ///
+    /// Open orders may fill further or reach any final state except
+    /// `Rejected`, which only applies to orders that were never submitted.
+    /// Final states only "transition" to themselves, so re-reporting the
+    /// same status is always allowed.
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+
+        match (self, next) {
+            (a, b) if *a == b => true,
+            (Pending, PartiallyFilled | Executed | Failed | Cancelled) => true,
+            (PartiallyFilled, Executed | Failed | Cancelled) => true,
+            _ => false,
+        }
+    }
+}
+
+/// As with [`Order`], equality on the `f64` fields follows IEEE semantics
+/// (NaN is never equal).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderResult {
+    pub order_id: Uuid,
+    pub status: OrderStatus,
+    pub execution_price: Option<f64>,
+    pub executed_quantity: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+    pub message: Option<String>,
+    /// Where the engine spent its time on this order; `None` for results
+    /// that did not come through [`ExecutionEngine::execute_order`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<ExecutionTimings>,
+    /// The book the order filled against, from venues (such as the
+    /// simulator with an [`OrderBook`]) that expose one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book_snapshot: Option<OrderBook>,
+    /// The venue's own id for the order, for reconciling against venue
+    /// reports (see [`Database::get_order_by_exchange_id`](crate::Database::get_order_by_exchange_id))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange_order_id: Option<String>,
+    /// Fee for the executed quantity from the engine's [`FeeModel`], in the
+    /// quote currency; negative for a rebate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<f64>,
+}
+
+/// A field that differs between two [`OrderResult`]s, from
+/// [`OrderResult::diff`]
+///
+/// Values are rendered for display; `None` means the field is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Latency breakdown of one order through the engine, in microseconds
+///
+/// `total_us` runs from the start of pre-trade checks until the venue
+/// answered, so it also covers tracking and event publishing between the
+/// measured stages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTimings {
+    /// Pre-trade checks: validation, risk, replay and multisig
+    pub validate_us: u64,
+    pub sign_us: u64,
+    /// Round trip to the exchange adapter
+    pub submit_us: u64,
+    pub total_us: u64,
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Tracked orders, dropping terminal ones past `capacity`
+fn new_order_cache(capacity: usize) -> LruMap<Uuid, TrackedOrder> {
+    LruMap::new(
+        capacity,
+        |tracked: &TrackedOrder| matches!(&tracked.result, Some(result) if result.is_terminal()),
+    )
+}
+
+/// Span for one order's trip through the engine, so its log lines can be
+/// joined to other services' by correlation id
+fn order_span(order: &Order) -> tracing::Span {
+    let span = tracing::info_span!(
+        "order",
+        id = %order.id,
+        correlation_id = tracing::field::Empty
+    );
+    if let Some(correlation_id) = &order.correlation_id {
+        span.record("correlation_id", correlation_id.as_str());
+    }
+    span
+}
+
+impl OrderResult {
+    /// See [`OrderStatus::is_terminal`]
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_terminal()
+    }
+
+    /// Round the execution price, executed quantity and fee to `decimals`
+    /// places
+    ///
+    /// For display and interchange only: rounding loses precision, so keep
+    /// the unrounded result for accounting and reconciliation.
+    pub fn round_prices(&mut self, decimals: u32) {
+        let round = |value: f64| {
+            let scale = 10f64.powi(decimals.min(15) as i32);
+            (value * scale).round() / scale
+        };
+        self.execution_price = self.execution_price.map(round);
+        self.executed_quantity = self.executed_quantity.map(round);
+        self.fee = self.fee.map(round);
+    }
+
+    /// JSON with prices rounded as by [`OrderResult::round_prices`]; the
+    /// result itself is left untouched
+    pub fn to_json_rounded(&self, decimals: u32) -> Result<String> {
+        let mut rounded = self.clone();
+        rounded.round_prices(decimals);
+        Ok(serde_json::to_string(&rounded)?)
+    }
+
+    /// Fields whose values differ from `self` (before) to `other` (after):
+    /// status, execution price and executed quantity, in that order
+    ///
+    /// Meant for reconciliation reports, comparing the recorded result with
+    /// what [`ExecutionEngine::reconcile`] got from the venue. Timestamps,
+    /// messages and fees are not compared.
+    pub fn diff(&self, other: &OrderResult) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        if self.status != other.status {
+            changes.push(FieldChange {
+                field: "status",
+                before: Some(self.status.as_str().to_string()),
+                after: Some(other.status.as_str().to_string()),
+            });
+        }
+        let fields = [
+            (
+                "execution_price",
+                self.execution_price,
+                other.execution_price,
+            ),
+            (
+                "executed_quantity",
+                self.executed_quantity,
+                other.executed_quantity,
+            ),
+        ];
+        for (field, before, after) in fields {
+            if before != after {
+                changes.push(FieldChange {
+                    field,
+                    before: before.map(|v| v.to_string()),
+                    after: after.map(|v| v.to_string()),
+                });
+            }
+        }
+        changes
+    }
+
+    /// Whether the venue took the order (it may still be open)
+    fn is_accepted(&self) -> bool {
+        matches!(
+            self.status,
+            OrderStatus::Pending | OrderStatus::PartiallyFilled | OrderStatus::Executed
+        )
+    }
+}
+
+/// `parent_qty` minus the sum of `slices`, added in order
+///
+/// Splitting a quantity in floating point can leave a residual of a few
+/// ULPs; anything but zero means the slices do not add back up to the
+/// parent.
+pub fn reconcile_slices(parent_qty: f64, slices: &[f64]) -> f64 {
+    parent_qty - slices.iter().sum::<f64>()
+}
+
+/// Fold any residual into the last slice so `slices` sums to `parent_qty`
+fn absorb_residual(parent_qty: f64, slices: &mut [f64]) {
+    let Some(last) = slices.len().checked_sub(1) else {
+        return;
+    };
+    slices[last] += reconcile_slices(parent_qty, slices);
+
+    // The correction can itself round (0.3 + 0.3 + 0.3 overshoots back and
+    // forth); the sum moves monotonically with the last slice, so walk it one
+    // ULP at a time until the sum is exact
+    for _ in 0..64 {
+        let residual = reconcile_slices(parent_qty, slices);
+        if residual == 0.0 || !residual.is_finite() {
+            break;
+        }
+        slices[last] = step_ulp(slices[last], residual > 0.0);
+    }
+}
+
+/// The adjacent `f64` above (`up`) or below `value`
+fn step_ulp(value: f64, up: bool) -> f64 {
+    if value == 0.0 {
+        let tiny = f64::from_bits(1);
+        return if up { tiny } else { -tiny };
+    }
+    let bits = value.to_bits();
+    // Away from zero increases the magnitude bits
+    if (value > 0.0) == up {
+        f64::from_bits(bits + 1)
+    } else {
+        f64::from_bits(bits - 1)
+    }
+}
+
+/// Slices of at most `max_qty` covering `total`: all but the last are
+/// `max_qty`, and the last absorbs the remainder and any rounding residual
+fn slice_quantities(total: f64, max_qty: f64) -> Vec<f64> {
+    if !max_qty.is_finite() || max_qty <= 0.0 || total <= max_qty {
+        return vec![total];
+    }
+
+    // 0.9 / 0.3 is 3.0000000000000004, which must not add a dust slice
+    let steps = total / max_qty;
+    let count = if (steps - steps.round()).abs() < 1e-9 {
+        steps.round()
+    } else {
+        steps.ceil()
+    } as usize;
+
+    let mut slices = vec![max_qty; count - 1];
+    slices.push((total - max_qty * (count - 1) as f64).min(max_qty));
+    absorb_residual(total, &mut slices);
+    slices
+}
+
+/// Volume-weighted average execution price across the fills in `results`
+///
+/// Results without a fill are ignored; returns `None` if nothing filled.
+/// The average is kept as a running mean, so fills at a single price
+/// average to exactly that price whatever the slice sizes.
+pub fn vwap(results: &[OrderResult]) -> Option<f64> {
+    let mut quantity = 0.0;
+    let mut mean = 0.0;
+    for (price, qty) in results
+        .iter()
+        .filter_map(|r| Some((r.execution_price?, r.executed_quantity?)))
+        .filter(|(_, qty)| *qty > 0.0)
+    {
+        quantity += qty;
+        mean += (price - mean) * (qty / quantity);
+    }
+    (quantity > 0.0).then_some(mean)
+}
+
+/// One-line summary for logs, such as `EXECUTED 0.1 @ 50000 [id=...]`
+///
+/// The message is appended for failed and rejected results, where it holds
+/// the reason.
+impl fmt::Display for OrderResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.status.as_str().to_uppercase())?;
+        if let Some(quantity) = self.executed_quantity {
+            write!(f, " {}", quantity)?;
+        }
+        if let Some(price) = self.execution_price {
+            write!(f, " @ {}", price)?;
+        }
+        write!(f, " [id={}]", self.order_id)?;
+        match (&self.status, &self.message) {
+            (OrderStatus::Failed | OrderStatus::Rejected, Some(message)) => {
+                write!(f, ": {}", message)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl OrderResult {
+    /// Execution price as a `Decimal`
+    pub fn execution_price_decimal(&self) -> Result<Option<rust_decimal::Decimal>> {
+        self.execution_price
+            .map(|p| to_decimal(p, "execution price"))
+            .transpose()
+    }
+
+    /// Executed quantity as a `Decimal`
+    pub fn executed_quantity_decimal(&self) -> Result<Option<rust_decimal::Decimal>> {
+        self.executed_quantity
+            .map(|q| to_decimal(q, "executed quantity"))
+            .transpose()
+    }
+}
+
+/// An order the engine has submitted, with the last known result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedOrder {
+    pub order: Order,
+    /// `None` while the outcome is unknown: the order is in flight, or its
+    /// submission timed out and it has not been reconciled yet
+    pub result: Option<OrderResult>,
+}
+
+/// Outcome of [`ExecutionEngine::execute_twap`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwapResult {
+    /// Result of each slice sent, in schedule order
+    pub slices: Vec<OrderResult>,
+    /// [`vwap`] across the slices' fills; `None` if nothing filled
+    pub vwap: Option<f64>,
+}
+
+/// What [`ExecutionEngine::simulate`] expects would be submitted for an order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    /// Quantity after lot-size rounding
+    pub rounded_quantity: f64,
+    /// Limit price snapped to the symbol's tick size; `None` for market
+    /// orders
+    pub rounded_price: Option<f64>,
+    /// Fee for filling the whole order at `estimated_fill_price`; `None`
+    /// without a fee model or a fill price
+    pub estimated_fee: Option<f64>,
+    /// The rounded limit price, or the current market price for market
+    /// orders; `None` if no market price is available
+    pub estimated_fill_price: Option<f64>,
+    /// Things that passed the checks but may surprise the caller
+    pub warnings: Vec<String>,
+}
+
+/// Serializable copy of an engine's in-memory state, for hot restarts
+///
+/// Taken by [`ExecutionEngine::snapshot`] and loaded by
+/// [`ExecutionEngine::restore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    /// When the snapshot was taken, by the engine's clock
+    pub taken_at: DateTime<Utc>,
+    /// Tracked orders that had not reached a terminal status
+    pub pending_orders: Vec<TrackedOrder>,
+    pub positions: HashMap<String, Position>,
+    /// Nonces already processed, per signer key id
+    pub seen_nonces: HashMap<String, NonceWindow>,
+    /// Signed base quantity the risk manager had reserved for each pending
+    /// order, empty when no risk limits were configured
+    #[serde(default)]
+    pub risk_reservations: HashMap<Uuid, f64>,
+    /// Net signed quantity the risk manager tallied per symbol on top of
+    /// `risk_reservations`, i.e. what had already filled
+    #[serde(default)]
+    pub settled_exposure: HashMap<String, f64>,
+    /// Limits the risk manager enforced, `None` when none were configured
+    #[serde(default)]
+    pub risk_limits: Option<RiskLimits>,
+}
+
+/// Number of recent nonces remembered per signer before the oldest is
+/// folded into [`NonceWindow::floor`]
+pub const NONCE_WINDOW: usize = 10_000;
+
+/// Nonces a signer has already used
+///
+/// The latest [`NONCE_WINDOW`] nonces are kept exactly, so orders may
+/// arrive somewhat out of order; anything at or below `floor`, the newest
+/// nonce dropped from the window, is rejected outright.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NonceWindow {
+    pub floor: Option<u64>,
+    pub recent: BTreeSet<u64>,
+}
+
+impl NonceWindow {
+    /// Whether `nonce` was used, or is too old to tell
+    pub fn contains(&self, nonce: u64) -> bool {
+        self.floor.is_some_and(|floor| nonce <= floor) || self.recent.contains(&nonce)
+    }
+
+    /// Record `nonce`, returning `false` if [`contains`](Self::contains) it
+    pub fn insert(&mut self, nonce: u64) -> bool {
+        if self.contains(nonce) {
+            return false;
+        }
+        self.recent.insert(nonce);
+        if self.recent.len() > NONCE_WINDOW {
+            self.floor = self.recent.pop_first();
+        }
+        true
+    }
+}
+
+/// Require M-of-N approval signatures for orders above a notional size
+#[derive(Clone)]
+pub struct MultiSigPolicy {
+    pub keyring: KeyRing,
+    pub threshold: usize,
+    /// Orders with a notional at or above this need approval
+    pub min_notional: f64,
+}
+
+/// How [`ExecutionEngine::execute_basket`] treats a failing leg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Atomicity {
+    /// Unwind the accepted legs if any leg fails
+    AllOrNone,
+    /// Submit each leg independently
+    BestEffort,
+}
+
+/// Whether the engine routes orders to a venue or only simulates them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EngineMode {
+    Live,
+    Paper,
+}
+
+/// Admission state for [`ExecutionEngine::with_max_pending`]
+#[derive(Debug, Default)]
+struct PendingCap {
+    /// Zero for no cap
+    max: usize,
+    /// Orders admitted but not yet in the tracked set
+    admitting: AtomicUsize,
+    /// Signalled when a slot may have freed up
+    freed: Notify,
+    /// Orders waiting in `reserve_pending`, by urgency rank
+    waiting: [AtomicUsize; 3],
+}
+
+/// Smallest size at which a [`SweptMap`] is swept
+const SWEEP_MIN: usize = 64;
+
+/// Map swept of its dead entries whenever it has doubled since the last
+/// sweep, so it stays proportional to its live entries at amortized
+/// constant cost
+struct SweptMap<K, V> {
+    map: HashMap<K, V>,
+    sweep_at: usize,
+}
+
+impl<K: Hash + Eq, V> SweptMap<K, V> {
+    fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+            sweep_at: SWEEP_MIN,
+        }
+    }
+
+    /// Keep only the entries for which `live` holds, if the map is due a
+    /// sweep
+    fn sweep(&mut self, live: impl FnMut(&K, &mut V) -> bool) {
+        if self.map.len() >= self.sweep_at {
+            self.map.retain(live);
+            self.sweep_at = (self.map.len() * 2).max(SWEEP_MIN);
+        }
+    }
+}
+
+/// An order's place among those waiting for a pending slot
+struct PendingWaiter<'a> {
+    cap: &'a PendingCap,
+    rank: usize,
+}
+
+impl Drop for PendingWaiter<'_> {
+    fn drop(&mut self) {
+        self.cap.waiting[self.rank].fetch_sub(1, Ordering::SeqCst);
+        // Lower-urgency waiters may have been held back for this one
+        self.cap.freed.notify_waiters();
+    }
+}
+
+/// One admitted order's place under the pending cap, held until the order is
+/// tracked (or fails pre-trade checks)
+struct PendingSlot(Arc<PendingCap>);
+
+impl Drop for PendingSlot {
+    fn drop(&mut self) {
+        self.0.admitting.fetch_sub(1, Ordering::SeqCst);
+        self.0.freed.notify_waiters();
+    }
+}
+
+/// Which pre-trade checks an order goes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    /// A standalone order: every check, reserving risk and recording the
+    /// nonce
+    Order,
+    /// An order about to be worked in slices: every check, but risk is only
+    /// checked, since each slice reserves its own share as it is sent
+    Parent,
+    /// A slice of an admitted parent: the checks that depend on its own
+    /// quantity; the signer, nonce, signal age and approvals were checked on
+    /// the parent
+    Slice,
+    /// A simulated order: every check, but risk is only checked and the
+    /// nonce is not recorded
+    DryRun,
+}
+
+/// Executes orders against the configured venue
+///
+/// Cloning is cheap and clones share all state (risk tally, rate limiter).
+#[derive(Clone)]
+pub struct ExecutionEngine {
+    signing_key: SigningKey,
+    mode: EngineMode,
+    adapter: Option<Arc<dyn ExchangeAdapter>>,
+    simulator: Arc<SimulatedExchange>,
+    risk: Option<Arc<RiskManager>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    orders: Arc<Mutex<LruMap<Uuid, TrackedOrder>>>,
+    /// Split orders, with the aggregate of their slices' results; kept apart
+    /// from `orders` since the venue only knows the slices
+    parents: Arc<Mutex<LruMap<Uuid, TrackedOrder>>>,
+    multisig: Option<Arc<MultiSigPolicy>>,
+    seen_nonces: Arc<Mutex<HashMap<String, NonceWindow>>>,
+    /// Claims nonces across replicas, on top of `seen_nonces`
+    shared_nonces: Option<SignalManager>,
+    events: EventBus,
+    signing_scheme: SigningScheme,
+    symbol_rules: Arc<HashMap<String, SymbolRules>>,
+    halted: Arc<AtomicBool>,
+    positions: Arc<Mutex<HashMap<String, Position>>>,
+    min_flip_interval: Duration,
+    /// Side and time of each symbol's last fill, while it can still
+    /// throttle a flip
+    last_fills: Arc<Mutex<SweptMap<String, (OrderSide, Instant)>>>,
+    decision_verifier: Option<Arc<Verifier>>,
+    order_verifier: Option<Arc<Verifier>>,
+    store: Option<Arc<dyn Store>>,
+    watchers: Arc<Mutex<SweptMap<Uuid, Vec<oneshot::Sender<OrderResult>>>>>,
+    price_source: Option<Arc<dyn PriceSource>>,
+    price_band: Option<PriceBand>,
+    clock: Arc<dyn Clock>,
+    dead_letters: Option<Arc<dyn DeadLetterSink>>,
+    reject_marketable_limits: bool,
+    metrics: Arc<EngineMetrics>,
+    max_signal_age: Option<Duration>,
+    audit: Option<Arc<dyn AuditSink>>,
+    validation_rules: Arc<Vec<Arc<dyn ValidationRule>>>,
+    alerts: Arc<AlertQueue>,
+    pending: Arc<PendingCap>,
+    fee_model: Option<Arc<dyn FeeModel>>,
+    id_generator: Arc<dyn OrderIdGenerator>,
+}
+
+impl ExecutionEngine {
+    /// Create a live engine
+    ///
+    /// Until a venue is configured with [`ExecutionEngine::with_adapter`],
+    /// live orders are routed to the built-in simulator.
+    pub fn new(signing_key: SigningKey) -> Self {
+        Self {
+            signing_key,
+            mode: EngineMode::Live,
+            adapter: None,
+            simulator: Arc::new(SimulatedExchange::new()),
+            risk: None,
+            rate_limiter: None,
+            orders: Arc::new(Mutex::new(new_order_cache(DEFAULT_ORDER_CACHE_CAPACITY))),
+            parents: Arc::new(Mutex::new(LruMap::new(
+                DEFAULT_ORDER_CACHE_CAPACITY,
+                |_| true,
+            ))),
+            multisig: None,
+            seen_nonces: Arc::new(Mutex::new(HashMap::new())),
+            shared_nonces: None,
+            events: EventBus::default(),
+            signing_scheme: SigningScheme::default(),
+            symbol_rules: Arc::new(HashMap::new()),
+            halted: Arc::new(AtomicBool::new(false)),
+            positions: Arc::new(Mutex::new(HashMap::new())),
+            min_flip_interval: Duration::ZERO,
+            last_fills: Arc::new(Mutex::new(SweptMap::new())),
+            decision_verifier: None,
+            order_verifier: None,
+            store: None,
+            watchers: Arc::new(Mutex::new(SweptMap::new())),
+            price_source: None,
+            price_band: None,
+            clock: Arc::new(SystemClock),
+            dead_letters: None,
+            reject_marketable_limits: false,
+            metrics: Arc::new(EngineMetrics::default()),
+            max_signal_age: None,
+            audit: None,
+            validation_rules: Arc::new(default_rules().into_iter().map(Arc::from).collect()),
+            alerts: Arc::new(AlertQueue::new(
+                Arc::new(NoopAlertSink),
+                ALERT_QUEUE_CAPACITY,
+            )),
+            pending: Arc::new(PendingCap::default()),
+            fee_model: None,
+            id_generator: Arc::new(UuidGenerator),
+        }
+    }
+
+    /// Create a paper-trading engine that never touches a real adapter
+    pub fn new_paper(signing_key: SigningKey) -> Self {
+        Self {
+            mode: EngineMode::Paper,
+            ..Self::new(signing_key)
+        }
+    }
+
+    /// Run `rule` after the validation rules already configured
+    pub fn with_validation_rule(mut self, rule: impl ValidationRule + 'static) -> Self {
+        Arc::make_mut(&mut self.validation_rules).push(Arc::new(rule));
+        self
+    }
+
+    /// Replace the validation rules, including the built-in ones from
+    /// [`default_rules`], with `rules`
+    pub fn with_validation_rules(mut self, rules: Vec<Box<dyn ValidationRule>>) -> Self {
+        self.validation_rules = Arc::new(rules.into_iter().map(Arc::from).collect());
+        self
+    }
+
+    /// Reject orders whose `source_timestamp` is more than `max_age` behind
+    /// the engine's clock. Orders without a source timestamp are unaffected.
+    pub fn with_max_signal_age(mut self, max_age: Duration) -> Self {
+        self.max_signal_age = Some(max_age);
+        self
+    }
+
+    /// Reject limit orders priced through the market price (a buy above it
+    /// or a sell below it) as probable mistakes. Off by default.
+    ///
+    /// The market price comes from the price source (see
+    /// [`with_price_source`](Self::with_price_source)); without one, or when
+    /// it has no price for the symbol, the check is skipped.
+    pub fn with_reject_marketable_limits(mut self, reject: bool) -> Self {
+        self.reject_marketable_limits = reject;
+        self
+    }
+
+    /// Alert `sink` when an order fails or is rejected
+    ///
+    /// Alerts are delivered one at a time from a bounded queue (see
+    /// [`AlertSink`]).
+    pub fn with_alert_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.alerts = Arc::new(AlertQueue::new(sink, ALERT_QUEUE_CAPACITY));
+        self
+    }
+
+    /// Record every signature the engine produces to `sink`
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    /// Write orders the store fails to persist to `sink` instead of dropping them
+    pub fn with_dead_letter_sink(mut self, sink: Arc<dyn DeadLetterSink>) -> Self {
+        self.dead_letters = Some(sink);
+        self
+    }
+
+    /// Timestamp events and results from `clock` instead of the system clock
+    ///
+    /// Results from the built-in simulator use it too.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.simulator = Arc::new((*self.simulator).clone().with_clock(clock.clone()));
+        self.clock = clock;
+        self
+    }
+
+    /// Route live orders to the given venue adapter
+    pub fn with_adapter(mut self, adapter: Arc<dyn ExchangeAdapter>) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Replace the simulator used for paper trading (and for live trading
+    /// when no adapter is configured)
+    ///
+    /// The simulator is switched to the engine's clock.
+    pub fn with_simulator(mut self, simulator: SimulatedExchange) -> Self {
+        self.simulator = Arc::new(simulator.with_clock(self.clock.clone()));
+        self
+    }
+
+    /// Reject orders that reverse the side of a fill on the same symbol
+    /// within `interval`; zero (the default) disables the check
+    pub fn with_min_flip_interval(mut self, interval: Duration) -> Self {
+        self.min_flip_interval = interval;
+        self
+    }
+
+    /// Charge fills through `model`, recording the fee on each result and
+    /// in the symbol's [`Position`]
+    pub fn with_fee_model(mut self, model: Arc<dyn FeeModel>) -> Self {
+        self.fee_model = Some(model);
+        self
+    }
+
+    /// Assign client order ids with `generator` instead of [`UuidGenerator`]
+    pub fn with_order_id_generator(mut self, generator: Arc<dyn OrderIdGenerator>) -> Self {
+        self.id_generator = generator;
+        self
+    }
+
+    /// Persist every order result to `store`
+    pub fn with_store(mut self, store: Arc<dyn Store>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Require decisions passed to [`ExecutionEngine::execute_with_decision`]
+    /// to be signed by one of the verifier's keys
+    pub fn with_decision_verifier(mut self, verifier: Verifier) -> Self {
+        self.decision_verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Require orders to be signed by one of the verifier's keys
+    ///
+    /// Nonces are then checked per signing key. Without a verifier orders
+    /// are not authenticated and all nonces share the engine's key id.
+    pub fn with_order_verifier(mut self, verifier: Verifier) -> Self {
+        self.order_verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Value and (when simulated) fill market orders at prices from `source`
+    pub fn with_price_source(mut self, source: Arc<dyn PriceSource>) -> Self {
+        self.simulator = Arc::new((*self.simulator).clone().with_price_source(source.clone()));
+        self.price_source = Some(source);
+        self
+    }
+
+    /// Reject limit orders priced outside `band` around the price source's
+    /// price (see [`ExecutionEngine::with_price_source`])
+    ///
+    /// Orders are rejected with the source's error when it cannot give a
+    /// price, unless the band is [`fail_open`](PriceBand::fail_open).
+    pub fn with_price_band(mut self, band: PriceBand) -> Self {
+        self.price_band = Some(band);
+        self
+    }
+
+    /// Enforce pre-trade risk limits on every order
+    ///
+    /// Replacing earlier limits, such as those restored from a snapshot,
+    /// keeps the orders and exposure already tallied.
+    pub fn with_risk_limits(mut self, limits: RiskLimits) -> Self {
+        let risk = match &self.risk {
+            Some(previous) => previous.with_limits(limits),
+            None => RiskManager::new(limits),
+        };
+        self.risk = Some(Arc::new(risk));
+        self
+    }
+
+    pub fn risk_manager(&self) -> Option<&RiskManager> {
+        self.risk.as_deref()
+    }
+
+    /// Refuse new orders while `max` orders are unresolved: in flight, timed
+    /// out with an unknown outcome, or pending or partially filled at the
+    /// venue
+    ///
+    /// Over the cap, [`execute_order`](Self::execute_order) and the other
+    /// entry points fail with [`ExecutionError::PendingCapacity`] (or, for
+    /// [`submit_or_reject`](Self::submit_or_reject), a `Rejected` result);
+    /// [`execute_order_queued`](Self::execute_order_queued) waits for a slot
+    /// instead. Zero (the default) means no cap.
+    pub fn with_max_pending(mut self, max: usize) -> Self {
+        self.pending = Arc::new(PendingCap {
+            max,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Keep at most `capacity` orders in memory (default
+    /// [`DEFAULT_ORDER_CACHE_CAPACITY`]); zero means no bound
+    ///
+    /// Past the capacity, the orders with a terminal result that were least
+    /// recently submitted or updated are forgotten. Unresolved orders are
+    /// never dropped, so the cache can exceed its capacity while they are
+    /// outstanding. A forgotten order is no longer reported by
+    /// [`tracked_order`](Self::tracked_order), [`watch_order`](Self::watch_order)
+    /// or [`cancel_remaining`](Self::cancel_remaining), and a late venue
+    /// update for it via [`reconcile`](Self::reconcile) is not applied to
+    /// positions; look such orders up in the configured store instead.
+    /// Replay protection is unaffected: seen nonces are kept separately.
+    pub fn with_order_cache_capacity(self, capacity: usize) -> Self {
+        let capacity = if capacity == 0 { usize::MAX } else { capacity };
+        self.orders.lock().unwrap().set_capacity(capacity);
+        self.parents.lock().unwrap().set_capacity(capacity);
+        self
+    }
+
+    /// Throttle order submission through a (possibly shared) rate limiter
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Also claim each order's nonce in Redis, so replicas sharing it reject
+    /// each other's replays
+    ///
+    /// See [`SignalManager::reserve_nonce`]. An order whose claim fails
+    /// because Redis is unreachable is rejected.
+    pub fn with_shared_nonces(mut self, signals: SignalManager) -> Self {
+        self.shared_nonces = Some(signals);
+        self
+    }
+
+    /// Require approval signatures on high-value orders
+    pub fn with_multisig_policy(mut self, policy: MultiSigPolicy) -> Self {
+        self.multisig = Some(Arc::new(policy));
+        self
+    }
+
+    /// Receive order lifecycle events from this engine and its clones
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<OrderEvent> {
+        self.events.subscribe()
+    }
+
+    /// Choose the encoding the engine signs orders with
+    pub fn with_signing_scheme(mut self, scheme: SigningScheme) -> Self {
+        self.signing_scheme = scheme;
+        self
+    }
+
+    /// Encoding the engine signs orders with
+    pub fn signing_scheme(&self) -> SigningScheme {
+        self.signing_scheme
+    }
+
+    /// Apply venue rules (lot size and tick size rounding) to orders on
+    /// `symbol`
+    pub fn with_symbol_rules(mut self, symbol: impl Into<String>, rules: SymbolRules) -> Self {
+        Arc::make_mut(&mut self.symbol_rules).insert(symbol.into(), rules);
+        self
+    }
+
+    pub fn symbol_rules(&self, symbol: &str) -> Option<&SymbolRules> {
+        self.symbol_rules.get(symbol)
+    }
+
+    pub fn mode(&self) -> EngineMode {
+        self.mode
+    }
+
+    /// Whether orders are only simulated
+    pub fn is_paper(&self) -> bool {
+        self.mode == EngineMode::Paper
+    }
+
+    /// Reject every new order until [`ExecutionEngine::resume`] is called
+    ///
+    /// Orders already past the check run to completion. The flag is a
+    /// single atomic store, but the call also logs, so it is not safe in a
+    /// raw signal handler; call it from a task awaiting `tokio::signal`.
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::SeqCst);
+        tracing::warn!("Execution engine halted");
+    }
+
+    pub fn resume(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+        tracing::info!("Execution engine resumed");
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    /// Halt the engine and wait up to `timeout` for in-flight orders to resolve
+    ///
+    /// Returns how many orders were still unresolved when the wait ended;
+    /// their ids remain available from [`unresolved_orders`](Self::unresolved_orders)
+    /// on any clone for later reconciliation.
+    pub async fn shutdown(self, timeout: Duration) -> Result<usize> {
+        self.halt();
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let pending = self.unresolved_orders().len();
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if pending == 0 || remaining.is_zero() {
+                if pending > 0 {
+                    tracing::warn!("Shutting down with {} unresolved orders", pending);
+                }
+                return Ok(pending);
+            }
+            tokio::time::sleep(SHUTDOWN_POLL_INTERVAL.min(remaining)).await;
+        }
+    }
+
+    /// Execute an order, waiting for the rate limiter if necessary
+    ///
+    /// An order larger than its symbol's
+    /// [`max_order_qty`](SymbolRules::max_order_qty) is split with
+    /// [`Order::split_by_max`] and the slices submitted in turn; the returned
+    /// result then aggregates the slices under the parent order's id.
+    ///
+    /// A split order's signature, nonce, signal age, approvals and risk
+    /// limits are checked once, on the parent. Each slice is signed by the
+    /// engine and reserves its own share of risk as it is sent.
+    pub async fn execute_order(&self, order: Order) -> Result<OrderResult> {
+        if let Some(max_qty) = self.max_slice(&order).await? {
+            if order.quantity > max_qty {
+                return self.execute_split(order, max_qty).await;
+            }
+        }
+
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire_with(order.urgency).await;
+        }
+        self.process_order(order, None).await
+    }
+
+    /// Execute an order on the strength of a signed decision
+    ///
+    /// The decision must reference this order's id and be signed by one of
+    /// the keys of the engine's decision verifier (see
+    /// [`with_decision_verifier`](Self::with_decision_verifier)). Without a
+    /// verifier no signature can be trusted, so every decision is rejected.
+    pub async fn execute_with_decision(
+        &self,
+        order: Order,
+        decision: &Decision,
+    ) -> Result<OrderResult> {
+        if decision.order_id != order.id {
+            return Err(ExecutionError::Validation(format!(
+                "decision {} references order {}, not {}",
+                decision.decision_id, decision.order_id, order.id
+            ))
+            .into());
+        }
+        let verifier = self
+            .decision_verifier
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("no decision verifier is configured".to_string()))?;
+        verifier.verify_decision(decision)?;
+        self.execute_order(order).await
+    }
+
+    /// Execute an order, giving up on the venue call after `timeout`
+    ///
+    /// On timeout this returns an error, but the order may still have reached
+    /// the exchange and been filled. It stays tracked with an unknown result
+    /// (see [`ExecutionEngine::unresolved_orders`]) until reconciled.
+    pub async fn execute_order_timeout(
+        &self,
+        order: Order,
+        timeout: Duration,
+    ) -> Result<OrderResult> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire_with(order.urgency).await;
+        }
+        self.process_order(order, Some(timeout)).await
+    }
+
+    /// Execute an order, first waiting for a slot under the
+    /// [pending cap](Self::with_max_pending) if it is full
+    ///
+    /// Unlike [`execute_order`](Self::execute_order), orders are not split
+    /// by [`max_order_qty`](SymbolRules::max_order_qty).
+    pub async fn execute_order_queued(&self, order: Order) -> Result<OrderResult> {
+        let slot = self.reserve_pending(order.urgency).await;
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire_with(order.urgency).await;
+        }
+        self.process_reserved(order, None, slot).await
+    }
+
+    /// Execute an order, failing immediately if the rate limiter has no token
+    pub async fn try_execute_order(&self, order: Order) -> Result<OrderResult> {
+        if let Some(limiter) = &self.rate_limiter {
+            if !limiter.try_acquire_with(order.urgency) {
+                return Err(ExecutionError::RateLimited.into());
+            }
+        }
+        self.process_order(order, None).await
+    }
+
+    /// Work an order as a sequence of child slices of `slice_qty`
+    ///
+    /// The parent goes through the pre-trade checks once, as a whole (see
+    /// [`execute_order`](Self::execute_order)); slices (see
+    /// [`Order::child`]) are then submitted one at a time, `interval` apart,
+    /// until the parent quantity is used up. If a slice is rejected or
+    /// fails, no further slices are sent and the results so far are
+    /// returned, ending with the unsuccessful one.
+    pub async fn execute_iceberg(
+        &self,
+        order: Order,
+        slice_qty: f64,
+        interval: Duration,
+    ) -> Result<Vec<OrderResult>> {
+        if !slice_qty.is_finite() || slice_qty <= 0.0 {
+            return Err(
+                ExecutionError::Validation("Slice quantity must be positive".to_string()).into(),
+            );
+        }
+        let started = Instant::now();
+        let order = self.admit_parent(order).await?;
+
+        let quantities = slice_quantities(order.quantity, slice_qty);
+        let count = quantities.len();
+        let mut results = Vec::new();
+        for quantity in quantities {
+            if !results.is_empty() {
+                tokio::time::sleep(interval).await;
+            }
+
+            let result = self.submit_slice(order.make_child(quantity)).await;
+            let succeeded = result.is_accepted();
+            results.push(result);
+            if !succeeded {
+                break;
+            }
+        }
+
+        self.track_parent(order, &results, count, started);
+        Ok(results)
+    }
+
+    /// Work a market order as `num_slices` equal child orders spread evenly
+    /// over `total_duration`
+    ///
+    /// The first slice is sent immediately and the rest at fixed offsets from
+    /// the start, so slow submissions do not push the schedule back. The last
+    /// slice absorbs any rounding remainder. As with
+    /// [`execute_iceberg`](Self::execute_iceberg), the parent is checked once
+    /// up front and a rejected or failed slice ends the schedule. Returns the
+    /// slice results with the achieved VWAP across their fills.
+    pub async fn execute_twap(
+        &self,
+        order: Order,
+        num_slices: usize,
+        total_duration: Duration,
+    ) -> Result<TwapResult> {
+        if num_slices == 0 {
+            return Err(
+                ExecutionError::Validation("TWAP needs at least one slice".to_string()).into(),
+            );
+        }
+        if order.order_type.is_limit() {
+            return Err(ExecutionError::Validation(
+                "TWAP orders must be market orders".to_string(),
+            )
+            .into());
+        }
+        let started = Instant::now();
+        let order = self.admit_parent(order).await?;
+
+        let mut quantities = vec![order.quantity / num_slices as f64; num_slices];
+        absorb_residual(order.quantity, &mut quantities);
+        let interval = total_duration / num_slices as u32;
+        let start = tokio::time::Instant::now();
+        let mut results = Vec::with_capacity(num_slices);
+
+        for (i, quantity) in quantities.into_iter().enumerate() {
+            tokio::time::sleep_until(start + interval * i as u32).await;
+
+            let result = self.submit_slice(order.make_child(quantity)).await;
+            let succeeded = result.is_accepted();
+            results.push(result);
+            if !succeeded {
+                break;
+            }
+        }
+
+        self.track_parent(order, &results, num_slices, started);
+        Ok(TwapResult {
+            vwap: vwap(&results),
+            slices: results,
+        })
+    }
+
+    /// Submit `order` as slices of at most `max_qty`, stopping at the first
+    /// slice that is rejected or fails
+    async fn execute_split(&self, order: Order, max_qty: f64) -> Result<OrderResult> {
+        let started = Instant::now();
+        let order = self.admit_parent(order).await?;
+
+        let children = order.split_by_max(max_qty);
+        let count = children.len();
+        let mut results = Vec::with_capacity(count);
+        for child in children {
+            let result = self.submit_slice(child).await;
+            let succeeded = result.is_accepted();
+            results.push(result);
+            if !succeeded {
+                break;
+            }
+        }
+
+        Ok(self.track_parent(order, &results, count, started))
+    }
+
+    /// Run an order that will be worked in slices through the pre-trade
+    /// checks as a whole
+    async fn admit_parent(&self, order: Order) -> Result<Order> {
+        let id = order.id;
+        let correlation_id = order.correlation_id.clone();
+        self.pre_trade(order, Admission::Parent)
+            .await
+            .inspect_err(|e| self.record_rejection(id, correlation_id, e))
+    }
+
+    /// Track a parent order with the aggregate of its slices' `results`,
+    /// out of `count` slices planned, and return the aggregate
+    fn track_parent(
+        &self,
+        order: Order,
+        results: &[OrderResult],
+        count: usize,
+        started: Instant,
+    ) -> OrderResult {
+        let result = self.aggregate_slices(&order, results, count, started);
+        self.parents.lock().unwrap().insert(
+            order.id,
+            TrackedOrder {
+                order,
+                result: Some(result.clone()),
+            },
+        );
+        result
+    }
+
+    fn aggregate_slices(
+        &self,
+        order: &Order,
+        results: &[OrderResult],
+        count: usize,
+        started: Instant,
+    ) -> OrderResult {
+        let filled: f64 = results.iter().filter_map(|r| r.executed_quantity).sum();
+        let all_executed =
+            results.len() == count && results.iter().all(|r| r.status == OrderStatus::Executed);
+        let status = match results.last() {
+            _ if all_executed => OrderStatus::Executed,
+            _ if filled > 0.0 => OrderStatus::PartiallyFilled,
+            Some(last) => last.status,
+            None => OrderStatus::Pending,
+        };
+
+        // Stage times add up across slices; the total is wall-clock time
+        let stages =
+            results
+                .iter()
+                .filter_map(|r| r.timings)
+                .fold(ExecutionTimings::default(), |sum, t| ExecutionTimings {
+                    validate_us: sum.validate_us + t.validate_us,
+                    sign_us: sum.sign_us + t.sign_us,
+                    submit_us: sum.submit_us + t.submit_us,
+                    total_us: 0,
+                });
+        let timings = ExecutionTimings {
+            total_us: micros(started.elapsed()),
+            ..stages
+        };
+
+        OrderResult {
+            order_id: order.id,
+            status,
+            execution_price: vwap(results),
+            executed_quantity: (filled > 0.0).then_some(filled),
+            timestamp: self.clock.now(),
+            message: if all_executed {
+                Some(format!("Executed in {} slices", results.len()))
+            } else {
+                results.last().and_then(|last| last.message.clone())
+            },
+            timings: Some(timings),
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: results
+                .iter()
+                .filter_map(|r| r.fee)
+                .reduce(|sum, fee| sum + fee),
+        }
+    }
+
+    /// Submit a group of orders, optionally as all-or-none
+    ///
+    /// Legs are submitted in order and one result is returned per leg. With
+    /// [`Atomicity::AllOrNone`] every leg is validated up front; if a leg is
+    /// then rejected or fails, the remaining legs are not sent (their results
+    /// are `Rejected`) and the legs already accepted are unwound: open ones
+    /// are cancelled and any filled quantity is offset with an opposite-side
+    /// market order.
+    ///
+    /// This is compensation, not a transaction. Offsetting orders trade at
+    /// the then-current price and go through the normal pre-trade checks, so
+    /// a risk limit or [`ExecutionEngine::with_min_flip_interval`] can block
+    /// them; failures to unwind are logged. True atomicity needs venue
+    /// support for contingent orders.
+    pub async fn execute_basket(
+        &self,
+        orders: Vec<Order>,
+        atomicity: Atomicity,
+    ) -> Result<Vec<OrderResult>> {
+        if atomicity == Atomicity::AllOrNone {
+            for order in &orders {
+                self.validate_order(order)?;
+            }
+        }
+
+        let mut results = Vec::with_capacity(orders.len());
+        let mut accepted = Vec::new();
+        let mut aborted = false;
+        for order in orders {
+            if aborted {
+                results.push(OrderResult {
+                    order_id: order.id,
+                    status: OrderStatus::Rejected,
+                    execution_price: None,
+                    executed_quantity: None,
+                    timestamp: self.clock.now(),
+                    message: Some("basket aborted: an earlier leg failed".to_string()),
+                    timings: None,
+                    book_snapshot: None,
+                    exchange_order_id: None,
+                    fee: None,
+                });
+                continue;
+            }
+
+            let leg = order.clone();
+            let result = self.submit_recorded(order, Admission::Order).await;
+            if result.is_accepted() {
+                accepted.push((leg, result.clone()));
+            } else if atomicity == Atomicity::AllOrNone {
+                aborted = true;
+            }
+            results.push(result);
+        }
+
+        if aborted {
+            for (leg, result) in accepted {
+                self.unwind_leg(&leg, &result).await;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Cancel what is still open of a basket leg and offset what filled
+    async fn unwind_leg(&self, leg: &Order, result: &OrderResult) {
+        let mut filled = result.executed_quantity.unwrap_or(0.0);
+        if !result.is_terminal() {
+            match self.cancel_order(leg.id).await {
+                Ok(cancelled) => filled = cancelled.executed_quantity.unwrap_or(filled),
+                Err(e) => tracing::error!("Failed to cancel basket leg {}: {}", leg.id, e),
+            }
+        }
+        if filled <= 0.0 {
+            return;
+        }
+
+        let offset = Order::new_at(
+            leg.symbol.clone(),
+            leg.side.opposite(),
+            OrderType::Market,
+            filled,
+            self.clock.now(),
+        );
+        let result = self.submit_recorded(offset, Admission::Order).await;
+        if !result.is_accepted() {
+            tracing::error!(
+                "Failed to offset basket leg {}: {}",
+                leg.id,
+                result.message.unwrap_or_default()
+            );
+        }
+    }
+
+    /// Submit one child slice of an admitted parent, recording a venue error
+    /// as a `Failed` result
+    async fn submit_slice(&self, mut child: Order) -> OrderResult {
+        child.timestamp = self.clock.now();
+        self.submit_recorded(child, Admission::Slice).await
+    }
+
+    /// [`ExecutionEngine::submit_or_reject`], recording a venue error as a
+    /// `Failed` result
+    async fn submit_recorded(&self, order: Order, admission: Admission) -> OrderResult {
+        let id = order.id;
+        match self.submit_admission(order, admission).await {
+            Ok(result) => result,
+            Err(e) => OrderResult {
+                order_id: id,
+                status: OrderStatus::Failed,
+                execution_price: None,
+                executed_quantity: None,
+                timestamp: self.clock.now(),
+                message: Some(e.to_string()),
+                timings: None,
+                book_snapshot: None,
+                exchange_order_id: None,
+                fee: None,
+            },
+        }
+    }
+
+    async fn process_order(&self, order: Order, timeout: Option<Duration>) -> Result<OrderResult> {
+        let slot = match self.try_reserve_pending(order.urgency) {
+            Ok(slot) => slot,
+            Err(e) => {
+                self.record_rejection(order.id, order.correlation_id.clone(), &e);
+                return Err(e);
+            }
+        };
+        self.process_reserved(order, timeout, slot).await
+    }
+
+    /// [`process_order`](Self::process_order) for an order already admitted
+    /// under the pending cap
+    async fn process_reserved(
+        &self,
+        order: Order,
+        timeout: Option<Duration>,
+        slot: Option<PendingSlot>,
+    ) -> Result<OrderResult> {
+        let span = order_span(&order);
+        async move {
+            let started = Instant::now();
+            let id = order.id;
+            let correlation_id = order.correlation_id.clone();
+            let order = match self.pre_trade(order, Admission::Order).await {
+                Ok(order) => order,
+                Err(e) => {
+                    self.record_rejection(id, correlation_id, &e);
+                    return Err(e);
+                }
+            };
+            self.dispatch(order, timeout, started, slot).await
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Execute an order, turning pre-trade failures into a `Rejected` result
+    ///
+    /// Validation, risk and replay checks that fail produce `Ok` with
+    /// [`OrderStatus::Rejected`] and the reason in `message`, so callers can
+    /// tell "never submitted" apart from a venue failure (which is still an
+    /// `Err`, recorded as `Failed`).
+    pub async fn submit_or_reject(&self, order: Order) -> Result<OrderResult> {
+        self.submit_admission(order, Admission::Order).await
+    }
+
+    async fn submit_admission(&self, order: Order, admission: Admission) -> Result<OrderResult> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire_with(order.urgency).await;
+        }
+
+        let span = order_span(&order);
+        let id = order.id;
+        let correlation_id = order.correlation_id.clone();
+        let started = Instant::now();
+        let admitted = match self.try_reserve_pending(order.urgency) {
+            Ok(slot) => self
+                .pre_trade(order, admission)
+                .instrument(span.clone())
+                .await
+                .map(|order| (order, slot)),
+            Err(e) => Err(e),
+        };
+        match admitted {
+            Ok((order, slot)) => {
+                self.dispatch(order, None, started, slot)
+                    .instrument(span)
+                    .await
+            }
+            Err(e) => {
+                let reason = match &e {
+                    Error::Execution(inner) => inner.to_string(),
+                    other => other.to_string(),
+                };
+                span.in_scope(|| tracing::info!("Order {} rejected: {}", id, reason));
+                self.record_rejection(id, correlation_id, &e);
+                let result = OrderResult {
+                    order_id: id,
+                    status: OrderStatus::Rejected,
+                    execution_price: None,
+                    executed_quantity: None,
+                    timestamp: self.clock.now(),
+                    message: Some(reason),
+                    timings: None,
+                    book_snapshot: None,
+                    exchange_order_id: None,
+                    fee: None,
+                };
+                // Hold the orders lock so a concurrent watch_order sees
+                // either no waiter registered yet or this notification
+                let _orders = self.orders.lock().unwrap();
+                self.notify_watchers(&result);
+                Ok(result)
+            }
+        }
+    }
+
+    /// Admit one order under the pending cap; `None` when there is no cap
+    fn try_reserve_pending(&self, urgency: Urgency) -> Result<Option<PendingSlot>> {
+        let cap = &self.pending;
+        if cap.max == 0 {
+            return Ok(None);
+        }
+
+        // Counted and incremented under the orders lock, which dispatch also
+        // holds while moving an order from admitting to tracked
+        let orders = self.orders.lock().unwrap();
+        let unresolved = orders.pinned();
+        // Waiting orders of higher urgency are owed a slot each
+        let owed: usize = cap.waiting[urgency.rank() + 1..]
+            .iter()
+            .map(|waiting| waiting.load(Ordering::SeqCst))
+            .sum();
+        if unresolved + cap.admitting.load(Ordering::SeqCst) + owed >= cap.max {
+            return Err(ExecutionError::PendingCapacity.into());
+        }
+        cap.admitting.fetch_add(1, Ordering::SeqCst);
+        Ok(Some(PendingSlot(cap.clone())))
+    }
+
+    /// Wait until an order can be admitted under the pending cap, ahead of
+    /// waiting orders of lower urgency
+    async fn reserve_pending(&self, urgency: Urgency) -> Option<PendingSlot> {
+        if let Ok(slot) = self.try_reserve_pending(urgency) {
+            return slot;
+        }
+        let rank = urgency.rank();
+        self.pending.waiting[rank].fetch_add(1, Ordering::SeqCst);
+        let _waiter = PendingWaiter {
+            cap: &self.pending,
+            rank,
+        };
+        loop {
+            // Registered before checking, so a slot freed in between still
+            // wakes this task
+            let freed = self.pending.freed.notified();
+            if let Ok(slot) = self.try_reserve_pending(urgency) {
+                return slot;
+            }
+            freed.await;
+        }
+    }
+
+    /// Dry-run an order through the pre-trade checks without executing it
+    ///
+    /// Runs the same pre-trade checks as [`execute_order`](Self::execute_order)
+    /// (lot and tick rounding, validation, the signal age, reduce-only,
+    /// price band, marketable limit, flip and multisig checks, replay
+    /// detection and the risk limits), failing with the same error, and
+    /// reports the quantity and price that would be sent. Nothing is
+    /// recorded: the nonce stays unused, no risk is reserved, no events are
+    /// published and no adapter is called, so the same order can still be
+    /// executed afterwards. The fill price is only an estimate from the
+    /// rounded limit price or the price source.
+    pub async fn simulate(&self, order: &Order) -> Result<SimulationReport> {
+        let requested = order.quantity;
+        let requested_price = order.order_type.limit_price();
+        let order = self.pre_trade(order.clone(), Admission::DryRun).await?;
+
+        let mut warnings = Vec::new();
+        if order.quantity != requested {
+            warnings.push(format!(
+                "quantity {} rounded to {}",
+                requested, order.quantity
+            ));
+        }
+        let rounded_price = order.order_type.limit_price();
+        if let (Some(price), Some(rounded)) = (requested_price, rounded_price) {
+            if rounded != price {
+                warnings.push(format!("limit price {} rounded to {}", price, rounded));
+            }
+        }
+        if let Some(max_qty) = self.max_slice(&order).await.ok().flatten() {
+            let slices = order.split_by_max(max_qty).len();
+            if slices > 1 {
+                warnings.push(format!(
+                    "would be split into {} slices of at most {}",
+                    slices, max_qty
+                ));
+            }
+        }
+
+        let estimated_fill_price = match rounded_price {
+            Some(price) => Some(price),
+            None => self.reference_price(&order).await.ok(),
+        };
+        if estimated_fill_price.is_none() {
+            warnings.push(format!("no market price for {}", order.symbol));
+        }
+        let estimated_fee = match (&self.fee_model, estimated_fill_price) {
+            (Some(model), Some(price)) => {
+                let quantity = order.order_quantity().base_at(price);
+                Some(model.fee(&order, price, quantity))
+            }
+            _ => None,
+        };
+
+        Ok(SimulationReport {
+            rounded_quantity: order.quantity,
+            rounded_price,
+            estimated_fee,
+            estimated_fill_price,
+            warnings,
+        })
+    }
+
+    /// Re-run stored orders through the simulator, for comparing the fresh
+    /// results against the stored ones
+    ///
+    /// Each record is rebuilt with `Order::try_from` and submitted, in
+    /// order, with the fee model applied, to a fresh [`SimulatedExchange`]
+    /// configured like this engine's simulator (market price, price source,
+    /// books, fill model and latency). Replay always uses a simulator, never
+    /// the live adapter, whatever the engine's mode, and skips pre-trade
+    /// checks and signing. The engine's own simulator keeps its results, and
+    /// nothing is tracked by the engine, stored, published or added to
+    /// positions, so it can run against a production engine's
+    /// configuration. A malformed record or a simulator error fails the
+    /// whole replay.
+    pub async fn replay(&self, records: Vec<OrderRecord>) -> Result<Vec<OrderResult>> {
+        let simulator = self.simulator.detached();
+        let mut results = Vec::with_capacity(records.len());
+        for record in records {
+            let order = Order::try_from(record)?;
+            let mut result = simulator.submit(&order).await?;
+            self.charge_fee(&order, &mut result);
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Encode an order as a FIX NewOrderSingle, as [`Order::to_fix`], with
+    /// SendingTime taken from this engine's clock
+    pub fn to_fix(&self, order: &Order, sender: &str, target: &str, seq: u32) -> Result<String> {
+        order.to_fix(sender, target, seq, self.clock.now())
+    }
+
+    /// Checks run before anything is sent to the venue
+    async fn pre_trade(&self, mut order: Order, admission: Admission) -> Result<Order> {
+        if self.is_halted() {
+            return Err(ExecutionError::Halted.into());
+        }
+
+        self.apply_lot_rounding(&mut order);
+        self.apply_tick_rounding(&mut order);
+        self.validate_order(&order)?;
+
+        if admission == Admission::Slice {
+            if let Some(risk) = &self.risk {
+                risk.reserve(&order, self.reference_price(&order).await?)?;
+            }
+            return Ok(order);
+        }
+
+        self.check_signal_age(&order)?;
+        self.check_reduce_only(&order).await?;
+        self.check_price_band(&order).await?;
+        self.check_marketable_limit(&order).await?;
+        self.check_flip(&order)?;
+
+        if let Some(policy) = &self.multisig {
+            let notional = order
+                .order_quantity()
+                .notional_at(self.reference_price(&order).await?);
+            if notional >= policy.min_notional {
+                order.verify_multisig(&policy.keyring, policy.threshold)?;
+            }
+        }
+
+        // Checked here to reject replays early, but only recorded once the
+        // order is admitted so a rejected order does not burn its nonce
+        let signer = self.order_signer(&order)?;
+        if self.nonce_seen(&signer, order.nonce) {
+            return Err(ExecutionError::Replay.into());
+        }
+
+        let reserved = match (&self.risk, admission) {
+            (Some(risk), Admission::Order) => {
+                risk.reserve(&order, self.reference_price(&order).await?)?;
+                true
+            }
+            (Some(risk), _) => {
+                risk.check(&order, self.reference_price(&order).await?)?;
+                false
+            }
+            (None, _) => false,
+        };
+        if admission == Admission::DryRun {
+            return Ok(order);
+        }
+
+        // Claimed across replicas first, so an unreachable Redis leaves
+        // nothing recorded and the order can be retried
+        let recorded = match self.reserve_shared_nonce(&signer, &order).await {
+            Ok(()) => self.record_nonce(&signer, &order),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            if let (Some(risk), true) = (&self.risk, reserved) {
+                risk.release(&order);
+            }
+            return Err(e);
+        }
+
+        Ok(order)
+    }
+
+    /// Sign, track and submit an order that passed pre-trade checks
+    ///
+    /// `started` is when pre-trade checks began, for the result's timings.
+    /// `slot` is released once the order is tracked.
+    async fn dispatch(
+        &self,
+        mut order: Order,
+        timeout: Option<Duration>,
+        started: Instant,
+        slot: Option<PendingSlot>,
+    ) -> Result<OrderResult> {
+        let validated = Instant::now();
+
+        if order.client_order_id.is_none() {
+            order.client_order_id = Some(self.id_generator.next_id());
+        }
+
+        // Sign the order
+        order.sign_with(&self.signing_key, self.signing_scheme)?;
+        let signed = Instant::now();
+        if let Some(audit) = &self.audit {
+            // Auditing must not fail the order
+            match AuditEvent::for_order(
+                &order,
+                self.signing_key.key_id(),
+                self.signing_scheme,
+                self.clock.now(),
+            ) {
+                Ok(event) => audit.record(event).await,
+                Err(e) => {
+                    tracing::error!("Failed to build audit event for order {}: {}", order.id, e)
+                }
+            }
+        }
+
+        tracing::info!("Executing order: {}", order);
+
+        {
+            let mut orders = self.orders.lock().unwrap();
+            orders.insert(
+                order.id,
+                TrackedOrder {
+                    order: order.clone(),
+                    result: None,
+                },
+            );
+            // Now counted as tracked, so stop counting it as admitting
+            drop(slot);
+        }
+
+        self.metrics.record_submitted();
+        self.events.publish(OrderEvent::Submitted {
+            id: order.id,
+            timestamp: self.clock.now(),
+            correlation_id: order.correlation_id.clone(),
+        });
+
+        let submitting = Instant::now();
+        let mut result = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.submit(&order)).await {
+                Ok(result) => result,
+                Err(_) => {
+                    // Outcome unknown: keep the order tracked and its risk reserved
+                    tracing::warn!("Order {} timed out after {:?}", order.id, timeout);
+                    return Err(ExecutionError::Timeout.into());
+                }
+            },
+            None => self.submit(&order).await,
+        };
+        let submitted = Instant::now();
+        let timings = ExecutionTimings {
+            validate_us: micros(validated - started),
+            sign_us: micros(signed - validated),
+            submit_us: micros(submitted - submitting),
+            total_us: micros(submitted - started),
+        };
+        if let Ok(result) = &mut result {
+            result.timings = Some(timings);
+            self.charge_fee(&order, result);
+        }
+
+        let recorded = match &result {
+            Ok(result) => result.clone(),
+            Err(e) => OrderResult {
+                order_id: order.id,
+                status: OrderStatus::Failed,
+                execution_price: None,
+                executed_quantity: None,
+                timestamp: self.clock.now(),
+                message: Some(e.to_string()),
+                timings: Some(timings),
+                book_snapshot: None,
+                exchange_order_id: None,
+                fee: None,
+            },
+        };
+        self.publish_result(&recorded, order.correlation_id.clone());
+        self.record_fill(&order, None, &recorded);
+        if let Some(mut tracked) = self.orders.lock().unwrap().get_mut(&order.id) {
+            tracked.result = Some(recorded.clone());
+            self.notify_watchers(&recorded);
+        }
+        self.persist(&order, &recorded).await;
+        self.settle_risk(&order, &recorded);
+
+        result
+    }
+
+    /// Settle the order's risk reservation once `result` is terminal,
+    /// keeping its executed quantity as exposure
+    fn settle_risk(&self, order: &Order, result: &OrderResult) {
+        if let Some(risk) = &self.risk {
+            if result.is_terminal() {
+                risk.settle(order, result.executed_quantity.unwrap_or(0.0));
+            }
+        }
+    }
+
+    /// Snap the quantity to the symbol's lot size, if it has one
+    ///
+    /// Lot sizes are in the base asset, so quote-sized orders are left as
+    /// they are.
+    fn apply_lot_rounding(&self, order: &mut Order) {
+        if order.quote_quantity {
+            return;
+        }
+        let Some(rules) = self.symbol_rules.get(&order.symbol) else {
+            return;
+        };
+        let Some(lot_size) = rules.lot_size else {
+            return;
+        };
+
+        let rounded = round_to_lot(order.quantity, lot_size, rules.rounding_for(order.side));
+        if rounded != order.quantity {
+            tracing::info!(
+                "Rounded order {} quantity {} to {} (lot size {})",
+                order.id,
+                order.quantity,
+                rounded,
+                lot_size
+            );
+            order.quantity = rounded;
+        }
+    }
+
+    /// Snap a limit price to the symbol's tick size, if it has one
+    ///
+    /// Buys round down and sells up, so the order is never priced through
+    /// the limit the caller asked for.
+    fn apply_tick_rounding(&self, order: &mut Order) {
+        let OrderType::Limit { price } = &mut order.order_type else {
+            return;
+        };
+        let Some(tick_size) = self
+            .symbol_rules
+            .get(&order.symbol)
+            .and_then(|rules| rules.tick_size)
+        else {
+            return;
+        };
+
+        let mode = match order.side {
+            OrderSide::Buy => RoundingMode::Down,
+            OrderSide::Sell => RoundingMode::Up,
+        };
+        let rounded = round_to_tick(*price, tick_size, mode);
+        if rounded != *price {
+            tracing::info!(
+                "Rounded order {} limit price {} to {} (tick size {})",
+                order.id,
+                price,
+                rounded,
+                tick_size
+            );
+            *price = rounded;
+        }
+    }
+
+    /// Key id that an order's nonce is checked against
+    ///
+    /// The verified signer when an order verifier is configured, otherwise
+    /// this engine's key.
+    fn order_signer(&self, order: &Order) -> Result<String> {
+        match &self.order_verifier {
+            Some(verifier) => verifier.verify_order(order),
+            None => Ok(self.signing_key.key_id()),
+        }
+    }
+
+    /// Record the order's nonce for `signer`, rejecting replays
+    ///
+    /// The timestamp alone cannot detect replays because of clock skew
+    /// between signers, so recent nonces are remembered (see
+    /// [`NonceWindow`]).
+    fn record_nonce(&self, signer: &str, order: &Order) -> Result<()> {
+        let mut seen = self.seen_nonces.lock().unwrap();
+        if !seen
+            .entry(signer.to_string())
+            .or_default()
+            .insert(order.nonce)
+        {
+            tracing::warn!(
+                "Replay detected for order {} (nonce {})",
+                order.id,
+                order.nonce
+            );
+            return Err(ExecutionError::Replay.into());
+        }
+        Ok(())
+    }
+
+    /// Claim the order's nonce for `signer` across replicas, if configured
+    async fn reserve_shared_nonce(&self, signer: &str, order: &Order) -> Result<()> {
+        let Some(signals) = &self.shared_nonces else {
+            return Ok(());
+        };
+        if !signals.clone().reserve_nonce(signer, order.nonce).await? {
+            tracing::warn!(
+                "Replay detected across replicas for order {} (nonce {})",
+                order.id,
+                order.nonce
+            );
+            return Err(ExecutionError::Replay.into());
+        }
+        Ok(())
+    }
+
+    /// Whether `signer` already used `nonce`
+    fn nonce_seen(&self, signer: &str, nonce: u64) -> bool {
+        self.seen_nonces
+            .lock()
+            .unwrap()
+            .get(signer)
+            .is_some_and(|seen| seen.contains(nonce))
+    }
+
+    /// Get notified once an order reaches a terminal status
+    ///
+    /// Fires immediately if the order is already terminal. Otherwise it fires
+    /// when the engine records the terminal result, whether from submission,
+    /// [`cancel_order`](Self::cancel_order) or [`reconcile`](Self::reconcile).
+    /// With the built-in simulator orders fill during submission, so a watch
+    /// registered before `execute_order` fires before that call returns. The
+    /// receiver never completes for an id the engine never sees; dropping it
+    /// lets the engine forget the watch.
+    pub fn watch_order(&self, id: Uuid) -> oneshot::Receiver<OrderResult> {
+        let (tx, rx) = oneshot::channel();
+        let orders = self.orders.lock().unwrap();
+        match orders.get(&id).and_then(|tracked| tracked.result.as_ref()) {
+            Some(result) if result.is_terminal() => {
+                let _ = tx.send(result.clone());
+            }
+            _ => {
+                let mut watchers = self.watchers.lock().unwrap();
+                watchers.sweep(|_, senders| {
+                    senders.retain(|tx| !tx.is_closed());
+                    !senders.is_empty()
+                });
+                watchers.map.entry(id).or_default().push(tx);
+            }
+        }
+        rx
+    }
+
+    /// Deliver a terminal result to its watchers
+    ///
+    /// Callers hold the orders lock, which `watch_order` also takes, so a
+    /// watcher is never registered just after its notification went out.
+    fn notify_watchers(&self, result: &OrderResult) {
+        if !result.is_terminal() {
+            return;
+        }
+        self.pending.freed.notify_waiters();
+        if let Some(senders) = self.watchers.lock().unwrap().map.remove(&result.order_id) {
+            for tx in senders {
+                let _ = tx.send(result.clone());
+            }
+        }
+    }
+
+    /// Look up an order submitted through this engine
+    ///
+    /// An order worked in slices (see [`execute_order`](Self::execute_order),
+    /// [`execute_iceberg`](Self::execute_iceberg) and
+    /// [`execute_twap`](Self::execute_twap)) is found with the aggregate of
+    /// its slices' results as of when the last one was sent; the slices are
+    /// tracked under their own ids.
+    pub fn tracked_order(&self, id: Uuid) -> Option<TrackedOrder> {
+        if let Some(tracked) = self.orders.lock().unwrap().get(&id) {
+            return Some(tracked.clone());
+        }
+        self.parents.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Orders still pending or partially filled more than `older_than` after
+    /// their timestamp, by the engine's clock
+    ///
+    /// An order with no result yet (in flight or timed out) counts as
+    /// pending. Feed these into [`ExecutionEngine::reconcile`].
+    pub fn stale_pending_orders(&self, older_than: Duration) -> Vec<Uuid> {
+        let Ok(older_than) = chrono::Duration::from_std(older_than) else {
+            return Vec::new();
+        };
+        let now = self.clock.now();
+        self.orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|tracked| match &tracked.result {
+                None => true,
+                Some(result) => matches!(
+                    result.status,
+                    OrderStatus::Pending | OrderStatus::PartiallyFilled
+                ),
+            })
+            .filter(|tracked| now - tracked.order.timestamp > older_than)
+            .map(|tracked| tracked.order.id)
+            .collect()
+    }
+
+    /// Current order counters and gauges
+    pub fn metrics(&self) -> EngineMetricsSnapshot {
+        let open = self
+            .orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|tracked| !matches!(&tracked.result, Some(result) if result.is_terminal()))
+            .count();
+        self.metrics.snapshot(open, self.is_halted())
+    }
+
+    /// Orders whose outcome is not known (in flight or timed out)
+    pub fn unresolved_orders(&self) -> Vec<Uuid> {
+        self.orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|tracked| tracked.result.is_none())
+            .map(|tracked| tracked.order.id)
+            .collect()
+    }
+
+    /// The symbol's [`max_order_qty`](SymbolRules::max_order_qty) in the
+    /// order's own unit: valued at the reference price for quote-sized
+    /// orders
+    async fn max_slice(&self, order: &Order) -> Result<Option<f64>> {
+        let max_qty = self
+            .symbol_rules
+            .get(&order.symbol)
+            .and_then(|rules| rules.max_order_qty);
+        match max_qty {
+            Some(max_qty) if order.quote_quantity => {
+                Ok(Some(max_qty * self.reference_price(order).await?))
+            }
+            max_qty => Ok(max_qty),
+        }
+    }
+
+    /// Price used to value an order before it is filled
+    async fn reference_price(&self, order: &Order) -> Result<f64> {
+        match order.order_type.limit_price() {
+            Some(price) => Ok(price),
+            None => self.simulator.market_price(&order.symbol).await,
+        }
+    }
+
+    /// Cancel an open order at the venue
+    pub async fn cancel_order(&self, id: Uuid) -> Result<OrderResult> {
+        let result = self.venue().cancel(id).await?;
+        self.apply_cancel(id, result).await
+    }
+
+    /// Cancel whatever of a tracked order is still unfilled, keeping its fills
+    ///
+    /// A pending or partially filled order is cancelled at the venue. The
+    /// `Cancelled` result keeps the executed quantity and price (taken from
+    /// the last known result if the venue omits them) and its message gives
+    /// the quantity cancelled. An order that is already executed, failed,
+    /// cancelled or rejected is left alone and its result returned. Ids this
+    /// engine does not track fail with [`ExecutionError::UnknownOrder`].
+    pub async fn cancel_remaining(&self, id: Uuid) -> Result<OrderResult> {
+        let tracked = self
+            .tracked_order(id)
+            .ok_or(ExecutionError::UnknownOrder(id))?;
+        let previous = match tracked.result {
+            Some(result) if result.is_terminal() => return Ok(result),
+            previous => previous,
+        };
+
+        let mut result = self.venue().cancel(id).await?;
+        if let Some(previous) = &previous {
+            result.executed_quantity = result.executed_quantity.or(previous.executed_quantity);
+            result.execution_price = result.execution_price.or(previous.execution_price);
+        }
+        let filled = result.executed_quantity.unwrap_or(0.0);
+        let total = tracked.order.quantity;
+        // The remainder is in the order's unit, so a quote-sized order
+        // counts what its fills cost and says so
+        result.message = Some(match tracked.order.order_quantity() {
+            OrderQuantity::Base(_) => format!(
+                "Cancelled remaining {} of {} ({} filled)",
+                (total - filled).max(0.0),
+                total,
+                filled
+            ),
+            OrderQuantity::Quote(_) => {
+                let spent = filled * result.execution_price.unwrap_or(0.0);
+                format!(
+                    "Cancelled remaining {} of {} quote ({} base filled for {})",
+                    (total - spent).max(0.0),
+                    total,
+                    filled,
+                    spent
+                )
+            }
+        });
+        self.apply_cancel(id, result).await
+    }
+
+    /// Cancel every open tracked order, or only those on `symbol`
+    ///
+    /// Each pending or partially filled order (including one whose outcome
+    /// is unknown) is cancelled as by
+    /// [`cancel_remaining`](Self::cancel_remaining). Symbols are compared in
+    /// their [`Symbol`] form, so `btc-usd` matches orders on `BTC/USD`. The
+    /// cancels are sent concurrently and the results returned oldest order
+    /// first. A failed cancel does not stop the others: its entry is the
+    /// order's last known result (`Pending` if there is none) with the error
+    /// as the message, and the order stays open.
+    pub async fn cancel_all(&self, symbol: Option<&str>) -> Result<Vec<OrderResult>> {
+        let mut open: Vec<TrackedOrder> = self
+            .orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|tracked| match symbol {
+                Some(symbol) => same_symbol(symbol, &tracked.order.symbol),
+                None => true,
+            })
+            .filter(|tracked| !matches!(&tracked.result, Some(result) if result.is_terminal()))
+            .cloned()
+            .collect();
+        open.sort_by_key(|tracked| (tracked.order.timestamp, tracked.order.nonce));
+
+        let cancels = open.into_iter().map(|tracked| async move {
+            let id = tracked.order.id;
+            match self.cancel_remaining(id).await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("Failed to cancel order {}: {}", id, e);
+                    let mut result = tracked.result.unwrap_or_else(|| OrderResult {
+                        order_id: id,
+                        status: OrderStatus::Pending,
+                        execution_price: None,
+                        executed_quantity: None,
+                        timestamp: self.clock.now(),
+                        message: None,
+                        timings: None,
+                        book_snapshot: None,
+                        exchange_order_id: None,
+                        fee: None,
+                    });
+                    result.message = Some(format!("cancel failed: {}", e));
+                    result
+                }
+            }
+        });
+        Ok(future::join_all(cancels).await)
+    }
+
+    /// Record a venue's cancel result against the tracked order
+    ///
+    /// Fills the cancel reports beyond the last known result are recorded
+    /// like any other fill.
+    async fn apply_cancel(&self, id: Uuid, mut result: OrderResult) -> Result<OrderResult> {
+        let order = match self.orders.lock().unwrap().get_mut(&id) {
+            Some(mut tracked) => {
+                check_transition(tracked.result.as_ref(), &result)?;
+                self.charge_fee(&tracked.order, &mut result);
+                // Venues may leave out fills they reported before
+                let previous = tracked.result.as_ref();
+                let mut settled = result.clone();
+                settled.executed_quantity = settled
+                    .executed_quantity
+                    .or(previous.and_then(|r| r.executed_quantity));
+                settled.execution_price = settled
+                    .execution_price
+                    .or(previous.and_then(|r| r.execution_price));
+                settled.fee = settled.fee.or(previous.and_then(|r| r.fee));
+                self.record_fill(&tracked.order, previous, &settled);
+                self.settle_risk(&tracked.order, &settled);
+                tracked.result = Some(result.clone());
+                self.notify_watchers(&result);
+                Some(tracked.order.clone())
+            }
+            None => None,
+        };
+        let correlation_id = order
+            .as_ref()
+            .and_then(|order| order.correlation_id.clone());
+        self.publish_result(&result, correlation_id);
+        if let Some(order) = order {
+            self.persist(&order, &result).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Net position in a symbol from fills seen by this engine
+    pub fn position(&self, symbol: &str) -> Position {
+        self.positions
+            .lock()
+            .unwrap()
+            .get(symbol)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Positions for every symbol this engine has traded
+    pub fn positions(&self) -> HashMap<String, Position> {
+        self.positions.lock().unwrap().clone()
+    }
+
+    /// Copy the pending orders, their risk reservations, the settled risk
+    /// exposure, positions and seen nonces
+    ///
+    /// Orders with a terminal result are left out; their outcome is already
+    /// in the store, if one is configured.
+    pub fn snapshot(&self) -> EngineSnapshot {
+        let pending_orders: Vec<TrackedOrder> = self
+            .orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|tracked| !matches!(&tracked.result, Some(result) if result.is_terminal()))
+            .cloned()
+            .collect();
+        let mut risk_reservations = HashMap::new();
+        let mut settled_exposure = HashMap::new();
+        if let Some(risk) = &self.risk {
+            let reservations = risk.reservations();
+            let mut reserved_by_symbol: HashMap<&str, f64> = HashMap::new();
+            for tracked in &pending_orders {
+                if let Some(reserved) = reservations.get(&tracked.order.id) {
+                    risk_reservations.insert(tracked.order.id, *reserved);
+                    *reserved_by_symbol
+                        .entry(tracked.order.symbol.as_str())
+                        .or_insert(0.0) += reserved;
+                }
+            }
+            for (symbol, exposure) in risk.exposures() {
+                let reserved = reserved_by_symbol
+                    .get(symbol.as_str())
+                    .copied()
+                    .unwrap_or(0.0);
+                let settled = exposure - reserved;
+                if settled != 0.0 {
+                    settled_exposure.insert(symbol, settled);
+                }
+            }
+        }
+
+        EngineSnapshot {
+            taken_at: self.clock.now(),
+            pending_orders,
+            positions: self.positions(),
+            seen_nonces: self.seen_nonces.lock().unwrap().clone(),
+            risk_reservations,
+            settled_exposure,
+            risk_limits: self.risk.as_ref().map(|risk| risk.limits().clone()),
+        }
+    }
+
+    /// Rebuild an engine from a [`snapshot`](Self::snapshot), e.g. after a
+    /// hot restart
+    ///
+    /// The engine is configured as by [`new`](Self::new) apart from the
+    /// snapshot's risk limits, with the snapshot loaded as by
+    /// [`with_snapshot`](Self::with_snapshot). Other builders can follow.
+    pub fn restore(signing_key: SigningKey, snapshot: EngineSnapshot) -> Self {
+        Self::new(signing_key).with_snapshot(snapshot)
+    }
+
+    /// Load the state from a [`snapshot`](Self::snapshot) into this engine
+    ///
+    /// The pending orders go into the order cache. Their reservations and
+    /// the settled exposure are added to the risk manager without
+    /// re-checking the limits, since the orders were already accepted; an
+    /// engine without risk limits takes the snapshot's. Positions and seen
+    /// nonces replace this engine's.
+    pub fn with_snapshot(mut self, snapshot: EngineSnapshot) -> Self {
+        if self.risk.is_none() {
+            if let Some(limits) = snapshot.risk_limits.clone() {
+                self.risk = Some(Arc::new(RiskManager::new(limits)));
+            }
+        }
+        if let Some(risk) = &self.risk {
+            for (symbol, settled) in &snapshot.settled_exposure {
+                risk.restore_exposure(symbol, *settled);
+            }
+            for tracked in &snapshot.pending_orders {
+                if let Some(reserved) = snapshot.risk_reservations.get(&tracked.order.id) {
+                    risk.restore_reservation(&tracked.order, *reserved);
+                }
+            }
+        }
+        {
+            let mut orders = self.orders.lock().unwrap();
+            for tracked in snapshot.pending_orders {
+                orders.insert(tracked.order.id, tracked);
+            }
+        }
+        *self.positions.lock().unwrap() = snapshot.positions;
+        *self.seen_nonces.lock().unwrap() = snapshot.seen_nonces;
+        self
+    }
+
+    /// Fold any newly filled quantity of `order` into its symbol's position,
+    /// including fills a cancel reports
+    fn record_fill(&self, order: &Order, previous: Option<&OrderResult>, result: &OrderResult) {
+        if !matches!(
+            result.status,
+            OrderStatus::Executed | OrderStatus::PartiallyFilled | OrderStatus::Cancelled
+        ) {
+            return;
+        }
+        let (Some(filled), Some(price)) = (result.executed_quantity, result.execution_price) else {
+            return;
+        };
+
+        let already_filled = previous.and_then(|p| p.executed_quantity).unwrap_or(0.0);
+        let delta = filled - already_filled;
+        if delta <= 0.0 {
+            return;
+        }
+        // `fee` covers the whole executed quantity, like `executed_quantity`
+        let fee_delta = result.fee.unwrap_or(0.0) - previous.and_then(|p| p.fee).unwrap_or(0.0);
+
+        let mut positions = self.positions.lock().unwrap();
+        let position = positions.entry(order.symbol.clone()).or_default();
+        position.apply_fill(order.side, delta, price);
+        position.apply_fee(fee_delta);
+        drop(positions);
+        if !self.min_flip_interval.is_zero() {
+            let interval = self.min_flip_interval;
+            let mut last_fills = self.last_fills.lock().unwrap();
+            last_fills.sweep(|_, (_, at)| at.elapsed() < interval);
+            last_fills
+                .map
+                .insert(order.symbol.clone(), (order.side, Instant::now()));
+        }
+    }
+
+    /// Set `result.fee` for its executed quantity, if there is a fee model
+    /// and the venue did not report a fee itself
+    fn charge_fee(&self, order: &Order, result: &mut OrderResult) {
+        let Some(model) = &self.fee_model else {
+            return;
+        };
+        if result.fee.is_some() {
+            return;
+        }
+        if let (Some(filled), Some(price)) = (result.executed_quantity, result.execution_price) {
+            result.fee = Some(model.fee(order, price, filled));
+        }
+    }
+
+    /// Check the order against the configured price band, if any
+    ///
+    /// Fails closed: an order is rejected with the price source's error when
+    /// it cannot give a price. With [`fail_open`](PriceBand::fail_open) set,
+    /// the error is logged and the order is checked as having no reference
+    /// price, so limit orders pass and market orders pass unless the band
+    /// requires a reference for them.
+    async fn check_price_band(&self, order: &Order) -> Result<()> {
+        let Some(band) = &self.price_band else {
+            return Ok(());
+        };
+        let reference = match &self.price_source {
+            Some(source) => match source.price(&order.symbol).await {
+                Ok(price) => Some(price),
+                Err(e) if band.fail_open => {
+                    tracing::warn!(
+                        "No reference price for {}, skipping band: {}",
+                        order.symbol,
+                        e
+                    );
+                    None
+                }
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+        band.check(order, reference)
+    }
+
+    /// Reject a reduce-only order that would open, grow or flip the position
+    ///
+    /// Measured against fills seen so far; other in-flight orders on the
+    /// symbol are not counted.
+    async fn check_reduce_only(&self, order: &Order) -> Result<()> {
+        if !order.reduce_only {
+            return Ok(());
+        }
+        let net = self.position(&order.symbol).net_quantity;
+        let reducible = match order.side {
+            OrderSide::Buy => -net,
+            OrderSide::Sell => net,
+        };
+        let quantity = match order.order_quantity() {
+            OrderQuantity::Base(quantity) => quantity,
+            quote => quote.base_at(self.reference_price(order).await?),
+        };
+        if quantity > reducible {
+            return Err(ExecutionError::ReduceOnlyViolation.into());
+        }
+        Ok(())
+    }
+
+    /// Reject an order derived from a signal older than the configured age
+    fn check_signal_age(&self, order: &Order) -> Result<()> {
+        let (Some(max_age), Some(source)) = (self.max_signal_age, order.source_timestamp) else {
+            return Ok(());
+        };
+        let too_old = match chrono::Duration::from_std(max_age) {
+            Ok(max_age) => self.clock.now() - source > max_age,
+            Err(_) => false,
+        };
+        if too_old {
+            return Err(ExecutionError::SignalTooOld.into());
+        }
+        Ok(())
+    }
+
+    /// Reject a limit order that would cross the market price immediately
+    ///
+    /// Passes when no market price is available, since crossing cannot be
+    /// judged.
+    async fn check_marketable_limit(&self, order: &Order) -> Result<()> {
+        if !self.reject_marketable_limits {
+            return Ok(());
+        }
+        let Some(limit) = order.order_type.limit_price() else {
+            return Ok(());
+        };
+        let Some(source) = &self.price_source else {
+            return Ok(());
+        };
+        let Ok(market) = source.price(&order.symbol).await else {
+            return Ok(());
+        };
+        let crosses = match order.side {
+            OrderSide::Buy => limit > market,
+            OrderSide::Sell => limit < market,
+        };
+        if crosses {
+            return Err(ExecutionError::MarketableLimit.into());
+        }
+        Ok(())
+    }
+
+    /// Enforce the minimum interval between opposite-side fills on a symbol
+    fn check_flip(&self, order: &Order) -> Result<()> {
+        if self.min_flip_interval.is_zero() {
+            return Ok(());
+        }
+        if let Some((side, at)) = self.last_fills.lock().unwrap().map.get(&order.symbol) {
+            if *side != order.side && at.elapsed() < self.min_flip_interval {
+                return Err(ExecutionError::FlipThrottled.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a pre-trade rejection and publish it with the error's category
+    fn record_rejection(&self, id: Uuid, correlation_id: Option<String>, error: &Error) {
+        self.metrics.record_status(OrderStatus::Rejected);
+        self.events.publish(OrderEvent::Rejected {
+            id,
+            reason: error.category().to_string(),
+            timestamp: self.clock.now(),
+            correlation_id,
+        });
+        self.send_alert(
+            Level::Warning,
+            format!("Order {} rejected ({}): {}", id, error.category(), error),
+        );
+    }
+
+    /// Queue an alert for background delivery, logging rather than
+    /// returning a delivery failure
+    fn send_alert(&self, level: Level, message: String) {
+        self.alerts.send(level, message);
+    }
+
+    /// Publish the event corresponding to a result's status
+    fn publish_result(&self, result: &OrderResult, correlation_id: Option<String>) {
+        self.metrics.record_status(result.status);
+        let id = result.order_id;
+        let timestamp = result.timestamp;
+        let event = match result.status {
+            OrderStatus::Pending => return,
+            OrderStatus::PartiallyFilled => OrderEvent::PartiallyFilled {
+                id,
+                timestamp,
+                correlation_id,
+            },
+            OrderStatus::Executed => OrderEvent::Filled {
+                id,
+                timestamp,
+                correlation_id,
+            },
+            OrderStatus::Failed => {
+                self.send_alert(
+                    Level::Error,
+                    format!(
+                        "Order {} failed: {}",
+                        id,
+                        result.message.as_deref().unwrap_or("no message")
+                    ),
+                );
+                OrderEvent::Failed {
+                    id,
+                    timestamp,
+                    correlation_id,
+                }
+            }
+            OrderStatus::Cancelled => OrderEvent::Cancelled {
+                id,
+                timestamp,
+                correlation_id,
+            },
+            OrderStatus::Rejected => return,
+        };
+        self.events.publish(event);
+    }
+
+    /// Re-query the venue for each order and update the tracked results
+    ///
+    /// Use after timeouts or a restart to resolve orders whose outcome is
+    /// unknown. Results are persisted to the configured store, if any. A
+    /// venue status that would be an illegal transition (see
+    /// [`OrderStatus::can_transition_to`]) is not applied and fails the call.
+    pub async fn reconcile(&self, ids: &[Uuid]) -> Result<Vec<OrderResult>> {
+        let venue = self.venue();
+        let mut results = Vec::with_capacity(ids.len());
+
+        for &id in ids {
+            let mut result = venue.query_status(id).await?;
+
+            let order = match self.orders.lock().unwrap().get_mut(&id) {
+                Some(mut tracked) => {
+                    check_transition(tracked.result.as_ref(), &result)?;
+                    self.charge_fee(&tracked.order, &mut result);
+                    self.record_fill(&tracked.order, tracked.result.as_ref(), &result);
+                    self.settle_risk(&tracked.order, &result);
+                    tracked.result = Some(result.clone());
+                    self.notify_watchers(&result);
+                    Some(tracked.order.clone())
+                }
+                None => None,
+            };
+            if let Some(order) = order {
+                self.persist(&order, &result).await;
+            }
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Write an order's latest result to the configured store
+    ///
+    /// A failed write is logged rather than returned: the order has already
+    /// been sent, and its result is still held in memory.
+    async fn persist(&self, order: &Order, result: &OrderResult) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.store_order(order, result).await {
+                tracing::error!("Failed to persist order {}: {}", order.id, e);
+                if let Some(sink) = &self.dead_letters {
+                    if let Err(e) = sink.write(order, result).await {
+                        tracing::error!("Failed to dead-letter order {}: {}", order.id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The adapter orders are routed to in the current mode
+    fn venue(&self) -> Arc<dyn ExchangeAdapter> {
+        match (self.mode, &self.adapter) {
+            (EngineMode::Live, Some(adapter)) => adapter.clone(),
+            _ => self.simulator.clone(),
+        }
+    }
+
+    async fn submit(&self, order: &Order) -> Result<OrderResult> {
+        let mut result = self.venue().submit(order).await?;
+        if self.is_paper() {
+            result.message = Some(match result.message {
+                Some(message) => format!("PAPER: {}", message),
+                None => "PAPER".to_string(),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Validate order parameters
+    ///
+    /// Non-finite quantities and prices, and orders the engine's signing
+    /// scheme cannot encode (such as a symbol with control bytes under
+    /// [`SigningScheme::Binary`]), are always rejected, before any risk is
+    /// reserved or nonce recorded; the engine's validation rules then run in
+    /// order, stopping at the first failure.
+    pub fn validate_order(&self, order: &Order) -> Result<()> {
+        order.ensure_finite()?;
+        order.signing_bytes(self.signing_scheme)?;
+        for rule in self.validation_rules.iter() {
+            rule.check(order)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reject a result that would move a tracked order through an illegal
+/// status transition
+fn check_transition(previous: Option<&OrderResult>, next: &OrderResult) -> Result<()> {
+    match previous {
+        Some(previous) if !previous.status.can_transition_to(next.status) => {
+            Err(ExecutionError::IllegalTransition {
+                from: previous.status,
+                to: next.status,
+            }
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::RebateFeeModel;
+    use crate::order_id::PrefixedGenerator;
+    use crate::pricing::StaticPriceSource;
+    use crate::storage::InMemoryStore;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_order_creation() {
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        assert_eq!(order.symbol, "BTC/USD");
+        assert_eq!(order.quantity, 0.1);
+        assert_eq!(order.symbol_parsed().unwrap().base(), "BTC");
+    }
+
+    #[test]
+    fn test_order_template_instantiates_fresh_orders() {
+        let template = OrderTemplate {
+            symbol: "BTC/USD".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Limit { price: 50000.0 },
+            quantity: 0.5,
+        };
+        template.validate().unwrap();
+
+        let first = template.instantiate();
+        let second = template.instantiate();
+        assert_ne!(first.id, second.id);
+        assert!(second.nonce > first.nonce);
+        for order in [&first, &second] {
+            assert_eq!(order.symbol, template.symbol);
+            assert_eq!(order.side, template.side);
+            assert_eq!(order.order_type, template.order_type);
+            assert_eq!(order.quantity, template.quantity);
+            assert!(order.signature.is_none());
+        }
+
+        let invalid = OrderTemplate {
+            quantity: 0.0,
+            ..template
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_order_from_signal() {
+        let signal = |signal_type: &str| TradingSignal {
+            symbol: "ETH/USD".to_string(),
+            signal_type: signal_type.to_string(),
+            strength: 0.8,
+            timestamp: 0,
+            metadata: serde_json::Value::Null,
+        };
+
+        let order = Order::from_signal(&signal("buy"), 2.0).unwrap();
+        assert_eq!(order.symbol, "ETH/USD");
+        assert_eq!(order.side, OrderSide::Buy);
+        assert_eq!(order.order_type, OrderType::Market);
+        assert_eq!(order.quantity, 2.0);
+
+        let order = Order::from_signal(&signal("SELL"), 1.0).unwrap();
+        assert_eq!(order.side, OrderSide::Sell);
+
+        let err = Order::from_signal(&signal("hold"), 1.0).unwrap_err();
+        assert!(err.to_string().contains("'hold'"));
+    }
+
+    #[test]
+    fn test_display_summaries() {
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        assert_eq!(
+            order.to_string(),
+            format!("BUY 0.1 BTC/USD @ MARKET [id={}]", order.id)
+        );
+
+        order.side = OrderSide::Sell;
+        order.order_type = OrderType::Limit { price: 50000.0 };
+        order.time_in_force = TimeInForce::ImmediateOrCancel;
+        order.reduce_only = true;
+        order.sign(&SigningKey::generate()).unwrap();
+        let summary = order.to_string();
+        assert!(summary.starts_with("SELL 0.1 BTC/USD @ LIMIT 50000 IOC REDUCE-ONLY [id="));
+        let signature = order.signature.as_ref().unwrap();
+        assert!(!summary.contains(&hex::encode(signature.to_bytes())));
+
+        let result = OrderResult {
+            order_id: order.id,
+            status: OrderStatus::Executed,
+            execution_price: Some(50000.0),
+            executed_quantity: Some(0.1),
+            timestamp: Utc::now(),
+            message: Some("Order executed successfully".to_string()),
+            timings: None,
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: None,
+        };
+        assert_eq!(
+            result.to_string(),
+            format!("EXECUTED 0.1 @ 50000 [id={}]", order.id)
+        );
+
+        let rejected = OrderResult {
+            status: OrderStatus::Rejected,
+            execution_price: None,
+            executed_quantity: None,
+            message: Some("risk limit: max open orders".to_string()),
+            ..result
+        };
+        assert_eq!(
+            rejected.to_string(),
+            format!("REJECTED [id={}]: risk limit: max open orders", order.id)
+        );
+    }
+
+    #[test]
+    fn test_side_and_type_helpers() {
+        assert_eq!(OrderSide::Buy.opposite(), OrderSide::Sell);
+        assert_eq!(OrderSide::Sell.opposite(), OrderSide::Buy);
+        assert_eq!(OrderSide::Sell.as_str(), "sell");
+
+        let limit = OrderType::Limit { price: 100.0 };
+        assert!(limit.is_limit());
+        assert_eq!(limit.limit_price(), Some(100.0));
+        assert_eq!(limit.as_str(), "limit");
+        assert!(!OrderType::Market.is_limit());
+        assert_eq!(OrderType::Market.limit_price(), None);
+    }
+
+    #[test]
+    fn test_order_builder() {
+        let order = Order::builder()
+            .symbol("ETH/USD")
+            .side(OrderSide::Sell)
+            .limit(3000.0)
+            .quantity(2.0)
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .nonce(7)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.symbol, "ETH/USD");
+        assert_eq!(order.order_type.limit_price(), Some(3000.0));
+        assert!(matches!(
+            order.time_in_force,
+            TimeInForce::ImmediateOrCancel
+        ));
+        assert_eq!(order.nonce, 7);
+
+        let err = Order::builder()
+            .symbol("ETH/USD")
+            .quantity(2.0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: order side is required");
+    }
+
+    #[test]
+    fn test_json_signing_scheme() {
+        let key = SigningKey::generate();
+        let mut order = Order::builder()
+            .symbol("BTC/USD")
+            .side(OrderSide::Buy)
+            .limit(50000.0)
+            .quantity(0.5)
+            .nonce(42)
+            .build()
+            .unwrap();
+
+        let json = String::from_utf8(order.canonical_json().unwrap()).unwrap();
+        assert!(json.starts_with(r#"{"id":""#));
+        assert!(json.contains(r#""nonce":"42","order_type":{"price":50000,"type":"limit"}"#));
+
+        order.sign_json(&key).unwrap();
+        assert!(order.verify_json(&key.verification_key()).is_ok());
+        // Schemes are not interchangeable
+        assert!(order.verify(&key.verification_key()).is_err());
+    }
+
+    #[test]
+    fn test_equality_and_hashing() {
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        assert_eq!(order, order.clone());
+
+        let mut nan = order.clone();
+        nan.quantity = f64::NAN;
+        assert_ne!(nan, nan.clone());
+
+        let mut counts: HashMap<OrderStatus, usize> = HashMap::new();
+        *counts.entry(OrderStatus::Executed).or_default() += 1;
+        *counts.entry(OrderStatus::Executed).or_default() += 1;
+        assert_eq!(counts[&OrderStatus::Executed], 2);
+    }
+
+    #[test]
+    fn test_order_signing() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        assert!(order.sign(&key).is_ok());
+        assert!(order.signature.is_some());
+    }
+
+    #[test]
+    fn test_verify_any_version_accepts_v1_signatures() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+
+        // Signed over the raw v1 layout
+        let v1 = order
+            .canonical_bytes_versioned(CanonicalVersion::V1)
+            .unwrap();
+        assert_ne!(
+            v1,
+            order
+                .canonical_bytes_versioned(CanonicalVersion::V2)
+                .unwrap()
+        );
+        order.signature = Some(key.sign(&v1));
+        order.verify(&key.verification_key()).unwrap();
+        assert_eq!(
+            order.verify_any_version(&key.verification_key()).unwrap(),
+            CanonicalVersion::V1
+        );
+
+        order.sign(&key).unwrap();
+        assert_eq!(
+            order.verify_any_version(&key.verification_key()).unwrap(),
+            CanonicalVersion::CURRENT
+        );
+        assert!(order
+            .verify_any_version(&SigningKey::generate().verification_key())
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_any_version_accepts_legacy_signatures() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+
+        // The encoding signed before nonces were introduced, written out
+        // field by field rather than through the versioned encoder
+        let mut legacy = Vec::new();
+        legacy.extend_from_slice(order.id.as_bytes());
+        legacy.extend_from_slice(order.symbol.as_bytes());
+        legacy.push(1);
+        legacy.push(1);
+        legacy.extend_from_slice(&50000.0f64.to_le_bytes());
+        legacy.extend_from_slice(&0.1f64.to_le_bytes());
+        legacy.extend_from_slice(&order.timestamp.timestamp().to_le_bytes());
+        assert_eq!(
+            order
+                .canonical_bytes_versioned(CanonicalVersion::Legacy)
+                .unwrap(),
+            legacy
+        );
+
+        order.signature = Some(key.sign(&legacy));
+        assert!(order.verify(&key.verification_key()).is_err());
+        assert_eq!(
+            order.verify_any_version(&key.verification_key()).unwrap(),
+            CanonicalVersion::Legacy
+        );
+
+        // A market order has no price bytes
+        order.order_type = OrderType::Market;
+        let mut legacy = Vec::new();
+        legacy.extend_from_slice(order.id.as_bytes());
+        legacy.extend_from_slice(order.symbol.as_bytes());
+        legacy.push(1);
+        legacy.push(0);
+        legacy.extend_from_slice(&0.1f64.to_le_bytes());
+        legacy.extend_from_slice(&order.timestamp.timestamp().to_le_bytes());
+        order.signature = Some(key.sign(&legacy));
+        assert_eq!(
+            order.verify_any_version(&key.verification_key()).unwrap(),
+            CanonicalVersion::Legacy
+        );
+    }
+
+    #[test]
+    fn test_binary_v2_is_opt_in() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+
+        // The default scheme still signs the v1 bytes
+        assert_eq!(CanonicalVersion::CURRENT, CanonicalVersion::V1);
+        assert_eq!(
+            order.signing_bytes(SigningScheme::Binary).unwrap(),
+            order
+                .canonical_bytes_versioned(CanonicalVersion::V1)
+                .unwrap()
+        );
+
+        order.sign_with(&key, SigningScheme::BinaryV2).unwrap();
+        assert!(order.verify(&key.verification_key()).is_err());
+        order
+            .verify_with(&key.verification_key(), SigningScheme::BinaryV2)
+            .unwrap();
+        assert_eq!(
+            order.verify_any_version(&key.verification_key()).unwrap(),
+            CanonicalVersion::V2
+        );
+
+        // A control byte in the symbol could shift the v1 fields after it
+        order.symbol = "BTC\u{1}".to_string();
+        assert!(matches!(order.canonical_bytes(), Err(Error::Crypto(_))));
+        order
+            .canonical_bytes_versioned(CanonicalVersion::V2)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execution_engine() {
+        let key = SigningKey::generate();
+        let engine = ExecutionEngine::new(key);
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        let result = engine.execute_order(order).await;
+        assert!(result.is_ok());
+    }
+
+    /// Leaves every order resting; cancels fail for ids in `refuse`
+    #[derive(Default)]
+    struct RestingVenue {
+        refuse: Mutex<HashSet<Uuid>>,
+        /// How long each cancel takes
+        cancel_delay: Duration,
+        /// Quantity and price each order filled before its cancel landed
+        filled_before_cancel: Option<(f64, f64)>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExchangeAdapter for RestingVenue {
+        async fn submit(&self, order: &Order) -> Result<OrderResult> {
+            let mut result = SimulatedExchange::new().submit(order).await?;
+            result.status = OrderStatus::Pending;
+            result.execution_price = None;
+            result.executed_quantity = None;
+            Ok(result)
+        }
+
+        async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+            Err(ExecutionError::UnknownOrder(id).into())
+        }
+
+        async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+            tokio::time::sleep(self.cancel_delay).await;
+            if self.refuse.lock().unwrap().contains(&id) {
+                return Err(ExecutionError::VenueRejected {
+                    code: None,
+                    message: "too late to cancel".to_string(),
+                }
+                .into());
+            }
+            Ok(OrderResult {
+                order_id: id,
+                status: OrderStatus::Cancelled,
+                execution_price: self.filled_before_cancel.map(|(_, price)| price),
+                executed_quantity: self.filled_before_cancel.map(|(quantity, _)| quantity),
+                timestamp: Utc::now(),
+                message: None,
+                timings: None,
+                book_snapshot: None,
+                exchange_order_id: None,
+                fee: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_open_orders() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(RestingVenue::default()))
+            .with_risk_limits(RiskLimits {
+                max_open_orders: Some(2),
+                ..Default::default()
+            });
+
+        for _ in 0..2 {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            );
+            assert!(engine.execute_order(order).await.is_ok());
+        }
+
+        let order = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let err = engine.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+        assert!(err.to_string().contains("risk limit: max open orders"));
+        assert_eq!(engine.risk_manager().unwrap().open_orders(), 2);
+
+        // Orders stop counting once they reach a terminal state, and the
+        // rejected order did not use up its nonce
+        engine.cancel_all(None).await.unwrap();
+        assert_eq!(engine.risk_manager().unwrap().open_orders(), 0);
+        engine.execute_order(order).await.unwrap();
+        engine.cancel_all(None).await.unwrap();
+        let filled = ExecutionEngine::new(SigningKey::generate()).with_risk_limits(RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            );
+            filled.execute_order(order).await.unwrap();
+        }
+        assert_eq!(filled.risk_manager().unwrap().open_orders(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pending_cap_rejects_while_full() {
+        let venue =
+            SimulatedExchange::new().with_latency(Duration::from_millis(200), Duration::ZERO);
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(venue))
+            .with_max_pending(2);
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            )
+        };
+
+        let in_flight: Vec<_> = (0..2)
+            .map(|_| {
+                let engine = engine.clone();
+                let order = order();
+                tokio::spawn(async move { engine.execute_order(order).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(engine.unresolved_orders().len(), 2);
+
+        let err = engine.execute_order(order()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::PendingCapacity)
+        ));
+        assert!(err.to_string().contains("pending capacity reached"));
+        let rejected = engine.submit_or_reject(order()).await.unwrap();
+        assert_eq!(rejected.status, OrderStatus::Rejected);
+
+        // The queued order waits for one of the two to finish
+        let queued = engine.execute_order_queued(order()).await.unwrap();
+        assert_eq!(queued.status, OrderStatus::Executed);
+        for handle in in_flight {
+            assert!(handle.await.unwrap().is_ok());
+        }
+        assert!(engine.execute_order(order()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_try_execute_rate_limited_across_clones() {
+        let limiter = Arc::new(RateLimiter::new(2, 0.0));
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_rate_limiter(limiter);
+        let clone = engine.clone();
+
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            )
+        };
+        assert!(engine.try_execute_order(order()).await.is_ok());
+        assert!(clone.try_execute_order(order()).await.is_ok());
+
+        let err = engine.try_execute_order(order()).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::RateLimited)));
+        assert_eq!(err.to_string(), "Execution error: rate limited");
+    }
+
+    #[tokio::test]
+    async fn test_urgent_order_jumps_rate_limit_queue() {
+        let limiter = Arc::new(RateLimiter::new(1, 10.0));
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_rate_limiter(limiter);
+        let order = |urgency| {
+            Order::builder()
+                .symbol("BTC/USD")
+                .side(OrderSide::Buy)
+                .quantity(0.1)
+                .urgency(urgency)
+                .build()
+                .unwrap()
+        };
+        // Drain the bucket so both later orders have to wait
+        engine.execute_order(order(Urgency::Normal)).await.unwrap();
+
+        let served = Arc::new(Mutex::new(Vec::new()));
+        let spawn = |urgency| {
+            let engine = engine.clone();
+            let served = served.clone();
+            let order = order(urgency);
+            tokio::spawn(async move {
+                engine.execute_order(order).await.unwrap();
+                served.lock().unwrap().push(urgency);
+            })
+        };
+        let passive = spawn(Urgency::Passive);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let urgent = spawn(Urgency::Urgent);
+        passive.await.unwrap();
+        urgent.await.unwrap();
+
+        assert_eq!(
+            *served.lock().unwrap(),
+            vec![Urgency::Urgent, Urgency::Passive]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multisig_policy_for_large_orders() {
+        let approvers: Vec<SigningKey> = (0..3).map(|_| SigningKey::generate()).collect();
+        let mut keyring = KeyRing::new();
+        for approver in &approvers {
+            keyring.add(approver.verification_key());
+        }
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_multisig_policy(MultiSigPolicy {
+                keyring,
+                threshold: 2,
+                min_notional: 10_000.0,
+            });
+
+        // Small orders need no approval
+        let small = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        assert!(engine.execute_order(small).await.is_ok());
+
+        let mut large = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        large.add_approval(&approvers[0]).unwrap();
+        assert!(engine.execute_order(large.clone()).await.is_err());
+
+        large.add_approval(&approvers[1]).unwrap();
+        assert!(large
+            .verify_multisig(&engine.multisig.as_ref().unwrap().keyring, 2)
+            .is_ok());
+        assert!(engine.execute_order(large).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_order_rejected() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        assert!(engine.execute_order(order.clone()).await.is_ok());
+        let err = engine.execute_order(order).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Replay)));
+        assert_eq!(err.to_string(), "Execution error: replay detected");
+    }
+
+    #[tokio::test]
+    async fn test_nonces_are_per_signer() {
+        let alice = SigningKey::generate();
+        let bob = SigningKey::generate();
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_order_verifier(Verifier::new([
+                alice.verification_key(),
+                bob.verification_key(),
+            ]));
+        let signed = |key: &SigningKey| {
+            let mut order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            );
+            order.nonce = 7;
+            order.sign(key).unwrap();
+            order
+        };
+
+        engine.execute_order(signed(&alice)).await.unwrap();
+        engine.execute_order(signed(&bob)).await.unwrap();
+        let err = engine.execute_order(signed(&alice)).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Replay)));
+
+        let err = engine
+            .execute_order(signed(&SigningKey::generate()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Crypto(_)));
+
+        // The nonce is required on the wire
+        let mut json = serde_json::to_value(signed(&alice)).unwrap();
+        json.as_object_mut().unwrap().remove("nonce");
+        assert!(serde_json::from_value::<Order>(json).is_err());
+    }
+
+    #[test]
+    fn test_nonce_window_is_bounded() {
+        let mut window = NonceWindow::default();
+        for nonce in 0..NONCE_WINDOW as u64 + 10 {
+            assert!(window.insert(nonce + 100));
+        }
+        assert_eq!(window.recent.len(), NONCE_WINDOW);
+        assert!(!window.insert(100));
+        assert!(!window.insert(50));
+        assert!(!window.insert(NONCE_WINDOW as u64 + 109));
+        assert!(window.insert(NONCE_WINDOW as u64 + 110));
+    }
+
+    #[test]
+    fn test_nonces_strictly_increase() {
+        let a = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let b = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        assert!(b.nonce > a.nonce);
+    }
+
+    #[tokio::test]
+    async fn test_execution_publishes_events() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let mut events = engine.subscribe_events();
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let order = Order {
+            correlation_id: Some("request-1".to_string()),
+            ..order
+        };
+        let id = order.id;
+        engine.execute_order(order).await.unwrap();
+
+        let submitted = events.recv().await.unwrap();
+        assert!(matches!(submitted, OrderEvent::Submitted { .. }));
+        assert_eq!(submitted.order_id(), id);
+        assert_eq!(submitted.correlation_id(), Some("request-1"));
+        let filled = events.recv().await.unwrap();
+        assert!(matches!(filled, OrderEvent::Filled { .. }));
+        assert_eq!(filled.correlation_id(), Some("request-1"));
+    }
+
+    #[tokio::test]
+    async fn test_rejection_publishes_one_event() {
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_risk_limits(RiskLimits {
+            max_order_notional: Some(1000.0),
+            ..Default::default()
+        });
+        let mut events = engine.subscribe_events();
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            1.0,
+        );
+        let id = order.id;
+        assert!(engine.execute_order(order).await.is_err());
+
+        match events.try_recv().unwrap() {
+            OrderEvent::Rejected {
+                id: rejected,
+                reason,
+                ..
+            } => {
+                assert_eq!(rejected, id);
+                assert_eq!(reason, "risk_limit");
+            }
+            other => panic!("expected a rejection, got {:?}", other),
+        }
+        assert!(events.try_recv().is_err());
+
+        // submit_or_reject reports the same way
+        engine
+            .submit_or_reject(Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.0,
+            ))
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            OrderEvent::Rejected { reason, .. } if reason == "validation"
+        ));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_order_alerts_once() {
+        struct ChannelSink(tokio::sync::mpsc::UnboundedSender<(Level, String)>);
+
+        #[async_trait::async_trait]
+        impl AlertSink for ChannelSink {
+            async fn alert(&self, level: Level, message: String) -> Result<()> {
+                self.0.send((level, message)).unwrap();
+                Ok(())
+            }
+        }
+
+        struct FailingAdapter;
+
+        #[async_trait::async_trait]
+        impl ExchangeAdapter for FailingAdapter {
+            async fn submit(&self, _order: &Order) -> Result<OrderResult> {
+                Err(ExecutionError::VenueRejected {
+                    code: None,
+                    message: "venue down".to_string(),
+                }
+                .into())
+            }
+
+            async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+                Err(ExecutionError::UnknownOrder(id).into())
+            }
+
+            async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+                Err(ExecutionError::UnknownOrder(id).into())
+            }
+        }
+
+        let (tx, mut alerts) = tokio::sync::mpsc::unbounded_channel();
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(FailingAdapter))
+            .with_alert_sink(Arc::new(ChannelSink(tx)));
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+        assert!(engine.execute_order(order).await.is_err());
+
+        let (level, message) = alerts.recv().await.unwrap();
+        assert_eq!(level, Level::Error);
+        assert!(message.contains(&id.to_string()));
+        assert!(message.contains("venue down"));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(alerts.try_recv().is_err());
+
+        // Successful orders stay quiet
+        engine
+            .clone()
+            .with_adapter(Arc::new(SimulatedExchange::new()))
+            .execute_order(Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            ))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(alerts.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lot_rounding_before_submission() {
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_symbol_rules(
+            "BTC/USD",
+            SymbolRules {
+                lot_size: Some(0.01),
+                ..Default::default()
+            },
+        );
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.129,
+        );
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.executed_quantity, Some(0.12));
+
+        // Rounding down to zero leaves nothing to trade
+        let dust = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.004,
+        );
+        assert!(engine.execute_order(dust).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quote_quantity_order() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_simulator(SimulatedExchange::new().with_market_price(40000.0))
+            .with_symbol_rules(
+                "BTC/USD",
+                SymbolRules {
+                    lot_size: Some(0.01),
+                    max_order_qty: Some(0.001),
+                    ..Default::default()
+                },
+            );
+        let order = Order::builder()
+            .symbol("BTC/USD")
+            .side(OrderSide::Buy)
+            .quote_quantity(100.0)
+            .build()
+            .unwrap();
+        assert_eq!(order.order_quantity(), OrderQuantity::Quote(100.0));
+
+        // Lot sizes are in base, so the amount is not rounded; the $40
+        // (0.001 BTC) cap splits it into $40, $40 and $20
+        let report = engine.simulate(&order).await.unwrap();
+        assert_eq!(report.rounded_quantity, 100.0);
+        assert!(report.warnings.iter().any(|w| w.contains("3 slices")));
+
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        let filled = result.executed_quantity.unwrap();
+        assert!((filled - 0.0025).abs() < 1e-12);
+        assert!((engine.position("BTC/USD").net_quantity - 0.0025).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_halt_rejects_new_orders() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            )
+        };
+
+        engine.clone().halt();
+        assert!(engine.is_halted());
+        let err = engine.execute_order(order()).await.unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: engine halted");
+
+        engine.resume();
+        assert!(engine.execute_order(order()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_engine_timestamps_from_clock() {
+        use crate::clock::FixedClock;
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_clock(clock.clone());
+
+        let order = Order::new_at(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+            start,
+        );
+        assert_eq!(order.timestamp, start);
+
+        engine.halt();
+        clock.advance(chrono::Duration::seconds(5));
+        let result = engine.submit_or_reject(order.clone()).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Rejected);
+        assert_eq!(result.timestamp, start + chrono::Duration::seconds(5));
+
+        // Simulated fills and nonces follow the clock too
+        engine.resume();
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.timestamp, start + chrono::Duration::seconds(5));
+        let later = Order::new_at(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+            start + chrono::Duration::days(365 * 100),
+        );
+        assert_eq!(
+            later.nonce,
+            (start + chrono::Duration::days(365 * 100)).timestamp_micros() as u64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_metrics() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = |quantity| {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                quantity,
+            )
+        };
+
+        engine.execute_order(order(0.1)).await.unwrap();
+        engine.execute_order(order(-1.0)).await.unwrap_err();
+        engine.submit_or_reject(order(0.0)).await.unwrap();
+
+        let metrics = engine.metrics();
+        assert_eq!(metrics.orders_submitted, 1);
+        assert_eq!(metrics.orders_executed, 1);
+        assert_eq!(metrics.orders_rejected, 2);
+        assert_eq!(metrics.open_orders, 0);
+        assert!(!metrics.halted);
+    }
+
+    #[tokio::test]
+    async fn test_custom_validation_rules() {
+        struct MaxQuantity(f64);
+
+        impl ValidationRule for MaxQuantity {
+            fn check(&self, order: &Order) -> Result<()> {
+                if order.quantity > self.0 {
+                    return Err(ExecutionError::Validation("Quantity too large".to_string()).into());
+                }
+                Ok(())
+            }
+        }
+
+        struct Counting(Arc<AtomicU64>);
+
+        impl ValidationRule for Counting {
+            fn check(&self, _order: &Order) -> Result<()> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let checked = Arc::new(AtomicU64::new(0));
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_validation_rule(MaxQuantity(1.0))
+            .with_validation_rule(Counting(checked.clone()));
+        let order = |quantity| {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                quantity,
+            )
+        };
+
+        engine.execute_order(order(0.5)).await.unwrap();
+        assert_eq!(checked.load(Ordering::SeqCst), 1);
+
+        // The failing rule short-circuits the ones after it
+        let err = engine.execute_order(order(2.0)).await.unwrap_err();
+        assert!(err.to_string().contains("Quantity too large"));
+        let err = engine.execute_order(order(-1.0)).await.unwrap_err();
+        assert!(err.to_string().contains("Quantity must be positive"));
+        assert_eq!(checked.load(Ordering::SeqCst), 1);
+
+        // Replacing the rule set drops the built-in checks
+        let lenient = ExecutionEngine::new(SigningKey::generate()).with_validation_rules(vec![]);
+        lenient.validate_order(&order(0.0)).unwrap();
+        assert!(lenient.validate_order(&order(f64::NAN)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unsignable_order_is_rejected_before_admission() {
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_risk_limits(RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        });
+        let mut order = Order::new(
+            "BTC\u{1}/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        assert!(engine.validate_order(&order).is_err());
+        assert!(engine.execute_order(order.clone()).await.is_err());
+        let risk = engine.risk_manager().unwrap();
+        assert_eq!(risk.open_orders(), 0);
+        assert_eq!(risk.exposure(&order.symbol), 0.0);
+
+        // The nonce was not burned, so the corrected order goes through
+        order.symbol = "BTC/USD".to_string();
+        engine.execute_order(order).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_results_carry_timings() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+
+        let result = engine.execute_order(order).await.unwrap();
+        let timings = result.timings.expect("engine results are timed");
+        assert!(timings.total_us >= timings.submit_us);
+        assert!(timings.total_us >= timings.validate_us + timings.sign_us);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json["timings"]["total_us"].is_u64());
+
+        // Rejected before reaching the venue: nothing to break down
+        let rejected = engine
+            .submit_or_reject(Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.0,
+            ))
+            .await
+            .unwrap();
+        assert!(rejected.timings.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_or_reject() {
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_risk_limits(RiskLimits {
+            max_order_notional: Some(10_000.0),
+            ..Default::default()
+        });
+
+        let invalid = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            -1.0,
+        );
+        let result = engine.submit_or_reject(invalid).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Rejected);
+        assert_eq!(result.message.as_deref(), Some("Quantity must be positive"));
+
+        let too_big = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let result = engine.submit_or_reject(too_big).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Rejected);
+        assert!(result.message.unwrap().starts_with("risk limit"));
+
+        let ok = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let result = engine.submit_or_reject(ok).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_position_after_partial_sell() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+
+        let buy = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 100.0 },
+            2.0,
+        );
+        engine.execute_order(buy).await.unwrap();
+
+        let sell = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 120.0 },
+            0.5,
+        );
+        engine.execute_order(sell).await.unwrap();
+
+        let position = engine.position("BTC/USD");
+        assert_eq!(position.net_quantity, 1.5);
+        assert_eq!(position.avg_entry_price, 100.0);
+        assert_eq!(position.realized_pnl, 10.0);
+        assert_eq!(engine.positions().len(), 1);
+        assert!(engine.position("ETH/USD").is_flat());
+    }
+
+    #[tokio::test]
+    async fn test_maker_rebates_credit_net_pnl() {
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_fee_model(Arc::new(RebateFeeModel {
+                maker_rebate_bps: 2.0,
+                taker_fee_bps: 5.0,
+            }));
+
+        // Maker buy of 1 at 100: a 0.02 rebate
+        let buy = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 100.0 },
+            1.0,
+        );
+        let result = engine.execute_order(buy).await.unwrap();
+        let fee = result.fee.unwrap();
+        assert!(fee < 0.0);
+        assert!((fee + 0.02).abs() < 1e-12);
+
+        // Maker sell at 110: a further 0.022 rebate on top of the 10 gain
+        let sell = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 110.0 },
+            1.0,
+        );
+        engine.execute_order(sell).await.unwrap();
+
+        let position = engine.position("BTC/USD");
+        assert!(position.is_flat());
+        assert_eq!(position.realized_pnl, 10.0);
+        assert!((position.fees + 0.042).abs() < 1e-12);
+        assert!((position.net_pnl() - 10.042).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_has_no_side_effects() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_symbol_rules(
+                "BTC/USD",
+                SymbolRules {
+                    tick_size: Some(0.5),
+                    lot_size: Some(0.01),
+                    ..Default::default()
+                },
+            )
+            .with_risk_limits(RiskLimits {
+                max_open_orders: Some(1),
+                max_order_notional: Some(1000.0),
+                ..Default::default()
+            })
+            .with_fee_model(Arc::new(RebateFeeModel {
+                maker_rebate_bps: 2.0,
+                taker_fee_bps: 5.0,
+            }));
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 100.25 },
+            1.234,
+        );
+        let report = engine.simulate(&order).await.unwrap();
+        assert_eq!(report.rounded_quantity, 1.23);
+        // Buys round down to the tick
+        assert_eq!(report.rounded_price, Some(100.0));
+        assert_eq!(report.estimated_fill_price, Some(100.0));
+        let fee = report.estimated_fee.unwrap();
+        assert!((fee + 100.0 * 1.23 * 2.0 / 10_000.0).abs() < 1e-12);
+        assert_eq!(report.warnings.len(), 2);
+
+        // Simulating again reserves nothing and does not use up the nonce
+        engine.simulate(&order).await.unwrap();
+        assert_eq!(engine.metrics().orders_submitted, 0);
+        assert!(engine.tracked_order(order.id).is_none());
+        let result = engine.execute_order(order.clone()).await.unwrap();
+        assert_eq!(result.executed_quantity, Some(1.23));
+        // The order sent is the one the simulation described
+        let sent = engine.tracked_order(order.id).unwrap().order;
+        assert_eq!(sent.quantity, report.rounded_quantity);
+        assert_eq!(sent.order_type.limit_price(), report.rounded_price);
+
+        // Failing checks fail the simulation the same way
+        let err = engine.simulate(&order).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Replay)));
+        let large = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 100.0 },
+            20.0,
+        );
+        let err = engine.simulate(&large).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_stored_results() {
+        let store = Arc::new(InMemoryStore::new());
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_store(store.clone())
+            .with_fee_model(Arc::new(RebateFeeModel {
+                maker_rebate_bps: 2.0,
+                taker_fee_bps: 5.0,
+            }));
+        for order_type in [OrderType::Market, OrderType::Limit { price: 51000.0 }] {
+            let order = Order::new("BTC/USD".to_string(), OrderSide::Buy, order_type, 0.5);
+            engine.execute_order(order).await.unwrap();
+        }
+
+        let mut records = store.get_order_history(10).await.unwrap();
+        records.reverse();
+        let replayed = engine.replay(records.clone()).await.unwrap();
+        assert_eq!(replayed.len(), 2);
+        for (record, result) in records.iter().zip(&replayed) {
+            assert_eq!(result.order_id, record.id);
+            assert_eq!(result.status.as_str(), record.status);
+            assert_eq!(result.execution_price, record.execution_price);
+            assert_eq!(result.executed_quantity, record.executed_quantity);
+            assert!(result.fee.is_some());
+            assert_eq!(result.fee, record.fee);
+        }
+
+        // Replaying leaves the engine's state alone: positions, metrics and
+        // the simulator's own results
+        assert_eq!(engine.metrics().orders_submitted, 2);
+        assert_eq!(engine.position("BTC/USD").net_quantity, 1.0);
+        let mut unseen = records[0].clone();
+        unseen.id = Uuid::new_v4();
+        engine.replay(vec![unseen.clone()]).await.unwrap();
+        assert!(engine.simulator.query_status(unseen.id).await.is_err());
+        assert!(engine.tracked_order(unseen.id).is_none());
+
+        let mut malformed = records[0].clone();
+        malformed.side = "hold".to_string();
+        assert!(engine.replay(vec![malformed]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_order_ids_assigned_before_signing() {
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            )
+        };
+
+        let key = SigningKey::generate();
+        let engine = ExecutionEngine::new(key.clone());
+        let default = order();
+        let id = default.id;
+        engine.execute_order(default).await.unwrap();
+        let tracked = engine.tracked_order(id).unwrap().order;
+        let client_id = tracked.client_order_id.unwrap();
+        assert!(Uuid::parse_str(&client_id).is_ok());
+
+        let engine = engine.with_order_id_generator(Arc::new(PrefixedGenerator::new("tw-")));
+        let prefixed = order();
+        let id = prefixed.id;
+        engine.execute_order(prefixed).await.unwrap();
+        let tracked = engine.tracked_order(id).unwrap().order;
+        let client_id = tracked.client_order_id.as_deref().unwrap();
+        assert!(client_id.starts_with("tw-"));
+        assert_eq!(client_id.len(), 3 + 32);
+        // Not signed, so the venue may be sent any client id
+        let mut renamed = tracked.clone();
+        renamed.client_order_id = Some("other".to_string());
+        renamed.verify(&key.verification_key()).unwrap();
+
+        // A caller-supplied id is kept
+        let preset = Order {
+            client_order_id: Some("mine-1".to_string()),
+            ..order()
+        };
+        let id = preset.id;
+        engine.execute_order(preset).await.unwrap();
+        let tracked = engine.tracked_order(id).unwrap().order;
+        assert_eq!(tracked.client_order_id.as_deref(), Some("mine-1"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_remaining_keeps_fills() {
+        let book = OrderBook::new("BTC/USD", vec![], vec![(100.0, 1.0)], Utc::now());
+        let venue = Arc::new(SimulatedExchange::new().with_order_book(book));
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(venue.clone())
+            .with_risk_limits(RiskLimits {
+                max_symbol_exposure: Some(10.0),
+                ..Default::default()
+            });
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            3.0,
+        );
+        let id = order.id;
+        let partial = engine.execute_order(order).await.unwrap();
+        assert_eq!(engine.risk_manager().unwrap().exposure("BTC/USD"), 3.0);
+        assert_eq!(partial.status, OrderStatus::PartiallyFilled);
+        assert_eq!(partial.executed_quantity, Some(1.0));
+
+        let cancelled = engine.cancel_remaining(id).await.unwrap();
+        assert_eq!(cancelled.status, OrderStatus::Cancelled);
+        assert_eq!(cancelled.executed_quantity, Some(1.0));
+        assert_eq!(cancelled.execution_price, Some(100.0));
+        assert_eq!(
+            cancelled.message.as_deref(),
+            Some("Cancelled remaining 2 of 3 (1 filled)")
+        );
+        assert_eq!(
+            engine.tracked_order(id).unwrap().result,
+            Some(cancelled.clone())
+        );
+        assert_eq!(engine.position("BTC/USD").net_quantity, 1.0);
+        // Only the unfilled 2 leave the risk tally
+        let risk = engine.risk_manager().unwrap();
+        assert_eq!(risk.exposure("BTC/USD"), 1.0);
+        assert_eq!(risk.open_orders(), 0);
+
+        // Terminal orders are left as they are
+        assert_eq!(engine.cancel_remaining(id).await.unwrap(), cancelled);
+        let limit = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 99.0 },
+            1.0,
+        );
+        let limit_id = limit.id;
+        let executed = engine.execute_order(limit).await.unwrap();
+        assert_eq!(engine.cancel_remaining(limit_id).await.unwrap(), executed);
+
+        assert!(matches!(
+            engine.cancel_remaining(Uuid::new_v4()).await,
+            Err(Error::Execution(ExecutionError::UnknownOrder(_)))
+        ));
+
+        // A quote-sized order reports its remainder in the quote currency
+        venue.set_order_book(OrderBook::new(
+            "BTC/USD",
+            vec![],
+            vec![(100.0, 1.0)],
+            Utc::now(),
+        ));
+        let mut quote = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.0,
+        );
+        quote.set_order_quantity(OrderQuantity::Quote(300.0));
+        let quote_id = quote.id;
+        engine.execute_order(quote).await.unwrap();
+        let cancelled = engine.cancel_remaining(quote_id).await.unwrap();
+        assert_eq!(
+            cancelled.message.as_deref(),
+            Some("Cancelled remaining 200 of 300 quote (1 base filled for 100)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fills_reported_by_cancels_are_recorded() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(RestingVenue {
+                filled_before_cancel: Some((0.4, 100.0)),
+                ..Default::default()
+            }))
+            .with_risk_limits(RiskLimits {
+                max_symbol_exposure: Some(10.0),
+                ..Default::default()
+            });
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                1.0,
+            )
+        };
+        let (first, second) = (order(), order());
+        engine.execute_order(first.clone()).await.unwrap();
+        engine.execute_order(second).await.unwrap();
+        assert_eq!(engine.position("BTC/USD").net_quantity, 0.0);
+
+        let cancelled = engine.cancel_order(first.id).await.unwrap();
+        assert_eq!(cancelled.executed_quantity, Some(0.4));
+        assert_eq!(engine.position("BTC/USD").net_quantity, 0.4);
+
+        // Also through cancel_remaining, which cancel_all uses
+        let results = engine.cancel_all(None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        let position = engine.position("BTC/USD");
+        assert!((position.net_quantity - 0.8).abs() < 1e-12);
+        assert!((engine.risk_manager().unwrap().exposure("BTC/USD") - 0.8).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_by_symbol() {
+        let venue = Arc::new(RestingVenue::default());
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_adapter(venue.clone());
+        let mut ids = Vec::new();
+        for symbol in ["BTC/USD", "ETH/USD", "BTC/USD"] {
+            let order = Order::new(
+                symbol.to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 100.0 },
+                1.0,
+            );
+            ids.push(order.id);
+            engine.execute_order(order).await.unwrap();
+        }
+        venue.refuse.lock().unwrap().insert(ids[2]);
+
+        // Matched in normalized form
+        let eth = engine.cancel_all(Some("eth-usd")).await.unwrap();
+        assert_eq!(eth.len(), 1);
+        assert_eq!(eth[0].order_id, ids[1]);
+        assert_eq!(eth[0].status, OrderStatus::Cancelled);
+
+        // One refusal does not stop the rest
+        let all = engine.cancel_all(None).await.unwrap();
+        let statuses: Vec<_> = all.iter().map(|r| (r.order_id, r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (ids[0], OrderStatus::Cancelled),
+                (ids[2], OrderStatus::Pending)
+            ]
+        );
+        assert_eq!(
+            all[1].message.as_deref(),
+            Some("cancel failed: Execution error: rejected by venue: too late to cancel")
+        );
+
+        // Only the refused order is still open
+        let again = engine.cancel_all(None).await.unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].order_id, ids[2]);
+        assert!(engine.cancel_all(Some("ETH/USD")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_all_cancels_concurrently() {
+        let venue = Arc::new(RestingVenue {
+            cancel_delay: Duration::from_secs(1),
+            ..Default::default()
+        });
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_adapter(venue);
+        for _ in 0..3 {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 100.0 },
+                1.0,
+            );
+            engine.execute_order(order).await.unwrap();
+        }
+
+        let start = tokio::time::Instant::now();
+        let results = engine.cancel_all(None).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.status == OrderStatus::Cancelled));
+        // Three one-second cancels in about one second, not three
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_order_cache_evicts_oldest_completed() {
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_order_cache_capacity(2);
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            );
+            ids.push(order.id);
+            engine.execute_order(order).await.unwrap();
+        }
+
+        assert!(engine.tracked_order(ids[0]).is_none());
+        assert!(engine.tracked_order(ids[1]).is_some());
+        assert!(engine.tracked_order(ids[2]).is_some());
+        // Positions are kept apart from the cache
+        assert!((engine.position("BTC/USD").net_quantity - 0.3).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_price_source_values_market_orders() {
+        let prices = StaticPriceSource::new().with_price("BTC/USD", 20_000.0);
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_price_source(Arc::new(prices))
+            .with_risk_limits(RiskLimits {
+                max_order_notional: Some(25_000.0),
+                ..Default::default()
+            });
+
+        // 1 BTC would breach the limit at the 50000 placeholder, not at 20000
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.execution_price, Some(20_000.0));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_in_flight_orders() {
+        let idle = ExecutionEngine::new(SigningKey::generate());
+        assert_eq!(idle.shutdown(Duration::from_millis(10)).await.unwrap(), 0);
+
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_adapter(Arc::new(SlowAdapter));
+        let worker = engine.clone();
+        tokio::spawn(async move {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                1.0,
+            );
+            worker.execute_order(order).await
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let observer = engine.clone();
+        let pending = engine.shutdown(Duration::from_millis(50)).await.unwrap();
+        assert_eq!(pending, 1);
+        assert!(observer.is_halted());
+        assert_eq!(observer.unresolved_orders().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_flip_throttle() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_min_flip_interval(Duration::from_millis(100));
+        let order = |side| {
+            Order::new(
+                "BTC/USD".to_string(),
+                side,
+                OrderType::Limit { price: 50000.0 },
+                0.1,
+            )
+        };
+
+        engine.execute_order(order(OrderSide::Buy)).await.unwrap();
+        // Adding to the position on the same side is fine
+        engine.execute_order(order(OrderSide::Buy)).await.unwrap();
+
+        let err = engine
+            .execute_order(order(OrderSide::Sell))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::FlipThrottled)
+        ));
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        engine.execute_order(order(OrderSide::Sell)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_non_finite_values_rejected() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let key = SigningKey::generate();
+
+        for (quantity, order_type) in [
+            (f64::NAN, OrderType::Market),
+            (f64::INFINITY, OrderType::Market),
+            (1.0, OrderType::Limit { price: f64::NAN }),
+            (
+                1.0,
+                OrderType::Limit {
+                    price: f64::INFINITY,
+                },
+            ),
+        ] {
+            let mut order = Order::new("BTC/USD".to_string(), OrderSide::Buy, order_type, quantity);
+            assert!(order.canonical_bytes().is_err());
+            assert!(order.sign(&key).is_err());
+
+            let err = engine.execute_order(order).await.unwrap_err();
+            assert_eq!(err.to_string(), "Execution error: non-finite value");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_decision_checks_linkage() {
+        let agent = SigningKey::generate();
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_decision_verifier(Verifier::new([agent.verification_key()]));
+        let order = || {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            )
+        };
+
+        let linked = order();
+        let mut decision = Decision::new(linked.id, serde_json::json!({"action": "buy"}));
+        decision.sign(&agent);
+        let result = engine
+            .execute_with_decision(linked, &decision)
+            .await
+            .unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+
+        let err = engine
+            .execute_with_decision(order(), &decision)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::Validation(_))
+        ));
+
+        let unknown = order();
+        let mut forged = Decision::new(unknown.id, serde_json::json!({"action": "buy"}));
+        forged.sign(&SigningKey::generate());
+        let err = engine
+            .execute_with_decision(unknown, &forged)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Crypto(_)));
+
+        // A signed decision proves nothing without keys to check it against
+        let unverified = ExecutionEngine::new(SigningKey::generate());
+        let linked = order();
+        let mut decision = Decision::new(linked.id, serde_json::json!({"action": "buy"}));
+        decision.sign(&agent);
+        let err = unverified
+            .execute_with_decision(linked, &decision)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Crypto(_)));
+    }
+
+    #[tokio::test]
+    async fn test_results_persisted_to_store() {
+        let store = Arc::new(InMemoryStore::new());
+        let engine = ExecutionEngine::new(SigningKey::generate()).with_store(store.clone());
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+        let id = order.id;
+        engine.execute_order(order).await.unwrap();
+
+        let history = store.get_order_history(10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, id);
+        assert_eq!(history[0].symbol, "BTC/USD");
+        assert_eq!(history[0].status, "executed");
+    }
+
+    #[tokio::test]
+    async fn test_signatures_are_audited() {
+        #[derive(Default)]
+        struct RecordingSink(Mutex<Vec<AuditEvent>>);
+
+        #[async_trait::async_trait]
+        impl AuditSink for RecordingSink {
+            async fn record(&self, event: AuditEvent) {
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        let key = SigningKey::generate();
+        let key_id = key.key_id();
+        let sink = Arc::new(RecordingSink::default());
+        let engine = ExecutionEngine::new(key).with_audit_sink(sink.clone());
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+        engine.execute_order(order).await.unwrap();
+
+        let signed = engine.tracked_order(id).unwrap().order;
+        let events = sink.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].order_id, id);
+        assert_eq!(events[0].key_id, key_id);
+        assert_eq!(
+            events[0].canonical_hash,
+            crypto::hash_data(&signed.canonical_bytes().unwrap()).to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_persist_goes_to_dead_letter_sink() {
+        use crate::storage::{DecisionRecord, OrderQuery, OrderRecord};
+        use crate::HashAlgorithm;
+
+        fn down<T>() -> Result<T> {
+            Err(ExecutionError::Validation("store down".to_string()).into())
+        }
+
+        struct DownStore;
+
+        #[async_trait::async_trait]
+        impl Store for DownStore {
+            async fn store_order(&self, _: &Order, _: &OrderResult) -> Result<()> {
+                down()
+            }
+            async fn store_order_with_response(
+                &self,
+                _: &Order,
+                _: &OrderResult,
+                _: &serde_json::Value,
+            ) -> Result<()> {
+                down()
+            }
+            async fn get_raw_response(&self, _: Uuid) -> Result<Option<serde_json::Value>> {
+                down()
+            }
+            async fn get_order_history(&self, _: i64) -> Result<Vec<OrderRecord>> {
+                down()
+            }
+            async fn query_orders(&self, _: &OrderQuery) -> Result<Vec<OrderRecord>> {
+                down()
+            }
+            async fn store_decision(&self, _: &Decision, _: HashAlgorithm) -> Result<Vec<u8>> {
+                down()
+            }
+            async fn get_decision(&self, _: Uuid) -> Result<Option<DecisionRecord>> {
+                down()
+            }
+        }
+
+        #[derive(Default)]
+        struct RecordingSink(Mutex<Vec<Uuid>>);
+
+        #[async_trait::async_trait]
+        impl DeadLetterSink for RecordingSink {
+            async fn write(&self, order: &Order, _: &OrderResult) -> Result<()> {
+                self.0.lock().unwrap().push(order.id);
+                Ok(())
+            }
+        }
+
+        let sink = Arc::new(RecordingSink::default());
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_store(Arc::new(DownStore))
+            .with_dead_letter_sink(sink.clone());
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(*sink.0.lock().unwrap(), vec![id]);
+    }
+
+    #[test]
+    fn test_status_state_machine() {
+        use OrderStatus::*;
+        let all = [
+            Pending,
+            PartiallyFilled,
+            Executed,
+            Failed,
+            Cancelled,
+            Rejected,
+        ];
+
+        for status in all {
+            assert!(status.can_transition_to(status));
+            if status.is_terminal() {
+                for next in all.into_iter().filter(|next| *next != status) {
+                    assert!(
+                        !status.can_transition_to(next),
+                        "{:?} -> {:?}",
+                        status,
+                        next
+                    );
+                }
+            }
+        }
+        assert!(!Pending.is_terminal() && !PartiallyFilled.is_terminal());
+        assert!(Pending.can_transition_to(PartiallyFilled));
+        assert!(PartiallyFilled.can_transition_to(Executed));
+        assert!(!PartiallyFilled.can_transition_to(Pending));
+        assert!(!Pending.can_transition_to(Rejected));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restore_round_trip() {
+        // Market orders fill; limit orders rest on the book
+        struct RestingLimits;
+
+        #[async_trait::async_trait]
+        impl ExchangeAdapter for RestingLimits {
+            async fn submit(&self, order: &Order) -> Result<OrderResult> {
+                let mut result = SimulatedExchange::new().submit(order).await?;
+                if order.order_type.limit_price().is_some() {
+                    result.status = OrderStatus::Pending;
+                    result.execution_price = None;
+                    result.executed_quantity = None;
+                }
+                Ok(result)
+            }
+
+            async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+                Err(ExecutionError::UnknownOrder(id).into())
+            }
+
+            async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+                Err(ExecutionError::UnknownOrder(id).into())
+            }
+        }
+
+        let key = SigningKey::generate();
+        let limits = RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        };
+        let engine = ExecutionEngine::new(key.clone())
+            .with_adapter(Arc::new(RestingLimits))
+            .with_risk_limits(limits.clone());
+        let filled = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.5,
+        );
+        let resting = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 4000.0 },
+            2.0,
+        );
+        let resting_id = resting.id;
+        engine.execute_order(filled.clone()).await.unwrap();
+        engine.execute_order(resting).await.unwrap();
+
+        let json = serde_json::to_string(&engine.snapshot()).unwrap();
+        let snapshot: EngineSnapshot = serde_json::from_str(&json).unwrap();
+        let ids: Vec<_> = snapshot
+            .pending_orders
+            .iter()
+            .map(|tracked| tracked.order.id)
+            .collect();
+        assert_eq!(ids, vec![resting_id]);
+        assert_eq!(
+            snapshot.risk_reservations,
+            HashMap::from([(resting_id, -2.0)])
+        );
+
+        let restored = ExecutionEngine::new(key)
+            .with_risk_limits(limits)
+            .with_order_cache_capacity(1)
+            .with_snapshot(snapshot);
+        assert_eq!(restored.positions(), engine.positions());
+        assert_eq!(restored.position("BTC/USD").net_quantity, 0.5);
+        assert_eq!(
+            restored
+                .tracked_order(resting_id)
+                .unwrap()
+                .result
+                .unwrap()
+                .status,
+            OrderStatus::Pending
+        );
+        assert!(restored.tracked_order(filled.id).is_none());
+
+        // The resting order still holds its reservation
+        let risk = restored.risk_manager().unwrap();
+        assert_eq!(risk.open_orders(), 1);
+        assert_eq!(risk.exposure("ETH/USD"), -2.0);
+        let err = restored
+            .execute_order(Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+
+        // Seen nonces survive, so the filled order cannot be replayed
+        let err = restored.execute_order(filled).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Replay)));
+    }
+
+    #[tokio::test]
+    async fn test_restore_keeps_settled_exposure() {
+        let key = SigningKey::generate();
+        let limits = RiskLimits {
+            max_symbol_exposure: Some(1.0),
+            ..Default::default()
+        };
+        let engine = ExecutionEngine::new(key.clone()).with_risk_limits(limits.clone());
+        let buy = |quantity| {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                quantity,
+            )
+        };
+        engine.execute_order(buy(0.8)).await.unwrap();
+
+        let json = serde_json::to_string(&engine.snapshot()).unwrap();
+        let snapshot: EngineSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            snapshot.settled_exposure,
+            HashMap::from([("BTC/USD".to_string(), 0.8)])
+        );
+
+        // The restored limits still count the position already held, also
+        // when the limits are set again afterwards
+        for restored in [
+            ExecutionEngine::restore(key.clone(), snapshot.clone()),
+            ExecutionEngine::restore(key.clone(), snapshot).with_risk_limits(limits),
+        ] {
+            assert_eq!(restored.position("BTC/USD").net_quantity, 0.8);
+            let err = restored.execute_order(buy(0.5)).await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Execution(ExecutionError::RiskLimit(_))
+            ));
+            restored.execute_order(buy(0.1)).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_illegal_transition_not_applied() {
+        // Fills immediately, then claims the filled order is pending again
+        struct RegressingAdapter;
+
+        #[async_trait::async_trait]
+        impl ExchangeAdapter for RegressingAdapter {
+            async fn submit(&self, order: &Order) -> Result<OrderResult> {
+                SimulatedExchange::new().submit(order).await
+            }
+
+            async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+                Ok(OrderResult {
+                    order_id: id,
+                    status: OrderStatus::Pending,
+                    execution_price: None,
+                    executed_quantity: None,
+                    timestamp: Utc::now(),
+                    message: None,
+                    timings: None,
+                    book_snapshot: None,
+                    exchange_order_id: None,
+                    fee: None,
+                })
+            }
+
+            async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+                Err(ExecutionError::UnknownOrder(id).into())
+            }
+        }
+
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_adapter(Arc::new(RegressingAdapter));
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+        let executed = engine.execute_order(order).await.unwrap();
+        assert!(executed.is_terminal());
+
+        let err = engine.reconcile(&[id]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::IllegalTransition {
+                from: OrderStatus::Executed,
+                to: OrderStatus::Pending,
+            })
+        ));
+        let tracked = engine.tracked_order(id).unwrap();
+        assert_eq!(tracked.result.unwrap().status, OrderStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_watch_order_fires_on_terminal_result() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+
+        let mut rx = engine.watch_order(id);
+        engine.execute_order(order).await.unwrap();
+        // The simulator fills synchronously, so the result is already there
+        assert_eq!(rx.try_recv().unwrap().status, OrderStatus::Executed);
+
+        let late = engine.watch_order(id).await.unwrap();
+        assert_eq!(late.order_id, id);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_watches_are_swept() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let kept = engine.watch_order(Uuid::new_v4());
+        for _ in 0..1000 {
+            drop(engine.watch_order(Uuid::new_v4()));
+        }
+        // Only the watch still held survives the sweeps, plus those
+        // registered since the last one
+        let watchers = engine.watchers.lock().unwrap().map.len();
+        assert!(watchers <= SWEEP_MIN, "{} watchers left", watchers);
+        drop(kept);
+
+        // Without a flip interval no fills are remembered
+        engine
+            .execute_order(Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            ))
+            .await
+            .unwrap();
+        assert!(engine.last_fills.lock().unwrap().map.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_order_fires_after_reconcile() {
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_adapter(Arc::new(SlowAdapter));
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let id = order.id;
+
+        let mut rx = engine.watch_order(id);
+        assert!(engine
+            .execute_order_timeout(order, Duration::from_millis(10))
+            .await
+            .is_err());
+        assert!(rx.try_recv().is_err());
+
+        engine.reconcile(&[id]).await.unwrap();
+        assert_eq!(rx.await.unwrap().status, OrderStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_price_band_against_price_source() {
+        let prices = StaticPriceSource::new().with_price("BTC/USD", 50000.0);
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_price_source(Arc::new(prices))
+            .with_price_band(PriceBand::new(5.0).require_reference_for_market());
+        let order = |symbol: &str, order_type| {
+            Order::new(symbol.to_string(), OrderSide::Buy, order_type, 0.1)
+        };
+
+        let err = engine
+            .execute_order(order("BTC/USD", OrderType::Limit { price: 75000.0 }))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::PriceOutsideBand)
+        ));
+        engine
+            .execute_order(order("BTC/USD", OrderType::Limit { price: 50500.0 }))
+            .await
+            .unwrap();
+
+        // No reference price for ETH/USD
+        assert!(engine
+            .execute_order(order("ETH/USD", OrderType::Market))
+            .await
+            .is_err());
+        assert!(engine
+            .execute_order(order("ETH/USD", OrderType::Limit { price: 3000.0 }))
+            .await
+            .is_err());
+
+        let fail_open = ExecutionEngine::new(SigningKey::generate())
+            .with_price_source(Arc::new(StaticPriceSource::new()))
+            .with_price_band(PriceBand::new(5.0).fail_open());
+        fail_open
+            .execute_order(order("ETH/USD", OrderType::Limit { price: 3000.0 }))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_marketable_limits_rejected() {
+        let prices = StaticPriceSource::new().with_price("BTC/USD", 50000.0);
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_price_source(Arc::new(prices))
+            .with_reject_marketable_limits(true);
+        let limit =
+            |side, price| Order::new("BTC/USD".to_string(), side, OrderType::Limit { price }, 0.1);
+
+        for order in [
+            limit(OrderSide::Buy, 50100.0),
+            limit(OrderSide::Sell, 49900.0),
+        ] {
+            let err = engine.execute_order(order).await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Execution(ExecutionError::MarketableLimit)
+            ));
+        }
+
+        engine
+            .execute_order(limit(OrderSide::Buy, 49900.0))
+            .await
+            .unwrap();
+        engine
+            .execute_order(limit(OrderSide::Sell, 50100.0))
+            .await
+            .unwrap();
+
+        // The simulator's placeholder price is not a market price
+        let unpriced =
+            ExecutionEngine::new(SigningKey::generate()).with_reject_marketable_limits(true);
+        unpriced
+            .execute_order(limit(OrderSide::Buy, 50100.0))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_basket_atomicity() {
+        // No price for ETH/USD, so that market leg fails at the venue
+        let prices = StaticPriceSource::new().with_price("BTC/USD", 50000.0);
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_price_source(Arc::new(prices));
+        let basket = || {
+            vec![
+                Order::new(
+                    "BTC/USD".to_string(),
+                    OrderSide::Buy,
+                    OrderType::Market,
+                    1.0,
+                ),
+                Order::new(
+                    "ETH/USD".to_string(),
+                    OrderSide::Sell,
+                    OrderType::Market,
+                    10.0,
+                ),
+                Order::new(
+                    "BTC/USD".to_string(),
+                    OrderSide::Buy,
+                    OrderType::Market,
+                    0.5,
+                ),
+            ]
+        };
+
+        let results = engine
+            .execute_basket(basket(), Atomicity::AllOrNone)
+            .await
+            .unwrap();
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                OrderStatus::Executed,
+                OrderStatus::Failed,
+                OrderStatus::Rejected
+            ]
+        );
+        // The filled first leg was offset
+        assert_eq!(engine.position("BTC/USD").net_quantity, 0.0);
+
+        let results = engine
+            .execute_basket(basket(), Atomicity::BestEffort)
+            .await
+            .unwrap();
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                OrderStatus::Executed,
+                OrderStatus::Failed,
+                OrderStatus::Executed
+            ]
+        );
+        assert_eq!(engine.position("BTC/USD").net_quantity, 1.5);
+
+        // All-or-none validates every leg before sending any
+        let mut invalid = basket();
+        invalid[2].quantity = -1.0;
+        assert!(engine
+            .execute_basket(invalid, Atomicity::AllOrNone)
+            .await
+            .is_err());
+        assert_eq!(engine.position("BTC/USD").net_quantity, 1.5);
+    }
+
+    #[tokio::test]
+    async fn test_reduce_only_orders() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = |side, quantity, reduce_only| {
+            Order::builder()
+                .symbol("BTC/USD")
+                .side(side)
+                .limit(50000.0)
+                .quantity(quantity)
+                .reduce_only(reduce_only)
+                .build()
+                .unwrap()
+        };
+        let violation =
+            |err: Error| matches!(err, Error::Execution(ExecutionError::ReduceOnlyViolation));
+
+        // Nothing to reduce while flat
+        let err = engine
+            .execute_order(order(OrderSide::Sell, 0.1, true))
+            .await
+            .unwrap_err();
+        assert!(violation(err));
+
+        engine
+            .execute_order(order(OrderSide::Buy, 1.0, false))
+            .await
+            .unwrap();
+        // Over-closing would flip the position short
+        let err = engine
+            .execute_order(order(OrderSide::Sell, 1.5, true))
+            .await
+            .unwrap_err();
+        assert!(violation(err));
+        // Adding to the long is not a reduction either
+        let err = engine
+            .execute_order(order(OrderSide::Buy, 0.1, true))
+            .await
+            .unwrap_err();
+        assert!(violation(err));
+
+        engine
+            .execute_order(order(OrderSide::Sell, 0.4, true))
+            .await
+            .unwrap();
+        // Exactly closing the rest is allowed
+        engine
+            .execute_order(order(OrderSide::Sell, 0.6, true))
+            .await
+            .unwrap();
+        assert!(engine.position("BTC/USD").is_flat());
+    }
+
+    #[test]
+    fn test_reduce_only_is_signed() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Market,
+            0.1,
+        );
+        let plain = order.canonical_bytes().unwrap();
+        order.reduce_only = true;
+        assert_eq!(order.canonical_bytes().unwrap().len(), plain.len() + 1);
+
+        order.sign(&key).unwrap();
+        order.reduce_only = false;
+        assert!(order.verify(&key.verification_key()).is_err());
+    }
+
+    #[test]
+    fn test_urgency_is_signed() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        // Normal urgency adds nothing, so existing signatures stay valid
+        let plain = order.canonical_bytes().unwrap();
+        order.urgency = Urgency::Urgent;
+        assert_eq!(order.canonical_bytes().unwrap().len(), plain.len() + 2);
+
+        order.sign(&key).unwrap();
+        order.urgency = Urgency::Passive;
+        assert!(order.verify(&key.verification_key()).is_err());
+    }
+
+    #[test]
+    fn test_later_layouts_tag_optional_fields() {
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let parent = Uuid::new_v4();
+        order.parent_id = Some(parent);
+        order.reduce_only = true;
+        order.urgency = Urgency::Urgent;
+
+        let mut tail = vec![b'p'];
+        tail.extend_from_slice(parent.as_bytes());
+        tail.extend_from_slice(&[b'r', 1, b'u', 2]);
+        let v2 = order
+            .canonical_bytes_versioned(CanonicalVersion::V2)
+            .unwrap();
+        assert!(v2.ends_with(&tail));
+        assert!(order.canonical_bytes_scaled(2, 8).unwrap().ends_with(&tail));
+
+        // V1 keeps its untagged bytes
+        let mut v1_tail = parent.as_bytes().to_vec();
+        v1_tail.extend_from_slice(&[1, b'u', 2]);
+        let v1 = order
+            .canonical_bytes_versioned(CanonicalVersion::V1)
+            .unwrap();
+        assert!(v1.ends_with(&v1_tail));
+        assert!(!v1.ends_with(&tail));
+    }
+
+    #[test]
+    fn test_quote_quantity_is_signed() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            100.0,
+        );
+        assert_eq!(order.order_quantity(), OrderQuantity::Base(100.0));
+        let plain = order.canonical_bytes().unwrap();
+        order.set_order_quantity(OrderQuantity::Quote(100.0));
+        assert_eq!(order.canonical_bytes().unwrap().len(), plain.len() + 4);
+        assert!(order.to_string().contains("QUOTE-QTY"));
+        // Later layouts mark it with a single tag byte
+        let v2 = order
+            .canonical_bytes_versioned(CanonicalVersion::V2)
+            .unwrap();
+        assert_eq!(v2.last(), Some(&b'q'));
+        assert!(order.canonical_bytes_scaled(2, 2).unwrap().ends_with(b"q"));
+
+        // Reading 100 dollars as 100 coins must not verify
+        order.sign(&key).unwrap();
+        order.quote_quantity = false;
+        assert!(order.verify(&key.verification_key()).is_err());
+        order.quote_quantity = true;
+        order.sign_json(&key).unwrap();
+        order.quote_quantity = false;
+        assert!(order.verify_json(&key.verification_key()).is_err());
+    }
+
+    #[test]
+    fn test_result_diff() {
+        let recorded = OrderResult {
+            order_id: Uuid::new_v4(),
+            status: OrderStatus::Pending,
+            execution_price: Some(50000.0),
+            executed_quantity: Some(0.1),
+            timestamp: Utc::now(),
+            message: None,
+            timings: None,
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: None,
+        };
+        assert!(recorded.diff(&recorded).is_empty());
+
+        let venue = OrderResult {
+            status: OrderStatus::Executed,
+            timestamp: Utc::now(),
+            message: Some("filled".to_string()),
+            ..recorded.clone()
+        };
+        assert_eq!(
+            recorded.diff(&venue),
+            vec![FieldChange {
+                field: "status",
+                before: Some("pending".to_string()),
+                after: Some("executed".to_string()),
+            }]
+        );
+
+        let unfilled = OrderResult {
+            execution_price: None,
+            ..recorded.clone()
+        };
+        let changes = recorded.diff(&unfilled);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "execution_price");
+        assert_eq!(changes[0].before.as_deref(), Some("50000"));
+        assert_eq!(changes[0].after, None);
+    }
+
+    #[test]
+    fn test_round_prices() {
+        let mut result = OrderResult {
+            order_id: Uuid::new_v4(),
+            status: OrderStatus::Executed,
+            execution_price: Some(50000.000000001),
+            executed_quantity: Some(0.1 + 0.2),
+            timestamp: Utc::now(),
+            message: None,
+            timings: None,
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: Some(0.0123456),
+        };
+
+        let json = result.to_json_rounded(3).unwrap();
+        assert!(json.contains(r#""execution_price":50000.0,"#));
+        assert!(json.contains(r#""executed_quantity":0.3,"#));
+        // The original keeps full precision
+        assert_eq!(result.executed_quantity, Some(0.1 + 0.2));
+
+        result.round_prices(3);
+        assert_eq!(result.execution_price, Some(50000.0));
+        assert_eq!(result.executed_quantity, Some(0.3));
+        assert_eq!(result.fee, Some(0.012));
+
+        let mut unfilled = OrderResult {
+            execution_price: None,
+            executed_quantity: None,
+            ..result
+        };
+        unfilled.round_prices(3);
+        assert_eq!(unfilled.execution_price, None);
+    }
+
+    #[test]
+    fn test_split_by_max() {
+        let order = |quantity| {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 50000.0 },
+                quantity,
+            )
+        };
+
+        let parent = order(2.5);
+        let children = parent.split_by_max(1.0);
+        let quantities: Vec<f64> = children.iter().map(|c| c.quantity).collect();
+        assert_eq!(quantities, vec![1.0, 1.0, 0.5]);
+        assert!(children.iter().all(|c| c.parent_id == Some(parent.id)));
+        assert!(children.iter().all(|c| c.order_type == parent.order_type));
+
+        // Exact multiples despite float noise produce no dust slice
+        assert_eq!(order(0.9).split_by_max(0.3).len(), 3);
+
+        let small = order(0.4);
+        let children = small.split_by_max(1.0);
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].quantity, 0.4);
+        assert_eq!(children[0].parent_id, Some(small.id));
+    }
+
+    #[test]
+    fn test_slices_sum_exactly_to_parent() {
+        assert_eq!(reconcile_slices(1.0, &[0.5, 0.5]), 0.0);
+        assert_ne!(reconcile_slices(0.9, &[0.3, 0.3, 0.3]), 0.0);
+
+        for (total, parts) in [(1.0, 3), (0.7, 3), (0.1, 7), (2.3, 9), (1e-3, 11)] {
+            let mut slices = vec![total / parts as f64; parts];
+            absorb_residual(total, &mut slices);
+            assert_eq!(
+                slices.iter().sum::<f64>(),
+                total,
+                "{} into {}",
+                total,
+                parts
+            );
+            assert_eq!(reconcile_slices(total, &slices), 0.0);
+        }
+
+        for (total, max_qty) in [(1.0, 0.3), (0.9, 0.3), (2.5, 1.0), (0.7, 0.2), (1.0, 0.07)] {
+            let slices = slice_quantities(total, max_qty);
+            assert_eq!(
+                slices.iter().sum::<f64>(),
+                total,
+                "{} by {}",
+                total,
+                max_qty
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orders_above_max_qty_are_split() {
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_symbol_rules(
+                "BTC/USD",
+                SymbolRules {
+                    max_order_qty: Some(1.0),
+                    ..Default::default()
+                },
+            )
+            .with_fee_model(Arc::new(RebateFeeModel {
+                maker_rebate_bps: 2.0,
+                taker_fee_bps: 5.0,
+            }));
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            2.5,
+        );
+        let id = order.id;
+
+        let result = engine.execute_order(order).await.unwrap();
+        assert_eq!(result.order_id, id);
+        assert_eq!(result.status, OrderStatus::Executed);
+        assert_eq!(result.executed_quantity, Some(2.5));
+        assert_eq!(result.execution_price, Some(50000.0));
+        assert_eq!(engine.metrics().orders_submitted, 3);
+        assert_eq!(engine.position("BTC/USD").net_quantity, 2.5);
+        // The slices' rebates add up on the aggregate
+        assert!((result.fee.unwrap() + 25.0).abs() < 1e-9);
+        assert_eq!(result.fee, Some(engine.position("BTC/USD").fees));
+        let parent = engine.tracked_order(id).unwrap();
+        assert_eq!(parent.result.unwrap().status, OrderStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_split_parent_is_checked_once() {
+        let trader = SigningKey::generate();
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_order_verifier(Verifier::new([trader.verification_key()]))
+            .with_max_signal_age(Duration::from_secs(60))
+            .with_risk_limits(RiskLimits {
+                max_order_notional: Some(100_000.0),
+                ..Default::default()
+            })
+            .with_symbol_rules(
+                "BTC/USD",
+                SymbolRules {
+                    max_order_qty: Some(1.0),
+                    ..Default::default()
+                },
+            );
+        let parent = |quantity| {
+            let mut order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 50000.0 },
+                quantity,
+            );
+            order.source_timestamp = Some(Utc::now());
+            order.sign(&trader).unwrap();
+            order
+        };
+
+        // Slices are signed by the engine, not the trader, and still go out
+        let order = parent(1.5);
+        let result = engine.execute_order(order.clone()).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Executed);
+        let resubmitted = engine.execute_order(order).await.unwrap_err();
+        assert!(matches!(
+            resubmitted,
+            Error::Execution(ExecutionError::Replay)
+        ));
+
+        // Limits apply to the whole parent, not just each slice
+        let err = engine.execute_order(parent(2.5)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+        let mut market = parent(2.5);
+        market.order_type = OrderType::Market;
+        market.sign(&trader).unwrap();
+        let err = engine
+            .execute_twap(market, 3, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+        let err = engine
+            .execute_iceberg(parent(2.5), 1.0, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::RiskLimit(_))
+        ));
+        assert_eq!(engine.metrics().orders_submitted, 2);
+
+        let mut unsigned = parent(1.5);
+        unsigned.signature = None;
+        assert!(matches!(
+            engine.execute_order(unsigned).await.unwrap_err(),
+            Error::Crypto(_)
+        ));
+    }
+
+    #[test]
+    fn test_child_orders() {
+        let mut parent = Order::builder()
+            .symbol("BTC/USD")
+            .side(OrderSide::Sell)
+            .limit(50000.0)
+            .quantity(1.0)
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .correlation_id("request-1")
+            .build()
+            .unwrap();
+        parent.sign(&SigningKey::generate()).unwrap();
+
+        let child = parent.child(0.25).unwrap();
+        assert_ne!(child.id, parent.id);
+        assert_ne!(child.nonce, parent.nonce);
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert_eq!(child.quantity, 0.25);
+        assert_eq!(child.symbol, parent.symbol);
+        assert_eq!(child.side, parent.side);
+        assert_eq!(child.order_type, parent.order_type);
+        assert_eq!(child.time_in_force, parent.time_in_force);
+        assert_eq!(child.correlation_id.as_deref(), Some("request-1"));
+        assert_eq!(child.source_timestamp, parent.source_timestamp);
+        assert!(child.signature.is_none());
+        assert!(parent.child(1.5).is_err());
+        for quantity in [0.0, -0.25, f64::NAN] {
+            assert!(parent.child(quantity).is_err());
+        }
+
+        // The parent link is covered by both signing encodings
+        let mut orphan = child.clone();
+        orphan.parent_id = None;
+        assert_ne!(
+            child.canonical_bytes().unwrap(),
+            orphan.canonical_bytes().unwrap()
+        );
+        assert_ne!(
+            child.canonical_json().unwrap(),
+            orphan.canonical_json().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_iceberg() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            1.0,
+        );
+
+        let results = engine
+            .execute_iceberg(order.clone(), 0.3, Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.status == OrderStatus::Executed));
+        let filled: f64 = results.iter().filter_map(|r| r.executed_quantity).sum();
+        assert!((filled - 1.0).abs() < 1e-9);
+        let child = engine.tracked_order(results[0].order_id).unwrap().order;
+        assert_eq!(child.parent_id, Some(order.id));
+
+        assert!(engine
+            .execute_iceberg(order, 0.0, Duration::ZERO)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_iceberg_stops_on_failed_slice() {
+        // Slices rest at the venue, so the third breaches the open-order cap
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(RestingVenue::default()))
+            .with_risk_limits(RiskLimits {
+                max_open_orders: Some(2),
+                ..Default::default()
+            });
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            1.0,
+        );
+
+        let results = engine
+            .execute_iceberg(order, 0.25, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].status, OrderStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_execute_twap() {
+        let engine = ExecutionEngine::new(SigningKey::generate());
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+
+        let started = tokio::time::Instant::now();
+        let twap = engine
+            .execute_twap(order.clone(), 3, Duration::from_millis(30))
+            .await
+            .unwrap();
+        // Slices go out at 0, 10 and 20 ms
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(twap.slices.len(), 3);
+        let quantities: Vec<f64> = twap
+            .slices
+            .iter()
+            .filter_map(|r| r.executed_quantity)
+            .collect();
+        assert!((quantities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((quantities[0] - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(twap.vwap, Some(50000.0));
+
+        assert!(engine.execute_twap(order, 0, Duration::ZERO).await.is_err());
+    }
+
+    #[test]
+    fn test_vwap() {
+        let fill = |price, qty| OrderResult {
+            order_id: Uuid::new_v4(),
+            status: OrderStatus::Executed,
+            execution_price: Some(price),
+            executed_quantity: Some(qty),
+            timestamp: Utc::now(),
+            message: None,
+            timings: None,
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: None,
+        };
+        let unfilled = OrderResult {
+            status: OrderStatus::Failed,
+            execution_price: None,
+            executed_quantity: None,
+            ..fill(0.0, 0.0)
+        };
+
+        assert_eq!(vwap(&[]), None);
+        assert_eq!(vwap(std::slice::from_ref(&unfilled)), None);
+        assert_eq!(
+            vwap(&[fill(100.0, 1.0), fill(110.0, 3.0), unfilled]),
+            Some(107.5)
+        );
+    }
+
+    struct SlowAdapter;
+
+    #[async_trait::async_trait]
+    impl ExchangeAdapter for SlowAdapter {
+        async fn submit(&self, order: &Order) -> Result<OrderResult> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            SimulatedExchange::new().submit(order).await
+        }
+
+        async fn query_status(&self, id: Uuid) -> Result<OrderResult> {
+            Ok(OrderResult {
+                order_id: id,
+                status: OrderStatus::Executed,
+                execution_price: Some(50000.0),
+                executed_quantity: Some(0.1),
+                timestamp: Utc::now(),
+                message: None,
+                timings: None,
+                book_snapshot: None,
+                exchange_order_id: None,
+                fee: None,
+            })
+        }
+
+        async fn cancel(&self, id: Uuid) -> Result<OrderResult> {
+            Err(ExecutionError::UnknownOrder(id).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_order_timeout() {
+        let engine =
+            ExecutionEngine::new(SigningKey::generate()).with_adapter(Arc::new(SlowAdapter));
         let order = Order::new(
             "BTC/USD".to_string(),
             OrderSide::Buy,
             OrderType::Market,
             0.1,
         );
+        let id = order.id;
 
-        let result = engine.execute_order(order).await;
-        assert!(result.is_ok());
+        let err = engine
+            .execute_order_timeout(order, Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Timeout)));
+        assert_eq!(engine.unresolved_orders(), vec![id]);
+        assert!(engine.tracked_order(id).unwrap().result.is_none());
+
+        let results = engine.reconcile(&[id]).await.unwrap();
+        assert_eq!(results[0].status, OrderStatus::Executed);
+        assert!(engine.unresolved_orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_signals_rejected() {
+        use crate::clock::FixedClock;
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_clock(Arc::new(FixedClock::new(now)))
+            .with_max_signal_age(Duration::from_secs(30));
+        let signal = |age: i64| TradingSignal {
+            symbol: "BTC/USD".to_string(),
+            signal_type: "buy".to_string(),
+            strength: 0.9,
+            timestamp: now.timestamp() - age,
+            metadata: serde_json::Value::Null,
+        };
+
+        let fresh = Order::from_signal(&signal(10), 0.1).unwrap();
+        assert_eq!(
+            fresh.source_timestamp,
+            Some(now - chrono::Duration::seconds(10))
+        );
+        engine.execute_order(fresh).await.unwrap();
+
+        let stale = Order::from_signal(&signal(31), 0.1).unwrap();
+        let err = engine.execute_order(stale).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::SignalTooOld)
+        ));
+
+        // Orders not derived from a signal are not age-checked
+        let manual = Order::new_at(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+            now - chrono::Duration::hours(1),
+        );
+        engine.execute_order(manual).await.unwrap();
+    }
+
+    #[test]
+    fn test_source_timestamp_is_signed() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let legacy = order.canonical_bytes().unwrap();
+        order.source_timestamp = Some(order.timestamp);
+        assert_eq!(order.canonical_bytes().unwrap().len(), legacy.len() + 8);
+
+        order.sign(&key).unwrap();
+        order.source_timestamp = Some(order.timestamp + chrono::Duration::seconds(60));
+        assert!(order.verify(&key.verification_key()).is_err());
+    }
+
+    #[test]
+    fn test_scaled_encoding() {
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 1.1 },
+            0.3,
+        );
+        let scaled = order.to_scaled(2, 8).unwrap();
+        assert_eq!(scaled.price, Some(110));
+        assert_eq!(scaled.quantity, 30_000_000);
+        let market = Order {
+            order_type: OrderType::Market,
+            ..order.clone()
+        };
+        assert_eq!(market.to_scaled(2, 8).unwrap().price, None);
+
+        // The integers are what is signed, little-endian after the side
+        let bytes = order.canonical_bytes_scaled(2, 8).unwrap();
+        let price_at = 1 + 4 + 4 + 16 + 4 + order.symbol.len() + 1 + 1;
+        assert_eq!(bytes[price_at..price_at + 8], 110i64.to_le_bytes());
+        assert_ne!(bytes, order.canonical_bytes_scaled(3, 8).unwrap());
+
+        // A signature only verifies at the scales it was made with
+        let key = SigningKey::generate();
+        let scheme = SigningScheme::Scaled {
+            price_scale: 2,
+            qty_scale: 8,
+        };
+        let mut signed = order.clone();
+        signed.sign_with(&key, scheme).unwrap();
+        signed.verify_with(&key.verification_key(), scheme).unwrap();
+        let other = SigningScheme::Scaled {
+            price_scale: 4,
+            qty_scale: 8,
+        };
+        assert!(signed.verify_with(&key.verification_key(), other).is_err());
+
+        assert!(order.to_scaled(MAX_SCALE + 1, 0).is_err());
+        let huge = Order {
+            quantity: 1e12,
+            ..order.clone()
+        };
+        assert!(huge.to_scaled(2, 18).is_err());
+
+        // Digits beyond the scale are rejected rather than rounded away
+        let err = order.to_scaled(0, 8).unwrap_err();
+        assert!(err.to_string().contains("not exact at scale 0"));
+        let fine = Order {
+            quantity: 0.123456789,
+            ..order.clone()
+        };
+        assert!(fine.to_scaled(2, 8).is_err());
+        assert_eq!(fine.to_scaled(2, 9).unwrap().quantity, 123_456_789);
+    }
+
+    #[test]
+    fn test_correlation_id_is_not_signed() {
+        let key = SigningKey::generate();
+        let mut first = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let mut second = first.clone();
+        first.correlation_id = Some("request-1".to_string());
+        second.correlation_id = Some("request-2".to_string());
+        // Tags are unsigned metadata too
+        first.tags = vec!["momentum-v2".to_string()];
+
+        first.sign(&key).unwrap();
+        second.sign(&key).unwrap();
+        assert_eq!(first.signature, second.signature);
+        assert_eq!(
+            first.canonical_json().unwrap(),
+            second.canonical_json().unwrap()
+        );
+
+        // Rewriting it in transit leaves the signature valid
+        first.correlation_id = None;
+        first.verify(&key.verification_key()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_pending_orders() {
+        use crate::clock::FixedClock;
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let engine = ExecutionEngine::new(SigningKey::generate())
+            .with_adapter(Arc::new(SlowAdapter))
+            .with_clock(clock.clone());
+        let stuck = Order::new_at(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+            start,
+        );
+        let id = stuck.id;
+        engine
+            .execute_order_timeout(stuck, Duration::from_millis(20))
+            .await
+            .unwrap_err();
+
+        let threshold = Duration::from_secs(60);
+        assert!(engine.stale_pending_orders(threshold).is_empty());
+        clock.advance(chrono::Duration::seconds(61));
+        assert_eq!(engine.stale_pending_orders(threshold), vec![id]);
+
+        // Once resolved it is no longer stale
+        engine.reconcile(&[id]).await.unwrap();
+        assert!(engine.stale_pending_orders(threshold).is_empty());
+    }
+
+    struct PanickingAdapter;
+
+    #[async_trait::async_trait]
+    impl ExchangeAdapter for PanickingAdapter {
+        async fn submit(&self, _order: &Order) -> Result<OrderResult> {
+            panic!("paper engine must not reach the live adapter");
+        }
+
+        async fn query_status(&self, _id: Uuid) -> Result<OrderResult> {
+            panic!("paper engine must not reach the live adapter");
+        }
+
+        async fn cancel(&self, _id: Uuid) -> Result<OrderResult> {
+            panic!("paper engine must not reach the live adapter");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paper_mode_uses_simulator() {
+        let engine = ExecutionEngine::new_paper(SigningKey::generate())
+            .with_adapter(Arc::new(PanickingAdapter));
+        assert!(engine.is_paper());
+        assert!(!ExecutionEngine::new(SigningKey::generate()).is_paper());
+
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let result = engine.execute_order(order).await.unwrap();
+        assert!(result.message.unwrap().starts_with("PAPER"));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_roundtrip_is_smaller_than_json() {
+        let key = SigningKey::generate();
+        let approver = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+        let approval = approver.sign(&order.canonical_bytes().unwrap());
+        order
+            .signatures
+            .push((approver.verification_key().key_id(), approval));
+        order.sign(&key).unwrap();
+
+        let binary = order.to_bincode().unwrap();
+        let json = serde_json::to_vec(&order).unwrap();
+        assert!(binary.len() < json.len());
+
+        let decoded = Order::from_bincode(&binary).unwrap();
+        assert_eq!(decoded, order);
+        assert!(decoded.verify(&key.verification_key()).is_ok());
+        assert!(Order::from_bincode(&binary[..binary.len() / 2]).is_err());
+
+        // JSON keeps its hex encoding
+        let from_json: Order = serde_json::from_slice(&json).unwrap();
+        assert_eq!(from_json, order);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_sums_reconcile_exactly() {
+        use rust_decimal::Decimal;
+
+        let a = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let b = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.2,
+        );
+
+        // The f64 sum drifts, the decimal sum does not
+        assert_ne!(a.quantity + b.quantity, 0.3);
+        let total = a.quantity_decimal().unwrap() + b.quantity_decimal().unwrap();
+        assert_eq!(total, Decimal::new(3, 1));
+
+        let limit = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 50000.01 },
+            0.1,
+        );
+        assert_eq!(
+            limit.limit_price_decimal().unwrap(),
+            Some(Decimal::new(5000001, 2))
+        );
+        assert!(Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            f64::NAN
+        )
+        .quantity_decimal()
+        .is_err());
     }
 }
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::execution::{Order, OrderType};
+
+/// Whether a fill added liquidity to the book or took it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+impl Liquidity {
+    /// Limit orders count as makers and market orders as takers
+    ///
+    /// A limit priced through the market takes liquidity at a real venue;
+    /// engines that allow those (see
+    /// [`ExecutionEngine::with_reject_marketable_limits`](crate::ExecutionEngine::with_reject_marketable_limits))
+    /// will under-charge them.
+    pub fn of(order: &Order) -> Self {
+        match order.order_type {
+            OrderType::Limit { .. } => Liquidity::Maker,
+            OrderType::Market => Liquidity::Taker,
+        }
+    }
+}
+
+/// Venue fee charged on a fill, in the quote currency
+///
+/// Fees are signed: positive is a cost paid to the venue and negative is a
+/// rebate paid by it. [`Position::net_pnl`](crate::Position::net_pnl)
+/// subtracts fees from realized PnL, so rebates add to it.
+pub trait FeeModel: Send + Sync {
+    /// Fee for filling `quantity` of `order` at `price`
+    fn fee(&self, order: &Order, price: f64, quantity: f64) -> f64;
+}
+
+/// Makers earn `maker_rebate_bps` and takers pay `taker_fee_bps`, both in
+/// basis points of the fill's notional
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RebateFeeModel {
+    pub maker_rebate_bps: f64,
+    pub taker_fee_bps: f64,
+}
+
+impl FeeModel for RebateFeeModel {
+    fn fee(&self, order: &Order, price: f64, quantity: f64) -> f64 {
+        let notional = price * quantity;
+        match Liquidity::of(order) {
+            Liquidity::Maker => -notional * self.maker_rebate_bps / 10_000.0,
+            Liquidity::Taker => notional * self.taker_fee_bps / 10_000.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::OrderSide;
+
+    #[test]
+    fn test_rebate_fee_model() {
+        let model = RebateFeeModel {
+            maker_rebate_bps: 2.0,
+            taker_fee_bps: 5.0,
+        };
+        let order =
+            |order_type| Order::new("BTC/USD".to_string(), OrderSide::Sell, order_type, 1.0);
+
+        let maker = order(OrderType::Limit { price: 50000.0 });
+        assert_eq!(Liquidity::of(&maker), Liquidity::Maker);
+        assert!((model.fee(&maker, 50000.0, 1.0) + 10.0).abs() < 1e-9);
+
+        let taker = order(OrderType::Market);
+        assert_eq!(Liquidity::of(&taker), Liquidity::Taker);
+        assert!((model.fee(&taker, 50000.0, 2.0) - 50.0).abs() < 1e-9);
+    }
+}
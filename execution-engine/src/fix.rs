@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+use crate::execution::{Order, OrderQuantity, OrderSide, OrderType, TimeInForce};
+use crate::{ExecutionError, Result};
+
+/// Field delimiter
+pub const SOH: char = '\x01';
+
+const BEGIN_STRING: &str = "FIX.4.4";
+
+/// UTCTimestamp with milliseconds, e.g. `20240101-12:00:00.000`
+fn utc_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+/// Reject a free-text value that would corrupt the message: SOH ends the
+/// field early and `=` makes the rest parse as another tag
+fn check_value(tag: u32, name: &str, value: &str) -> Result<()> {
+    if value.contains([SOH, '=']) {
+        return Err(ExecutionError::Validation(format!(
+            "{} ({}) cannot contain SOH or '=' in a FIX message",
+            name, tag
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Sum of the bytes modulo 256, as three digits
+fn checksum(bytes: &[u8]) -> String {
+    let sum = bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    format!("{:03}", sum)
+}
+
+impl Order {
+    /// Encode as a FIX 4.4 NewOrderSingle (`35=D`) from `sender` to
+    /// `target` with message sequence number `seq`, sent at `sending_time`
+    ///
+    /// ClOrdID (11) is the [`client_order_id`](Order::client_order_id),
+    /// falling back to the order id, and TransactTime (60) is the order's
+    /// timestamp; [`ExecutionEngine::to_fix`](crate::ExecutionEngine::to_fix)
+    /// takes SendingTime (52) from the engine clock. The sender, target,
+    /// ClOrdID and symbol are sent verbatim, so one containing SOH or `=`
+    /// fails with [`ExecutionError::Validation`]. Quote-sized orders
+    /// carry CashOrderQty (152) instead of OrderQty (38). Only the fields
+    /// the engine models are sent: no account, exec instructions or
+    /// session-level handling, which belong to the FIX session that
+    /// transmits the message.
+    pub fn to_fix(
+        &self,
+        sender: &str,
+        target: &str,
+        seq: u32,
+        sending_time: DateTime<Utc>,
+    ) -> Result<String> {
+        let id = self.id.to_string();
+        let cl_ord_id = self.client_order_id.as_deref().unwrap_or(&id);
+        check_value(49, "SenderCompID", sender)?;
+        check_value(56, "TargetCompID", target)?;
+        check_value(11, "ClOrdID", cl_ord_id)?;
+        check_value(55, "Symbol", &self.symbol)?;
+        let side = match self.side {
+            OrderSide::Buy => "1",
+            OrderSide::Sell => "2",
+        };
+        let time_in_force = match self.time_in_force {
+            TimeInForce::GoodTilCancelled => "1",
+            TimeInForce::ImmediateOrCancel => "3",
+            TimeInForce::FillOrKill => "4",
+        };
+
+        let mut body = String::new();
+        let mut field = |tag: u32, value: &str| {
+            let _ = write!(body, "{}={}{}", tag, value, SOH);
+        };
+        field(35, "D");
+        field(49, sender);
+        field(56, target);
+        field(34, &seq.to_string());
+        field(52, &utc_timestamp(sending_time));
+        field(11, cl_ord_id);
+        field(55, &self.symbol);
+        field(54, side);
+        field(60, &utc_timestamp(self.timestamp));
+        match self.order_quantity() {
+            OrderQuantity::Base(quantity) => field(38, &quantity.to_string()),
+            OrderQuantity::Quote(amount) => field(152, &amount.to_string()),
+        }
+        match self.order_type {
+            OrderType::Market => field(40, "1"),
+            OrderType::Limit { price } => {
+                field(40, "2");
+                field(44, &price.to_string());
+            }
+        }
+        field(59, time_in_force);
+
+        let mut message = format!("8={}{}9={}{}{}", BEGIN_STRING, SOH, body.len(), SOH, body);
+        let checksum = checksum(message.as_bytes());
+        let _ = write!(message, "10={}{}", checksum, SOH);
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// `(tag, value)` pairs of a message, in order
+    fn parse(message: &str) -> Vec<(u32, &str)> {
+        message
+            .strip_suffix(SOH)
+            .unwrap()
+            .split(SOH)
+            .map(|field| {
+                let (tag, value) = field.split_once('=').unwrap();
+                (tag.parse().unwrap(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_new_order_single() {
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 50000.5 },
+            0.25,
+        );
+        order.time_in_force = TimeInForce::ImmediateOrCancel;
+        let sent = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let message = order.to_fix("TINYWINDOW", "VENUE", 7, sent).unwrap();
+        let fields = parse(&message);
+        let value = |tag| fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+
+        // Header first and trailer last, in the order FIX requires
+        let tags: Vec<u32> = fields.iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(tags[..3], [8, 9, 35]);
+        assert_eq!(tags.last(), Some(&10));
+        assert_eq!(value(8), Some("FIX.4.4"));
+        assert_eq!(value(35), Some("D"));
+        assert_eq!(value(49), Some("TINYWINDOW"));
+        assert_eq!(value(56), Some("VENUE"));
+        assert_eq!(value(34), Some("7"));
+        assert_eq!(value(52), Some("20240102-03:04:05.000"));
+        assert_eq!(value(11), Some(order.id.to_string().as_str()));
+        assert_eq!(value(55), Some("BTC/USD"));
+        assert_eq!(value(54), Some("2"));
+        assert_eq!(value(38), Some("0.25"));
+        assert_eq!(value(40), Some("2"));
+        assert_eq!(value(44), Some("50000.5"));
+        assert_eq!(value(59), Some("3"));
+
+        // Body length counts from after the 9 field up to the checksum
+        let trailer = message.rfind("10=").unwrap();
+        let body_start = message.find("35=").unwrap();
+        assert_eq!(value(9), Some((trailer - body_start).to_string().as_str()));
+        let sum = message.as_bytes()[..trailer]
+            .iter()
+            .map(|b| *b as u32)
+            .sum::<u32>();
+        assert_eq!(value(10), Some(format!("{:03}", sum % 256).as_str()));
+
+        // Market orders carry no price, and a client id replaces the id
+        let mut market = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        market.client_order_id = Some("tw-1".to_string());
+        let message = market.to_fix("A", "B", 1, sent).unwrap();
+        let fields = parse(&message);
+        let value = |tag| fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+        assert_eq!(value(40), Some("1"));
+        assert_eq!(value(44), None);
+        assert_eq!(value(54), Some("1"));
+        assert_eq!(value(11), Some("tw-1"));
+
+        market.set_order_quantity(OrderQuantity::Quote(100.0));
+        let message = market.to_fix("A", "B", 2, sent).unwrap();
+        let fields = parse(&message);
+        let value = |tag| fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+        assert_eq!(value(152), Some("100"));
+        assert_eq!(value(38), None);
+    }
+
+    #[test]
+    fn test_rejects_delimiters_in_values() {
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let now = Utc::now();
+        assert!(order.to_fix("A\x01", "B", 1, now).is_err());
+        assert!(order.to_fix("A", "B=C", 1, now).is_err());
+
+        let mut injected = order.clone();
+        injected.client_order_id = Some(format!("tw-1{}44=1", SOH));
+        let err = injected.to_fix("A", "B", 1, now).unwrap_err();
+        assert!(err.to_string().contains("ClOrdID (11)"));
+
+        let mut symbol = order;
+        symbol.symbol = "BTC=USD".to_string();
+        assert!(symbol.to_fix("A", "B", 1, now).is_err());
+    }
+
+    #[test]
+    fn test_engine_stamps_sending_time_from_its_clock() {
+        let at = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let engine = crate::ExecutionEngine::new(crate::SigningKey::generate())
+            .with_clock(std::sync::Arc::new(crate::FixedClock::new(at)));
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let message = engine.to_fix(&order, "A", "B", 1).unwrap();
+        let fields = parse(&message);
+        assert!(fields.contains(&(52, "20240601-12:00:00.000")));
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{execution::OrderSide, ExecutionError, Result};
+
+/// Normalized `BASE/QUOTE` trading pair
+///
+/// Parsing uppercases and accepts `-` as an alternative separator, so
+/// "btc/usd", "BTC-USD" and "BTC/USD" all become `BTC/USD`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Symbol(String);
+
+impl Symbol {
+    pub fn parse(s: &str) -> Result<Symbol> {
+        let normalized = s.trim().to_ascii_uppercase().replace('-', "/");
+        let invalid =
+            || ExecutionError::Validation(format!("invalid symbol '{}': expected BASE/QUOTE", s));
+
+        let (base, quote) = normalized.split_once('/').ok_or_else(invalid)?;
+        let valid_part =
+            |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid_part(base) || !valid_part(quote) {
+            return Err(invalid().into());
+        }
+
+        Ok(Symbol(normalized))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn base(&self) -> &str {
+        self.0
+            .split_once('/')
+            .map(|(base, _)| base)
+            .unwrap_or_default()
+    }
+
+    pub fn quote(&self) -> &str {
+        self.0
+            .split_once('/')
+            .map(|(_, quote)| quote)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Symbol {
+    type Error = crate::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Symbol::parse(&s)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> String {
+        symbol.0
+    }
+}
+
+/// Direction used when snapping a value to an increment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingMode {
+    /// Nearest increment, halves away from zero
+    Nearest,
+    /// Toward negative infinity
+    #[default]
+    Down,
+    /// Toward positive infinity
+    Up,
+    /// Toward zero
+    TowardZero,
+}
+
+/// Trading rules a venue applies to one symbol
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolRules {
+    /// Minimum price increment
+    pub tick_size: Option<f64>,
+    /// Minimum quantity increment
+    pub lot_size: Option<f64>,
+    /// Rounding applied to buy quantities
+    pub buy_rounding: RoundingMode,
+    /// Rounding applied to sell quantities
+    pub sell_rounding: RoundingMode,
+    /// Largest quantity the venue accepts in one order; the engine splits
+    /// larger orders (see [`Order::split_by_max`](crate::Order::split_by_max))
+    pub max_order_qty: Option<f64>,
+}
+
+impl SymbolRules {
+    pub fn rounding_for(&self, side: OrderSide) -> RoundingMode {
+        match side {
+            OrderSide::Buy => self.buy_rounding,
+            OrderSide::Sell => self.sell_rounding,
+        }
+    }
+}
+
+/// Snap a price to a multiple of `tick_size`
+pub fn round_to_tick(price: f64, tick_size: f64, mode: RoundingMode) -> f64 {
+    round_to_increment(price, tick_size, mode)
+}
+
+/// Snap a quantity to a multiple of `lot_size`
+pub fn round_to_lot(quantity: f64, lot_size: f64, mode: RoundingMode) -> f64 {
+    round_to_increment(quantity, lot_size, mode)
+}
+
+fn round_to_increment(value: f64, increment: f64, mode: RoundingMode) -> f64 {
+    if !increment.is_finite() || increment <= 0.0 || !value.is_finite() {
+        return value;
+    }
+
+    let steps = value / increment;
+    // Values already on an increment must not move because of float noise
+    // (0.3 / 0.1 is 2.9999999999999996)
+    let nearest = steps.round();
+    let steps = if (steps - nearest).abs() < 1e-9 {
+        nearest
+    } else {
+        match mode {
+            RoundingMode::Nearest => nearest,
+            RoundingMode::Down => steps.floor(),
+            RoundingMode::Up => steps.ceil(),
+            RoundingMode::TowardZero => steps.trunc(),
+        }
+    };
+
+    // Strip multiplication noise by rounding to the increment's precision
+    let decimals = decimal_places(increment);
+    let scale = 10f64.powi(decimals as i32);
+    (steps * increment * scale).round() / scale
+}
+
+/// Number of decimal places needed to write `value` (capped at 12)
+fn decimal_places(value: f64) -> u32 {
+    let mut decimals = 0;
+    let mut scaled = value;
+    while decimals < 12 && (scaled - scaled.round()).abs() > 1e-9 {
+        scaled *= 10.0;
+        decimals += 1;
+    }
+    decimals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_normalization() {
+        for input in ["btc/usd", "BTC-USD", " BTC/USD "] {
+            assert_eq!(Symbol::parse(input).unwrap().as_str(), "BTC/USD");
+        }
+
+        let symbol = Symbol::parse("eth/usdt").unwrap();
+        assert_eq!(symbol.base(), "ETH");
+        assert_eq!(symbol.quote(), "USDT");
+
+        for input in ["BTCUSD", "BTC/", "/USD", "BTC/USD/EUR", "BTC:PERP", ""] {
+            assert!(
+                Symbol::parse(input).is_err(),
+                "{} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_rounding_modes_at_boundaries() {
+        // Exactly halfway between ticks
+        assert_eq!(round_to_tick(100.25, 0.5, RoundingMode::Nearest), 100.5);
+        assert_eq!(round_to_tick(100.25, 0.5, RoundingMode::Down), 100.0);
+        assert_eq!(round_to_tick(100.25, 0.5, RoundingMode::Up), 100.5);
+        assert_eq!(round_to_tick(100.25, 0.5, RoundingMode::TowardZero), 100.0);
+
+        // Down and TowardZero differ below zero
+        assert_eq!(round_to_tick(-1.25, 0.5, RoundingMode::Down), -1.5);
+        assert_eq!(round_to_tick(-1.25, 0.5, RoundingMode::TowardZero), -1.0);
+
+        // Already on a lot boundary despite float noise
+        for mode in [
+            RoundingMode::Nearest,
+            RoundingMode::Down,
+            RoundingMode::Up,
+            RoundingMode::TowardZero,
+        ] {
+            assert_eq!(round_to_lot(0.1 + 0.2, 0.1, mode), 0.3);
+        }
+
+        assert_eq!(round_to_lot(0.129, 0.01, RoundingMode::Down), 0.12);
+        assert_eq!(round_to_lot(0.121, 0.01, RoundingMode::Up), 0.13);
+        assert_eq!(round_to_lot(5.0, 0.0, RoundingMode::Up), 5.0);
+    }
+}
@@ -1,12 +1,68 @@
+pub mod alert;
+pub mod audit;
+pub mod book;
+pub mod clock;
 pub mod crypto;
+pub mod dead_letter;
+pub mod decision;
+pub mod events;
+pub mod exchange;
 pub mod execution;
+pub mod fees;
+pub mod fix;
+pub mod instrument;
+mod jsonl;
+mod lru;
+pub mod metrics;
+pub mod order_id;
+pub mod position;
+pub mod pricing;
+pub mod rate_limit;
+mod retry;
+pub mod risk;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod signals;
+mod singleflight;
 pub mod storage;
+pub mod validation;
+pub mod verifier;
 
-pub use crypto::{Signature, SigningKey, VerificationKey};
-pub use execution::{ExecutionEngine, Order, OrderResult};
-pub use signals::SignalManager;
-pub use storage::Database;
+pub use alert::{
+    AlertSink, Level, NoopAlertSink, WebhookAlertSink, ALERT_QUEUE_CAPACITY, WEBHOOK_TIMEOUT,
+};
+pub use audit::{read_audit_log, AuditEvent, AuditSink, FileAuditSink};
+pub use book::{BookLevel, OrderBook};
+pub use clock::{Clock, FixedClock, SystemClock};
+#[cfg(feature = "secp256k1")]
+pub use crypto::Secp256k1;
+pub use crypto::{
+    Ed25519, HashAlgorithm, KeyRing, Signature, SignatureScheme, SigningKey, VerificationKey,
+};
+pub use dead_letter::{DeadLetter, DeadLetterSink, FileDeadLetterSink};
+pub use decision::Decision;
+pub use events::{EventBus, OrderEvent};
+pub use exchange::{ExchangeAdapter, FillModel, SimulatedExchange};
+pub use execution::{
+    reconcile_slices, vwap, Atomicity, CanonicalVersion, EngineMode, EngineSnapshot,
+    ExecutionEngine, ExecutionTimings, FieldChange, MultiSigPolicy, NonceWindow, Order,
+    OrderBuilder, OrderQuantity, OrderResult, OrderTemplate, ScaledOrder, SigningScheme,
+    SimulationReport, TimeInForce, TrackedOrder, TwapResult, Urgency,
+};
+pub use fees::{FeeModel, Liquidity, RebateFeeModel};
+pub use instrument::{RoundingMode, Symbol, SymbolRules};
+pub use metrics::EngineMetricsSnapshot;
+pub use order_id::{OrderIdGenerator, PrefixedGenerator, UuidGenerator};
+pub use position::Position;
+pub use pricing::{PriceSource, StaticPriceSource};
+pub use rate_limit::RateLimiter;
+pub use risk::{PriceBand, RiskLimits, RiskManager};
+pub use signals::{RedisPriceSource, SignalCodec, SignalManager};
+pub use storage::{
+    Cursor, Database, DecisionRecord, InMemoryStore, OrderQuery, OrderRecord, OrderStats, Store,
+};
+pub use validation::{NonEmptySymbol, PositiveLimitPrice, PositiveQuantity, ValidationRule};
+pub use verifier::{VerifiedCache, Verifier};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -20,10 +76,146 @@ pub enum Error {
     Redis(#[from] redis::RedisError),
 
     #[error("Execution error: {0}")]
-    Execution(String),
+    Execution(#[from] ExecutionError),
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[cfg(feature = "bincode")]
+    #[error("Binary encoding error: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack encoding error: {0}")]
+    MsgpackEncode(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack decoding error: {0}")]
+    MsgpackDecode(#[from] rmp_serde::decode::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Why an order could not be executed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ExecutionError {
+    /// The order failed local validation
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("insufficient funds")]
+    InsufficientFunds,
+
+    #[error("rejected by venue{}: {message}", code.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default())]
+    VenueRejected {
+        code: Option<String>,
+        message: String,
+    },
+
+    /// A pre-trade risk limit would be breached
+    #[error("risk limit: {0}")]
+    RiskLimit(String),
+
+    #[error("rate limited")]
+    RateLimited,
+
+    #[error("execution timed out")]
+    Timeout,
+
+    #[error("duplicate order")]
+    Duplicate,
+
+    /// The order's nonce was already processed for this signer
+    #[error("replay detected")]
+    Replay,
+
+    /// The engine's kill switch is engaged
+    #[error("engine halted")]
+    Halted,
+
+    #[error("unknown order {0}")]
+    UnknownOrder(uuid::Uuid),
+
+    /// A status update would move an order backwards (e.g. executed to pending)
+    #[error("illegal status transition {} -> {}", from.as_str(), to.as_str())]
+    IllegalTransition {
+        from: execution::OrderStatus,
+        to: execution::OrderStatus,
+    },
+
+    /// A limit price deviates too far from the reference price
+    #[error("price outside band")]
+    PriceOutsideBand,
+
+    /// A limit order priced through the market (buy above or sell below the
+    /// reference price) while marketable limits are rejected
+    #[error("marketable limit rejected")]
+    MarketableLimit,
+
+    /// A reduce-only order would open, grow or flip the position
+    #[error("reduce-only violation")]
+    ReduceOnlyViolation,
+
+    /// The signal the order was derived from is older than the allowed age
+    #[error("signal too old")]
+    SignalTooOld,
+
+    /// An opposite-side order on the symbol executed too recently
+    #[error("flip throttled")]
+    FlipThrottled,
+
+    /// The engine already tracks its maximum number of unresolved orders
+    #[error("pending capacity reached")]
+    PendingCapacity,
+}
+
+impl Error {
+    /// Short machine-readable name of the error kind, such as `database` or,
+    /// for execution errors, the [`ExecutionError::category`]
+    pub fn category(&self) -> &'static str {
+        match self {
+            Error::Crypto(_) => "crypto",
+            Error::Database(_) => "database",
+            Error::Redis(_) => "redis",
+            Error::Execution(e) => e.category(),
+            Error::Serialization(_) => "serialization",
+            #[cfg(feature = "bincode")]
+            Error::Bincode(_) => "bincode",
+            #[cfg(feature = "msgpack")]
+            Error::MsgpackEncode(_) | Error::MsgpackDecode(_) => "msgpack",
+            Error::Io(_) => "io",
+            Error::Http(_) => "http",
+        }
+    }
+}
+
+impl ExecutionError {
+    /// Short machine-readable name of the variant, for metrics and events
+    pub fn category(&self) -> &'static str {
+        match self {
+            ExecutionError::Validation(_) => "validation",
+            ExecutionError::InsufficientFunds => "insufficient_funds",
+            ExecutionError::VenueRejected { .. } => "venue_rejected",
+            ExecutionError::RiskLimit(_) => "risk_limit",
+            ExecutionError::RateLimited => "rate_limited",
+            ExecutionError::Timeout => "timeout",
+            ExecutionError::Duplicate => "duplicate",
+            ExecutionError::Replay => "replay",
+            ExecutionError::Halted => "halted",
+            ExecutionError::UnknownOrder(_) => "unknown_order",
+            ExecutionError::IllegalTransition { .. } => "illegal_transition",
+            ExecutionError::PriceOutsideBand => "price_outside_band",
+            ExecutionError::MarketableLimit => "marketable_limit",
+            ExecutionError::ReduceOnlyViolation => "reduce_only_violation",
+            ExecutionError::SignalTooOld => "signal_too_old",
+            ExecutionError::FlipThrottled => "flip_throttled",
+            ExecutionError::PendingCapacity => "pending_capacity",
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+
+/// Map that drops its least recently used entries once over capacity
+///
+/// Inserting or updating through [`get_mut`](Self::get_mut) counts as a
+/// use; [`get`](Self::get) does not. Only entries for which `evictable`
+/// holds are dropped, so the map can stay over capacity while every entry
+/// is still needed. Evictability is rechecked whenever an entry changes, so
+/// [`pinned`](Self::pinned) is constant time and eviction never scans past
+/// entries it cannot drop.
+#[derive(Debug)]
+pub(crate) struct LruMap<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    /// Evictable entries by last use, oldest first
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    /// `usize::MAX` for no bound
+    capacity: usize,
+    evictable: fn(&V) -> bool,
+    /// Entries for which `evictable` does not hold
+    pinned: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LruMap<K, V> {
+    pub(crate) fn new(capacity: usize, evictable: fn(&V) -> bool) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            capacity,
+            evictable,
+            pinned: 0,
+        }
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        let tick = self.next_tick();
+        if (self.evictable)(&value) {
+            self.order.insert(tick, key.clone());
+        } else {
+            self.pinned += 1;
+        }
+        if let Some((old_value, old)) = self.entries.insert(key, (value, tick)) {
+            if self.order.remove(&old).is_none() && !(self.evictable)(&old_value) {
+                self.pinned -= 1;
+            }
+        }
+        self.evict();
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &K) -> Option<EntryMut<'_, K, V>> {
+        let tick = self.next_tick();
+        let (value, last) = self.entries.get_mut(key)?;
+        let was_evictable = self.order.remove(last).is_some();
+        *last = tick;
+        Some(EntryMut {
+            key: Some(key.clone()),
+            tick,
+            value,
+            order: &mut self.order,
+            pinned: &mut self.pinned,
+            evictable: self.evictable,
+            was_evictable,
+        })
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.values().map(|(value, _)| value)
+    }
+
+    /// Number of entries that are not evictable
+    pub(crate) fn pinned(&self) -> usize {
+        self.pinned
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some((_, key)) = self.order.pop_first() else {
+                return;
+            };
+            self.entries.remove(&key);
+        }
+    }
+}
+
+/// A value borrowed from an [`LruMap`] for updating
+///
+/// Dropping it rechecks whether the entry is evictable, queueing it for
+/// eviction or counting it as pinned.
+pub(crate) struct EntryMut<'a, K, V> {
+    /// Taken when the entry is queued for eviction on drop
+    key: Option<K>,
+    tick: u64,
+    value: &'a mut V,
+    order: &'a mut BTreeMap<u64, K>,
+    pinned: &'a mut usize,
+    evictable: fn(&V) -> bool,
+    was_evictable: bool,
+}
+
+impl<K, V> Deref for EntryMut<'_, K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.value
+    }
+}
+
+impl<K, V> DerefMut for EntryMut<'_, K, V> {
+    fn deref_mut(&mut self) -> &mut V {
+        self.value
+    }
+}
+
+impl<K, V> Drop for EntryMut<'_, K, V> {
+    fn drop(&mut self) {
+        let evictable = (self.evictable)(self.value);
+        if let (true, Some(key)) = (evictable, self.key.take()) {
+            self.order.insert(self.tick, key);
+        }
+        match (self.was_evictable, evictable) {
+            (true, false) => *self.pinned += 1,
+            (false, true) => *self.pinned -= 1,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut map = LruMap::new(2, |_: &u32| true);
+        map.insert("a", 1);
+        map.insert("b", 2);
+        // Updating "a" makes "b" the least recently used
+        *map.get_mut(&"a").unwrap() += 10;
+        map.insert("c", 3);
+
+        assert_eq!(map.get(&"a"), Some(&11));
+        assert_eq!(map.get(&"b"), None);
+        assert_eq!(map.get(&"c"), Some(&3));
+        assert_eq!(map.values().count(), 2);
+    }
+
+    #[test]
+    fn test_keeps_entries_that_are_not_evictable() {
+        // Only finished entries may be dropped
+        let mut map = LruMap::new(1, |(_, finished): &(u32, bool)| *finished);
+        map.insert("a", (1, false));
+        map.insert("b", (2, true));
+        map.insert("c", (3, false));
+        assert!(map.get(&"a").is_some());
+        assert!(map.get(&"b").is_none());
+        assert!(map.get(&"c").is_some());
+
+        // A new entry can be the one evicted
+        map.insert("d", (4, true));
+        assert!(map.get(&"d").is_none());
+
+        map.set_capacity(usize::MAX);
+        map.insert("e", (5, true));
+        assert_eq!(map.values().count(), 3);
+    }
+
+    #[test]
+    fn test_counts_pinned_entries() {
+        let mut map = LruMap::new(usize::MAX, |(_, finished): &(u32, bool)| *finished);
+        map.insert("a", (1, false));
+        map.insert("b", (2, false));
+        map.insert("c", (3, true));
+        assert_eq!(map.pinned(), 2);
+
+        map.get_mut(&"a").unwrap().1 = true;
+        assert_eq!(map.pinned(), 1);
+        map.get_mut(&"c").unwrap().1 = false;
+        assert_eq!(map.pinned(), 2);
+        // Replacing an entry recounts it
+        map.insert("b", (4, true));
+        assert_eq!(map.pinned(), 1);
+        map.insert("d", (5, true));
+        assert_eq!(map.pinned(), 1);
+
+        // Pinned "c" becomes evictable, as the most recently used entry
+        map.set_capacity(3);
+        assert!(map.get(&"a").is_none());
+        map.get_mut(&"c").unwrap().1 = true;
+        for key in ["e", "f"] {
+            map.insert(key, (6, false));
+        }
+        assert!(map.get(&"b").is_none());
+        assert!(map.get(&"d").is_none());
+        assert!(map.get(&"c").is_some());
+        map.insert("g", (7, false));
+        assert!(map.get(&"c").is_none());
+        assert_eq!(map.pinned(), 3);
+    }
+}
@@ -1,6 +1,11 @@
 use execution_engine::execution::{Order, OrderSide, OrderType};
 use execution_engine::{ExecutionEngine, SigningKey};
 
+use std::time::Duration;
+
+/// How long to wait for in-flight orders on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize logging
@@ -24,11 +29,11 @@ async fn main() -> anyhow::Result<()> {
         0.1,
     );
 
-    tracing::info!("Created order: {:?}", order);
+    tracing::info!("Created order: {}", order);
 
     match engine.execute_order(order).await {
         Ok(result) => {
-            tracing::info!("Order executed successfully: {:?}", result);
+            tracing::info!("Order executed successfully: {}", result);
         }
         Err(e) => {
             tracing::error!("Failed to execute order: {}", e);
@@ -40,5 +45,10 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("TinyWindow Execution Engine running");
 
+    tokio::signal::ctrl_c().await?;
+    tracing::info!("Shutdown requested, draining in-flight orders");
+    let pending = engine.shutdown(SHUTDOWN_TIMEOUT).await?;
+    tracing::info!("Execution engine stopped ({} orders unresolved)", pending);
+
     Ok(())
 }
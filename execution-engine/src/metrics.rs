@@ -0,0 +1,195 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+use crate::execution::OrderStatus;
+
+/// Counters the engine updates as orders move through it
+#[derive(Debug, Default)]
+pub(crate) struct EngineMetrics {
+    submitted: AtomicU64,
+    pending: AtomicU64,
+    partially_filled: AtomicU64,
+    executed: AtomicU64,
+    failed: AtomicU64,
+    cancelled: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl EngineMetrics {
+    /// An order was signed and sent to the venue
+    pub(crate) fn record_submitted(&self) {
+        self.submitted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// An order reached `status`
+    pub(crate) fn record_status(&self, status: OrderStatus) {
+        let counter = match status {
+            OrderStatus::Pending => &self.pending,
+            OrderStatus::PartiallyFilled => &self.partially_filled,
+            OrderStatus::Executed => &self.executed,
+            OrderStatus::Failed => &self.failed,
+            OrderStatus::Cancelled => &self.cancelled,
+            OrderStatus::Rejected => &self.rejected,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self, open_orders: usize, halted: bool) -> EngineMetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        EngineMetricsSnapshot {
+            orders_submitted: load(&self.submitted),
+            orders_pending: load(&self.pending),
+            orders_partially_filled: load(&self.partially_filled),
+            orders_executed: load(&self.executed),
+            orders_failed: load(&self.failed),
+            orders_cancelled: load(&self.cancelled),
+            orders_rejected: load(&self.rejected),
+            open_orders,
+            halted,
+        }
+    }
+}
+
+/// Point-in-time copy of the engine's counters and gauges
+///
+/// Status counters count results, so an order that is first partially
+/// filled and then executed is counted under both.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EngineMetricsSnapshot {
+    pub orders_submitted: u64,
+    pub orders_pending: u64,
+    pub orders_partially_filled: u64,
+    pub orders_executed: u64,
+    pub orders_failed: u64,
+    pub orders_cancelled: u64,
+    pub orders_rejected: u64,
+    /// Tracked orders not yet in a terminal state
+    pub open_orders: usize,
+    pub halted: bool,
+}
+
+impl EngineMetricsSnapshot {
+    /// Render in the Prometheus text exposition format, for a `/metrics`
+    /// endpoint
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "engine_orders_submitted_total",
+            "counter",
+            "Orders sent to the venue",
+        );
+        let _ = writeln!(
+            out,
+            "engine_orders_submitted_total {}",
+            self.orders_submitted
+        );
+
+        write_header(
+            &mut out,
+            "engine_orders_total",
+            "counter",
+            "Order results by status",
+        );
+        for (status, count) in [
+            (OrderStatus::Pending, self.orders_pending),
+            (OrderStatus::PartiallyFilled, self.orders_partially_filled),
+            (OrderStatus::Executed, self.orders_executed),
+            (OrderStatus::Failed, self.orders_failed),
+            (OrderStatus::Cancelled, self.orders_cancelled),
+            (OrderStatus::Rejected, self.orders_rejected),
+        ] {
+            let _ = writeln!(
+                out,
+                "engine_orders_total{{status=\"{}\"}} {}",
+                status.as_str(),
+                count
+            );
+        }
+
+        write_header(
+            &mut out,
+            "engine_open_orders",
+            "gauge",
+            "Tracked orders not yet in a terminal state",
+        );
+        let _ = writeln!(out, "engine_open_orders {}", self.open_orders);
+
+        write_header(
+            &mut out,
+            "engine_halted",
+            "gauge",
+            "Whether the kill switch is engaged",
+        );
+        let _ = writeln!(out, "engine_halted {}", u8::from(self.halted));
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Check each sample follows a `# TYPE` line for its metric and has the
+    /// form `name{labels} value`
+    fn assert_valid_exposition(text: &str) {
+        let mut typed = HashSet::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').unwrap();
+                assert!(matches!(kind, "counter" | "gauge"), "{}", line);
+                assert!(typed.insert(name.to_string()), "duplicate TYPE: {}", line);
+                continue;
+            }
+            if line.starts_with("# HELP ") {
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok(), "bad value: {}", line);
+            let name = match series.split_once('{') {
+                Some((name, labels)) => {
+                    let labels = labels.strip_suffix('}').unwrap();
+                    for label in labels.split(',') {
+                        let (key, value) = label.split_once('=').unwrap();
+                        assert!(key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+                        assert!(value.starts_with('"') && value.ends_with('"'), "{}", line);
+                    }
+                    name
+                }
+                None => series,
+            };
+            assert!(
+                name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "bad name: {}",
+                line
+            );
+            assert!(typed.contains(name), "sample without TYPE: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_prometheus_exposition() {
+        let metrics = EngineMetrics::default();
+        metrics.record_submitted();
+        metrics.record_submitted();
+        metrics.record_status(OrderStatus::Executed);
+        metrics.record_status(OrderStatus::Rejected);
+
+        let text = metrics.snapshot(1, true).to_prometheus();
+        assert_valid_exposition(&text);
+        assert!(text.contains("engine_orders_submitted_total 2\n"));
+        assert!(text.contains("engine_orders_total{status=\"executed\"} 1\n"));
+        assert!(text.contains("engine_orders_total{status=\"failed\"} 0\n"));
+        assert!(text.contains("engine_open_orders 1\n"));
+        assert!(text.contains("engine_halted 1\n"));
+    }
+}
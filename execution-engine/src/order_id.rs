@@ -0,0 +1,63 @@
+use uuid::Uuid;
+
+/// Scheme for the client order ids sent to venues
+///
+/// The engine assigns one to each order without a
+/// [`client_order_id`](crate::Order::client_order_id) just before signing
+/// (see [`ExecutionEngine::with_order_id_generator`](crate::ExecutionEngine::with_order_id_generator)).
+/// The order's `id` stays its identity everywhere else, including in the
+/// signed bytes, so generators only need to be unique per venue account.
+pub trait OrderIdGenerator: Send + Sync {
+    /// A client id not handed out before
+    fn next_id(&self) -> String;
+}
+
+/// A random hyphenated UUID; the engine's default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl OrderIdGenerator for UuidGenerator {
+    fn next_id(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// `prefix` followed by a random UUID as 32 hex digits, for venues that tag
+/// or filter orders by prefix
+#[derive(Debug, Clone, Default)]
+pub struct PrefixedGenerator {
+    pub prefix: String,
+}
+
+impl PrefixedGenerator {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl OrderIdGenerator for PrefixedGenerator {
+    fn next_id(&self) -> String {
+        format!("{}{}", self.prefix, Uuid::new_v4().simple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators() {
+        let id = UuidGenerator.next_id();
+        assert!(Uuid::parse_str(&id).is_ok());
+        assert_ne!(UuidGenerator.next_id(), id);
+
+        let generator = PrefixedGenerator::new("tw-");
+        let prefixed = generator.next_id();
+        assert_eq!(prefixed.len(), 3 + 32);
+        let hex = prefixed.strip_prefix("tw-").unwrap();
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(generator.next_id(), prefixed);
+    }
+}
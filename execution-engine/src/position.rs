@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::execution::OrderSide;
+
+/// Net holding in one symbol, built by folding fills in order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Positive when long, negative when short
+    pub net_quantity: f64,
+    /// Average price of the open quantity (zero when flat)
+    pub avg_entry_price: f64,
+    /// Profit realized by closing quantity, before fees
+    pub realized_pnl: f64,
+    /// Fees paid on fills; negative when rebates earned exceed fees paid
+    /// (see [`FeeModel`](crate::FeeModel) for the sign convention)
+    #[serde(default)]
+    pub fees: f64,
+}
+
+impl Position {
+    /// Apply a fill of `quantity` at `price`
+    ///
+    /// Fills on the same side as the position move the average entry price;
+    /// opposite fills realize PnL against it, and any excess beyond flat opens
+    /// a new position at the fill price.
+    pub fn apply_fill(&mut self, side: OrderSide, quantity: f64, price: f64) {
+        let signed = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => -quantity,
+        };
+
+        if self.net_quantity == 0.0 || self.net_quantity.signum() == signed.signum() {
+            let open = self.net_quantity.abs();
+            self.avg_entry_price =
+                (self.avg_entry_price * open + price * quantity) / (open + quantity);
+            self.net_quantity += signed;
+            return;
+        }
+
+        let closed = quantity.min(self.net_quantity.abs());
+        self.realized_pnl += closed * (price - self.avg_entry_price) * self.net_quantity.signum();
+        self.net_quantity += signed;
+
+        if quantity > closed {
+            // Crossed through zero: the remainder opens at the fill price
+            self.avg_entry_price = price;
+        } else if self.net_quantity == 0.0 {
+            self.avg_entry_price = 0.0;
+        }
+    }
+
+    /// Record a fill's fee; a negative fee is a rebate
+    pub fn apply_fee(&mut self, fee: f64) {
+        self.fees += fee;
+    }
+
+    /// Realized PnL after fees, with rebates added back
+    pub fn net_pnl(&self) -> f64 {
+        self.realized_pnl - self.fees
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.net_quantity == 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_fills() {
+        let mut position = Position::default();
+        position.apply_fill(OrderSide::Buy, 1.0, 100.0);
+        position.apply_fill(OrderSide::Buy, 1.0, 200.0);
+        assert_eq!(position.avg_entry_price, 150.0);
+
+        // Sell through flat into a short
+        position.apply_fill(OrderSide::Sell, 3.0, 160.0);
+        assert_eq!(position.realized_pnl, 20.0);
+        assert_eq!(position.net_quantity, -1.0);
+        assert_eq!(position.avg_entry_price, 160.0);
+
+        // Covering the short below entry is a gain
+        position.apply_fill(OrderSide::Buy, 1.0, 150.0);
+        assert_eq!(position.realized_pnl, 30.0);
+        assert!(position.is_flat());
+        assert_eq!(position.avg_entry_price, 0.0);
+    }
+
+    #[test]
+    fn test_rebates_add_to_net_pnl() {
+        let mut position = Position::default();
+        position.apply_fill(OrderSide::Buy, 1.0, 100.0);
+        position.apply_fee(0.5);
+        position.apply_fill(OrderSide::Sell, 1.0, 110.0);
+        position.apply_fee(-0.25);
+
+        assert_eq!(position.realized_pnl, 10.0);
+        assert_eq!(position.fees, 0.25);
+        assert_eq!(position.net_pnl(), 9.75);
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{ExecutionError, Result};
+
+/// Source of current prices, used to fill and value market orders
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    async fn price(&self, symbol: &str) -> Result<f64>;
+}
+
+/// Fixed prices per symbol, for tests and backtests
+#[derive(Debug, Clone, Default)]
+pub struct StaticPriceSource(pub HashMap<String, f64>);
+
+impl StaticPriceSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price(mut self, symbol: impl Into<String>, price: f64) -> Self {
+        self.0.insert(symbol.into(), price);
+        self
+    }
+}
+
+#[async_trait]
+impl PriceSource for StaticPriceSource {
+    async fn price(&self, symbol: &str) -> Result<f64> {
+        self.0.get(symbol).copied().ok_or_else(|| {
+            ExecutionError::Validation(format!("no price available for {}", symbol)).into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_price_source() {
+        let prices = StaticPriceSource::new().with_price("BTC/USD", 42000.0);
+        assert_eq!(prices.price("BTC/USD").await.unwrap(), 42000.0);
+        assert!(prices.price("ETH/USD").await.is_err());
+    }
+}
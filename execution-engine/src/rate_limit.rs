@@ -0,0 +1,215 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+
+use crate::execution::Urgency;
+
+/// Token-bucket limiter for order submission
+///
+/// The bucket starts full with `capacity` tokens and refills continuously at
+/// `refill_per_sec`. Share one limiter (via `Arc`) between every engine that
+/// talks to the same venue account.
+///
+/// Callers waiting in [`acquire_with`](Self::acquire_with) are owed a token
+/// each by every caller of lower [`Urgency`], so tokens go to the most
+/// urgent waiters first (see [`Urgency`] for what that means for fairness).
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    bucket: Mutex<Bucket>,
+    /// Woken when a waiter leaves, since the tokens it was owed may now be
+    /// free for the waiters below it
+    left: Notify,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    /// Callers waiting in `acquire_with`, by urgency rank
+    waiting: [usize; 3],
+}
+
+/// A caller's place among the waiters, given up on drop (including when
+/// the waiting future is cancelled)
+struct Waiting<'a> {
+    limiter: &'a RateLimiter,
+    rank: usize,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.limiter.bucket.lock().unwrap().waiting[self.rank] -= 1;
+        self.limiter.left.notify_waiters();
+    }
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity as f64,
+                last_refill: Instant::now(),
+                waiting: [0; 3],
+            }),
+            left: Notify::new(),
+        }
+    }
+
+    /// Take a token if one is available, without waiting
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_with(Urgency::Normal)
+    }
+
+    /// Take a token if one is available and not owed to a waiting caller of
+    /// higher urgency, without waiting
+    pub fn try_acquire_with(&self, urgency: Urgency) -> bool {
+        self.take(urgency).is_ok()
+    }
+
+    /// Wait until a token is available and take it
+    pub async fn acquire(&self) {
+        self.acquire_with(Urgency::Normal).await
+    }
+
+    /// Wait until a token is available and not owed to a waiting caller of
+    /// higher urgency, and take it
+    pub async fn acquire_with(&self, urgency: Urgency) {
+        if self.take(urgency).is_ok() {
+            return;
+        }
+        let _waiting = self.wait_as(urgency);
+        loop {
+            // Registered before the retry, so a waiter leaving in between
+            // still wakes this one
+            let left = self.left.notified();
+            let Err(wait) = self.take(urgency) else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = left => {}
+            }
+        }
+    }
+
+    fn wait_as(&self, urgency: Urgency) -> Waiting<'_> {
+        let rank = urgency.rank();
+        self.bucket.lock().unwrap().waiting[rank] += 1;
+        Waiting {
+            limiter: self,
+            rank,
+        }
+    }
+
+    /// Tokens currently available (fractional while refilling)
+    pub fn available(&self) -> f64 {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens
+    }
+
+    /// Take a token, or return how long until one will be available
+    fn take(&self, urgency: Urgency) -> std::result::Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+
+        let owed: usize = bucket.waiting[urgency.rank() + 1..].iter().sum();
+        let available = bucket.tokens - owed as f64;
+        if available >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        if self.refill_per_sec <= 0.0 {
+            // Never refills; poll slowly rather than spinning
+            return Err(Duration::from_secs(1));
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - available) / self.refill_per_sec,
+        ))
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_acquire_exhausts_bucket() {
+        let limiter = RateLimiter::new(3, 0.0);
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+    }
+
+    #[tokio::test]
+    async fn test_urgent_waiters_go_first() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(1, 10.0));
+        assert!(limiter.try_acquire());
+
+        let served = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let wait = |urgency| {
+            let (limiter, served) = (limiter.clone(), served.clone());
+            tokio::spawn(async move {
+                limiter.acquire_with(urgency).await;
+                served.lock().unwrap().push(urgency);
+            })
+        };
+        // The passive caller queues first but the next token is owed to the
+        // urgent one
+        let passive = wait(Urgency::Passive);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let urgent = wait(Urgency::Urgent);
+        urgent.await.unwrap();
+        passive.await.unwrap();
+        assert_eq!(*served.lock().unwrap(), [Urgency::Urgent, Urgency::Passive]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_frees_its_token() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(1, 4.0));
+        assert!(limiter.try_acquire());
+
+        let urgent = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire_with(Urgency::Urgent).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // Owes the next token to the urgent waiter, so plans to sleep for two
+        let start = Instant::now();
+        let passive = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire_with(Urgency::Passive).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        urgent.abort();
+
+        // Takes the next token, at 250ms, instead of oversleeping to 500ms
+        passive.await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_throttles_to_refill_rate() {
+        let limiter = RateLimiter::new(1, 20.0);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        // One token up front, then four more at 20/s
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// Run `op` up to `max_attempts` times, doubling the delay after each
+/// retryable failure starting from `base_delay`
+///
+/// Non-retryable errors are returned immediately; otherwise the last error
+/// is returned once attempts run out.
+pub(crate) async fn with_backoff<T, F, Fut>(
+    max_attempts: u32,
+    base_delay: Duration,
+    mut op: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut delay = base_delay;
+
+    for attempt in 1.. {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max_attempts && e.is_retryable() => {
+                tracing::warn!(
+                    "Attempt {}/{} failed: {}; retrying in {:?}",
+                    attempt,
+                    max_attempts,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("attempt counter is unbounded")
+}
+
+impl Error {
+    /// Whether the failure is transient (the backing service may not be up
+    /// yet) and the operation is worth retrying
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Database(e) => matches!(
+                e,
+                sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::Tls(_)
+            ),
+            Error::Redis(e) => {
+                e.is_io_error()
+                    || e.is_connection_refusal()
+                    || e.is_timeout()
+                    || e.is_connection_dropped()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn io_error() -> Error {
+        sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionRefused)).into()
+    }
+
+    #[tokio::test]
+    async fn test_backoff_retries_transient_errors() {
+        let calls = AtomicU32::new(0);
+        let value = with_backoff(5, Duration::from_millis(1), || async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(io_error())
+            } else {
+                Ok(7)
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Gives up after max_attempts and returns the last error
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<()> = with_backoff(3, Duration::from_millis(1), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(io_error())
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Database(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_redis_error_classification() {
+        let dropped: Error =
+            redis::RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+                .into();
+        assert!(dropped.is_retryable());
+
+        let command: Error =
+            redis::RedisError::from((redis::ErrorKind::TypeError, "WRONGTYPE")).into();
+        assert!(!command.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_backoff_stops_on_permanent_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = with_backoff(5, Duration::from_millis(1), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Crypto("bad key".to_string()))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
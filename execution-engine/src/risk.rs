@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    execution::{Order, OrderSide},
+    ExecutionError, Result,
+};
+
+/// Pre-trade limits. A `None` limit is not enforced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskLimits {
+    /// Maximum `price * quantity` of a single order (the amount itself for
+    /// quote-sized orders)
+    pub max_order_notional: Option<f64>,
+    /// Maximum absolute net quantity held per symbol
+    pub max_symbol_exposure: Option<f64>,
+    /// Maximum number of reserved orders that have not reached a terminal
+    /// state
+    pub max_open_orders: Option<usize>,
+}
+
+/// Fat-finger guard: limit prices must lie within a percentage band around
+/// a reference price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceBand {
+    /// Largest allowed `|limit - reference| / reference`, in percent
+    pub max_price_deviation_pct: f64,
+    /// Reject market orders when no reference price is available
+    #[serde(default)]
+    pub require_reference_for_market: bool,
+    /// Treat a failing price source as having no reference price instead of
+    /// rejecting the order
+    #[serde(default)]
+    pub fail_open: bool,
+}
+
+impl PriceBand {
+    pub fn new(max_price_deviation_pct: f64) -> Self {
+        Self {
+            max_price_deviation_pct,
+            require_reference_for_market: false,
+            fail_open: false,
+        }
+    }
+
+    pub fn require_reference_for_market(mut self) -> Self {
+        self.require_reference_for_market = true;
+        self
+    }
+
+    pub fn fail_open(mut self) -> Self {
+        self.fail_open = true;
+        self
+    }
+
+    /// Check an order against the band around `reference`
+    ///
+    /// Limit orders are unchecked when there is no reference price.
+    pub fn check(&self, order: &Order, reference: Option<f64>) -> Result<()> {
+        match (order.order_type.limit_price(), reference) {
+            (Some(price), Some(reference)) => {
+                let deviation_pct = (price - reference).abs() / reference * 100.0;
+                if !deviation_pct.is_finite() || deviation_pct > self.max_price_deviation_pct {
+                    return Err(ExecutionError::PriceOutsideBand.into());
+                }
+            }
+            (None, None) if self.require_reference_for_market => {
+                return Err(ExecutionError::Validation(format!(
+                    "no reference price for {}",
+                    order.symbol
+                ))
+                .into());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RiskState {
+    exposure: HashMap<String, f64>,
+    /// Signed base quantity reserved for each open order (for quote-sized
+    /// orders this depends on the reference price at reservation)
+    open: HashMap<Uuid, f64>,
+}
+
+/// Enforces [`RiskLimits`] against a running tally of accepted orders
+#[derive(Debug)]
+pub struct RiskManager {
+    limits: RiskLimits,
+    state: Mutex<RiskState>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(RiskState::default()),
+        }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Check an order against the limits and, if it passes, add it to the tally
+    ///
+    /// `reference_price` is used to value the order; for limit orders this is
+    /// the limit price.
+    pub fn reserve(&self, order: &Order, reference_price: f64) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let next = self.check_against(&state, order, reference_price)?;
+        state.exposure.insert(order.symbol.clone(), next);
+        state
+            .open
+            .insert(order.id, signed_quantity(order, reference_price));
+        Ok(())
+    }
+
+    /// Check an order against the limits without adding it to the tally
+    pub fn check(&self, order: &Order, reference_price: f64) -> Result<()> {
+        let state = self.state.lock().unwrap();
+        self.check_against(&state, order, reference_price)
+            .map(|_| ())
+    }
+
+    /// Check an order against the current tally, returning the symbol's
+    /// exposure if it were accepted
+    fn check_against(&self, state: &RiskState, order: &Order, reference_price: f64) -> Result<f64> {
+        if let Some(max) = self.limits.max_open_orders {
+            if state.open.len() >= max {
+                return Err(ExecutionError::RiskLimit(format!(
+                    "max open orders ({}) reached",
+                    max
+                ))
+                .into());
+            }
+        }
+
+        if let Some(max) = self.limits.max_order_notional {
+            let notional = order.order_quantity().notional_at(reference_price);
+            if notional > max {
+                return Err(ExecutionError::RiskLimit(format!(
+                    "order notional {} exceeds {}",
+                    notional, max
+                ))
+                .into());
+            }
+        }
+
+        let current = state.exposure.get(&order.symbol).copied().unwrap_or(0.0);
+        let next = current + signed_quantity(order, reference_price);
+        if let Some(max) = self.limits.max_symbol_exposure {
+            if next.abs() > max {
+                return Err(ExecutionError::RiskLimit(format!(
+                    "{} exposure {} exceeds {}",
+                    order.symbol,
+                    next.abs(),
+                    max
+                ))
+                .into());
+            }
+        }
+        Ok(next)
+    }
+
+    /// Remove an order from the tally as if nothing of it filled (e.g. it
+    /// failed or was rejected)
+    pub fn release(&self, order: &Order) {
+        self.settle(order, 0.0);
+    }
+
+    /// Close an order's reservation once it reaches a terminal state
+    ///
+    /// The order no longer counts as open, and the unfilled part of its
+    /// reserved quantity leaves the exposure; the `filled` base quantity
+    /// stays, since it is now held. Orders that were never reserved, or
+    /// are already settled, are ignored.
+    pub fn settle(&self, order: &Order, filled: f64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(reserved) = state.open.remove(&order.id) else {
+            return;
+        };
+        let unfilled = reserved - signed(order.side, filled);
+        if let Some(exposure) = state.exposure.get_mut(&order.symbol) {
+            *exposure -= unfilled;
+        }
+    }
+
+    /// Signed base quantity reserved for each open order
+    pub fn reservations(&self) -> HashMap<Uuid, f64> {
+        self.state.lock().unwrap().open.clone()
+    }
+
+    /// Put back a reservation taken by another instance, e.g. one from
+    /// [`reservations`](Self::reservations) in a snapshot
+    ///
+    /// The order was already accepted, so the limits are not checked. An
+    /// order that is already reserved is left as is.
+    pub fn restore_reservation(&self, order: &Order, reserved: f64) {
+        let mut state = self.state.lock().unwrap();
+        if state.open.contains_key(&order.id) {
+            return;
+        }
+        state.open.insert(order.id, reserved);
+        *state.exposure.entry(order.symbol.clone()).or_insert(0.0) += reserved;
+    }
+
+    /// Net signed quantity currently tallied for every symbol
+    pub fn exposures(&self) -> HashMap<String, f64> {
+        self.state.lock().unwrap().exposure.clone()
+    }
+
+    /// Add held quantity to a symbol's tally, e.g. the settled exposure in
+    /// a snapshot; the limits are not checked
+    pub fn restore_exposure(&self, symbol: &str, quantity: f64) {
+        *self
+            .state
+            .lock()
+            .unwrap()
+            .exposure
+            .entry(symbol.to_string())
+            .or_insert(0.0) += quantity;
+    }
+
+    /// A manager enforcing `limits` against a copy of this one's tally
+    pub fn with_limits(&self, limits: RiskLimits) -> Self {
+        let state = self.state.lock().unwrap();
+        Self {
+            limits,
+            state: Mutex::new(RiskState {
+                exposure: state.exposure.clone(),
+                open: state.open.clone(),
+            }),
+        }
+    }
+
+    /// Number of orders currently counted as open
+    pub fn open_orders(&self) -> usize {
+        self.state.lock().unwrap().open.len()
+    }
+
+    /// Net signed quantity currently tallied for a symbol
+    pub fn exposure(&self, symbol: &str) -> f64 {
+        self.state
+            .lock()
+            .unwrap()
+            .exposure
+            .get(symbol)
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+/// Base quantity of the order at `reference_price`, negative for sells
+fn signed_quantity(order: &Order, reference_price: f64) -> f64 {
+    signed(order.side, order.order_quantity().base_at(reference_price))
+}
+
+fn signed(side: OrderSide, quantity: f64) -> f64 {
+    match side {
+        OrderSide::Buy => quantity,
+        OrderSide::Sell => -quantity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderQuantity, OrderType};
+    use crate::Error;
+
+    fn order(side: OrderSide, quantity: f64) -> Order {
+        Order::new("BTC/USD".to_string(), side, OrderType::Market, quantity)
+    }
+
+    #[test]
+    fn test_price_band() {
+        let band = PriceBand::new(5.0);
+        let limit = |price| {
+            Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price },
+                1.0,
+            )
+        };
+
+        band.check(&limit(50500.0), Some(50000.0)).unwrap();
+        let err = band.check(&limit(75000.0), Some(50000.0)).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Execution(ExecutionError::PriceOutsideBand)
+        ));
+        assert!(band.check(&limit(25000.0), Some(50000.0)).is_err());
+        band.check(&limit(75000.0), None).unwrap();
+
+        let market = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        band.check(&market, None).unwrap();
+        assert!(band
+            .require_reference_for_market()
+            .check(&market, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_notional_and_exposure_limits() {
+        let risk = RiskManager::new(RiskLimits {
+            max_order_notional: Some(10_000.0),
+            max_symbol_exposure: Some(0.3),
+            max_open_orders: None,
+        });
+
+        assert!(risk.reserve(&order(OrderSide::Buy, 1.0), 50_000.0).is_err());
+        assert!(risk.reserve(&order(OrderSide::Buy, 0.2), 50_000.0).is_ok());
+        assert!(risk.reserve(&order(OrderSide::Buy, 0.2), 1.0).is_err());
+
+        // Selling reduces exposure, so the same buy fits afterwards
+        let sell = order(OrderSide::Sell, 0.1);
+        assert!(risk.reserve(&sell, 50_000.0).is_ok());
+        assert!((risk.exposure("BTC/USD") - 0.1).abs() < 1e-12);
+
+        risk.release(&sell);
+        assert!((risk.exposure("BTC/USD") - 0.2).abs() < 1e-12);
+        assert_eq!(risk.open_orders(), 1);
+        // Releasing again changes nothing
+        risk.release(&sell);
+        assert!((risk.exposure("BTC/USD") - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_settle_frees_the_order_and_keeps_fills() {
+        let risk = RiskManager::new(RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        });
+        let first = order(OrderSide::Buy, 1.0);
+        risk.reserve(&first, 100.0).unwrap();
+        assert!(risk.reserve(&order(OrderSide::Buy, 1.0), 100.0).is_err());
+
+        // Partly filled, then cancelled: the fill is still held
+        risk.settle(&first, 0.4);
+        assert_eq!(risk.open_orders(), 0);
+        assert!((risk.exposure("BTC/USD") - 0.4).abs() < 1e-12);
+        risk.reserve(&order(OrderSide::Sell, 1.0), 100.0).unwrap();
+        assert!((risk.exposure("BTC/USD") + 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_quote_sized_orders() {
+        let risk = RiskManager::new(RiskLimits {
+            max_order_notional: Some(10_000.0),
+            max_symbol_exposure: Some(0.3),
+            max_open_orders: None,
+        });
+        let quote = |amount| {
+            let mut order = order(OrderSide::Buy, 0.0);
+            order.set_order_quantity(OrderQuantity::Quote(amount));
+            order
+        };
+
+        // The amount is the notional, whatever the price
+        assert!(risk.reserve(&quote(20_000.0), 1.0).is_err());
+
+        // $5,000 at 50,000 is 0.1 base; released at the same 0.1 even
+        // though the price has since moved
+        let buy = quote(5_000.0);
+        risk.reserve(&buy, 50_000.0).unwrap();
+        assert!((risk.exposure("BTC/USD") - 0.1).abs() < 1e-12);
+        assert!(risk.check(&quote(5_000.0), 20_000.0).is_err());
+        risk.release(&buy);
+        assert!(risk.exposure("BTC/USD").abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_restore_reservation() {
+        let limits = RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        };
+        let risk = RiskManager::new(limits.clone());
+        let sell = order(OrderSide::Sell, 2.0);
+        risk.reserve(&sell, 100.0).unwrap();
+
+        let restored = RiskManager::new(limits);
+        for (id, reserved) in risk.reservations() {
+            assert_eq!(id, sell.id);
+            restored.restore_reservation(&sell, reserved);
+        }
+        restored.restore_reservation(&sell, -2.0);
+        assert_eq!(restored.open_orders(), 1);
+        assert!((restored.exposure("BTC/USD") + 2.0).abs() < 1e-12);
+        assert!(restored
+            .reserve(&order(OrderSide::Buy, 1.0), 100.0)
+            .is_err());
+
+        restored.settle(&sell, 0.5);
+        assert!((restored.exposure("BTC/USD") + 0.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,203 @@
+//! HTTP interface to the execution engine (`server` feature)
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    execution::{ExecutionEngine, Order, OrderResult, SigningScheme},
+    storage::{OrderRecord, Store},
+    Error, ExecutionError, VerificationKey,
+};
+
+/// Default number of records returned by `GET /orders`
+pub const DEFAULT_HISTORY_LIMIT: i64 = 100;
+
+/// Shared state behind the HTTP handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub engine: ExecutionEngine,
+    /// Key submitted orders must be signed with
+    pub verification_key: VerificationKey,
+    /// Encoding clients sign orders over, independent of the scheme the
+    /// engine signs with
+    pub client_scheme: SigningScheme,
+    /// Order history store; `GET /orders` is unavailable without one
+    ///
+    /// The engine should be configured with the same store (see
+    /// [`ExecutionEngine::with_store`]) so submitted orders show up here.
+    pub store: Option<Arc<dyn Store>>,
+}
+
+/// Build the router exposing `POST /orders`, `GET /orders` and `GET /metrics`
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/orders", post(submit_order).get(order_history))
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    limit: Option<i64>,
+}
+
+async fn submit_order(
+    State(state): State<AppState>,
+    Json(order): Json<Order>,
+) -> std::result::Result<Json<OrderResult>, ApiError> {
+    order
+        .verify_with(&state.verification_key, state.client_scheme)
+        .map_err(|e| ApiError(StatusCode::UNAUTHORIZED, e.to_string()))?;
+
+    Ok(Json(state.engine.execute_order(order).await?))
+}
+
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.engine.metrics().to_prometheus(),
+    )
+}
+
+async fn order_history(
+    State(state): State<AppState>,
+    Query(params): Query<HistoryParams>,
+) -> std::result::Result<Json<Vec<OrderRecord>>, ApiError> {
+    let store = state.store.as_ref().ok_or_else(|| {
+        ApiError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "order history is not configured".to_string(),
+        )
+    })?;
+    let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    Ok(Json(store.get_order_history(limit).await?))
+}
+
+/// Error response rendered as `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            Error::Execution(ExecutionError::RateLimited) => StatusCode::TOO_MANY_REQUESTS,
+            Error::Execution(ExecutionError::Timeout) => StatusCode::GATEWAY_TIMEOUT,
+            Error::Execution(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderSide, OrderStatus, OrderType};
+    use crate::{InMemoryStore, SigningKey};
+
+    async fn spawn_server(client_key: &SigningKey, store: Option<Arc<dyn Store>>) -> String {
+        // The engine's own scheme must not affect client verification
+        let mut engine =
+            ExecutionEngine::new(SigningKey::generate()).with_signing_scheme(SigningScheme::Json);
+        if let Some(store) = &store {
+            engine = engine.with_store(store.clone());
+        }
+        let state = AppState {
+            engine,
+            verification_key: client_key.verification_key(),
+            client_scheme: SigningScheme::Binary,
+            store,
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        format!("http://{}/orders", addr)
+    }
+
+    fn order() -> Order {
+        Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_submit_signed_order() {
+        let client_key = SigningKey::generate();
+        let url = spawn_server(&client_key, Some(Arc::new(InMemoryStore::new()))).await;
+        let client = reqwest::Client::new();
+
+        let mut order = order();
+        order.sign(&client_key).unwrap();
+        let response = client.post(&url).json(&order).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let result: OrderResult = response.json().await.unwrap();
+        assert_eq!(result.order_id, order.id);
+        assert_eq!(result.status, OrderStatus::Executed);
+
+        let history: Vec<OrderRecord> = client
+            .get(format!("{}?limit=5", url))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, order.id);
+
+        let metrics = client
+            .get(url.replace("/orders", "/metrics"))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(metrics.contains("engine_orders_total{status=\"executed\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsigned_and_forged_orders() {
+        let client_key = SigningKey::generate();
+        let url = spawn_server(&client_key, None).await;
+        let client = reqwest::Client::new();
+
+        let unsigned = order();
+        let response = client.post(&url).json(&unsigned).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let mut forged = order();
+        forged.sign(&SigningKey::generate()).unwrap();
+        let response = client.post(&url).json(&forged).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let mut wrong_scheme = order();
+        wrong_scheme
+            .sign_with(&client_key, SigningScheme::Json)
+            .unwrap();
+        let response = client.post(&url).json(&wrong_scheme).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // History needs a database
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
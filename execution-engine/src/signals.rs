@@ -1,7 +1,54 @@
-use redis::{aio::ConnectionManager, AsyncCommands, Client};
-use serde::{Deserialize, Serialize};
+use futures_util::{
+    future,
+    stream::{self, BoxStream, Stream, StreamExt},
+};
+use redis::{
+    aio::ConnectionManager,
+    streams::{StreamReadOptions, StreamReadReply},
+    AsyncCommands, Client, ExistenceCheck, SetExpiry, SetOptions,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::Result;
+use crate::{
+    execution::OrderTemplate, pricing::PriceSource, retry, singleflight::SingleFlight, Error,
+    ExecutionError, Result,
+};
+
+/// Stream used for reliable (consumer group) signal delivery
+const SIGNAL_STREAM: &str = "signals";
+
+/// Entries fetched per `consume_signals` call
+const STREAM_BATCH_SIZE: usize = 100;
+
+/// Attempts for `publish_signal` and `get_signal` when Redis drops the
+/// connection; the `ConnectionManager` reconnects in the background
+const TRANSIENT_RETRY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled after each further attempt
+const TRANSIENT_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Decimal places of `strength` considered when deduplicating signals
+const DEDUP_STRENGTH_DECIMALS: i32 = 2;
+
+/// Set `KEYS[1]` to `ARGV[2]` only if it still holds `ARGV[1]` (`""`
+/// meaning unset), returning 1 if it was set
+const SET_IF_UNCHANGED: &str = r#"
+local current = redis.call('GET', KEYS[1])
+if (current or '') == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2])
+    return 1
+end
+return 0
+"#;
+
+/// How long a claimed nonce is remembered in Redis
+///
+/// Nonces are microsecond timestamps, so a replay older than this is
+/// already far outside any signal age or local [`NonceWindow`](crate::NonceWindow)
+/// check.
+const NONCE_CLAIM_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingSignal {
@@ -9,49 +56,534 @@ pub struct TradingSignal {
     pub signal_type: String,
     pub strength: f64,
     pub timestamp: i64,
+    #[serde(
+        serialize_with = "serialize_metadata",
+        deserialize_with = "deserialize_metadata"
+    )]
     pub metadata: serde_json::Value,
 }
 
+/// Metadata is a JSON value in JSON and a JSON string in binary formats,
+/// which cannot deserialize an arbitrary `Value`
+fn serialize_metadata<S>(
+    metadata: &serde_json::Value,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if serializer.is_human_readable() {
+        metadata.serialize(serializer)
+    } else {
+        serializer.serialize_str(&metadata.to_string())
+    }
+}
+
+fn deserialize_metadata<'de, D>(deserializer: D) -> std::result::Result<serde_json::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        serde_json::Value::deserialize(deserializer)
+    } else {
+        let s = String::deserialize(deserializer)?;
+        serde_json::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Stable sort, strongest first, with NaN strengths last
+fn sort_by_strength(signals: &mut [TradingSignal]) {
+    let key = |s: &TradingSignal| {
+        if s.strength.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            s.strength
+        }
+    };
+    signals.sort_by(|a, b| key(b).total_cmp(&key(a)));
+}
+
+#[cfg(feature = "bincode")]
+impl TradingSignal {
+    /// Encode in the compact binary wire format
+    pub fn to_bincode(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decode from [`TradingSignal::to_bincode`] output
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl TradingSignal {
+    /// Encode as MessagePack, with field names so decoders need not share
+    /// the field order
+    pub fn to_msgpack(&self) -> Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec_named(self)?)
+    }
+
+    /// Decode from [`TradingSignal::to_msgpack`] output
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+impl TradingSignal {
+    /// Read a metadata field, treating `metadata` as a JSON object
+    ///
+    /// Returns `None` if the field (or the object) is absent, and an error
+    /// if the field does not deserialize as `T`.
+    pub fn get_meta<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        match self.metadata.get(key) {
+            Some(value) => Ok(Some(T::deserialize(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a metadata field, replacing `metadata` with an empty object first
+    /// if it is null or not an object
+    pub fn set_meta<T: Serialize>(&mut self, key: impl Into<String>, value: T) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        if !self.metadata.is_object() {
+            self.metadata = serde_json::Value::Object(Default::default());
+        }
+        if let serde_json::Value::Object(fields) = &mut self.metadata {
+            fields.insert(key.into(), value);
+        }
+        Ok(())
+    }
+}
+
+/// Encoding of the signals stored by [`SignalManager::publish_signal`] and
+/// read by [`SignalManager::get_signal`]
+///
+/// Nothing in the stored value records the codec, so every publisher and
+/// consumer of a Redis instance must be configured with the same one; a
+/// mismatch surfaces as a decode error from `get_signal`. Streams, templates
+/// and prices are always JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignalCodec {
+    #[default]
+    Json,
+    /// [`TradingSignal::to_bincode`], for smaller values
+    #[cfg(feature = "bincode")]
+    Bincode,
+    /// [`TradingSignal::to_msgpack`], smaller than JSON and readable by
+    /// other languages' MessagePack libraries
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl SignalCodec {
+    pub fn encode(self, signal: &TradingSignal) -> Result<Vec<u8>> {
+        match self {
+            SignalCodec::Json => Ok(serde_json::to_vec(signal)?),
+            #[cfg(feature = "bincode")]
+            SignalCodec::Bincode => signal.to_bincode(),
+            #[cfg(feature = "msgpack")]
+            SignalCodec::MessagePack => signal.to_msgpack(),
+        }
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> Result<TradingSignal> {
+        match self {
+            SignalCodec::Json => Ok(serde_json::from_slice(bytes)?),
+            #[cfg(feature = "bincode")]
+            SignalCodec::Bincode => TradingSignal::from_bincode(bytes),
+            #[cfg(feature = "msgpack")]
+            SignalCodec::MessagePack => TradingSignal::from_msgpack(bytes),
+        }
+    }
+}
+
+/// Shared outcome of a coalesced `get_signal` read: the raw value, or the
+/// error's kind and message (Redis errors are not `Clone`)
+type SignalRead = std::result::Result<Option<Vec<u8>>, (redis::ErrorKind, String)>;
+
+/// Split an error for sharing between coalesced reads
+fn share_read_error(e: Error) -> (redis::ErrorKind, String) {
+    match e {
+        Error::Redis(e) => (e.kind(), e.detail().unwrap_or_default().to_string()),
+        other => (redis::ErrorKind::ClientError, other.to_string()),
+    }
+}
+
+/// Rebuild a shared read error, keeping its kind so callers can still tell
+/// a dropped connection from, say, a type error
+fn unshare_read_error((kind, detail): (redis::ErrorKind, String)) -> redis::RedisError {
+    redis::RedisError::from((kind, "signal read failed", detail))
+}
+
+#[derive(Clone)]
 pub struct SignalManager {
     client: ConnectionManager,
+    /// For dedicated connections, such as pub/sub, that the manager can't share
+    redis: Client,
+    reads: Arc<SingleFlight<String, SignalRead>>,
+    codec: SignalCodec,
 }
 
 impl SignalManager {
     /// Connect to Redis
     pub async fn connect(redis_url: &str) -> Result<Self> {
-        let client = Client::open(redis_url)?;
-        let client = ConnectionManager::new(client).await?;
+        let redis = Client::open(redis_url)?;
+        let client = ConnectionManager::new(redis.clone()).await?;
+
+        Ok(Self {
+            client,
+            redis,
+            reads: Arc::new(SingleFlight::new()),
+            codec: SignalCodec::default(),
+        })
+    }
 
-        Ok(Self { client })
+    /// Encode stored signals with `codec` instead of JSON (see
+    /// [`SignalCodec`] for why all parties must agree)
+    pub fn with_codec(mut self, codec: SignalCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Connect, retrying transient failures with exponential backoff
+    ///
+    /// Useful at startup when Redis may not be accepting connections yet.
+    /// Returns the last error if all `max_attempts` fail.
+    pub async fn connect_with_retry(
+        redis_url: &str,
+        max_attempts: u32,
+        base_delay: Duration,
+    ) -> Result<Self> {
+        retry::with_backoff(max_attempts, base_delay, || Self::connect(redis_url)).await
     }
 
     /// Publish a trading signal
+    ///
+    /// The signal is encoded with the manager's [`SignalCodec`] and stored
+    /// under `signal:{symbol}`, with the symbol percent-encoded (so
+    /// `BTC:PERP` becomes `signal:BTC%3APERP`); that key is what
+    /// `trading_signals` subscribers receive.
+    ///
+    /// Connection errors are retried briefly while Redis reconnects; command
+    /// errors are returned immediately. A retry after the SET succeeded may
+    /// notify subscribers twice.
     pub async fn publish_signal(&mut self, signal: &TradingSignal) -> Result<()> {
-        let key = format!("signal:{}", signal.symbol);
+        let key = redis_key("signal", &signal.symbol);
+        let value = self.codec.encode(signal)?;
+
+        retry::with_backoff(TRANSIENT_RETRY_ATTEMPTS, TRANSIENT_RETRY_DELAY, || {
+            let mut client = self.client.clone();
+            let (key, value) = (key.clone(), value.clone());
+            async move {
+                client.set_ex::<_, _, ()>(&key, value, 300).await?; // Expire after 5 minutes
+                client.publish::<_, _, ()>("trading_signals", &key).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Get the latest signal for a symbol
+    ///
+    /// Concurrent reads of the same symbol (from clones of this manager)
+    /// share a single Redis GET, retried briefly on connection errors.
+    pub async fn get_signal(&self, symbol: &str) -> Result<Option<TradingSignal>> {
+        let key = redis_key("signal", symbol);
+        let client = self.client.clone();
+        let value = self
+            .reads
+            .run(key.clone(), || async move {
+                retry::with_backoff(TRANSIENT_RETRY_ATTEMPTS, TRANSIENT_RETRY_DELAY, || {
+                    let mut client = client.clone();
+                    let key = key.clone();
+                    async move { Ok(client.get::<_, Option<Vec<u8>>>(&key).await?) }
+                })
+                .await
+                .map_err(share_read_error)
+            })
+            .await
+            .map_err(unshare_read_error)?;
+
+        value.map(|v| self.codec.decode(&v)).transpose()
+    }
+
+    /// Symbols that currently have a signal stored
+    ///
+    /// Scans the `signal:` keyspace, so it is O(keys) on the server; use it
+    /// for housekeeping rather than on a hot path. The order is unspecified.
+    pub async fn signal_symbols(&self) -> Result<Vec<String>> {
+        let mut client = self.client.clone();
+        let mut keys = client.scan_match::<_, String>("signal:*").await?;
+        let mut symbols = Vec::new();
+        while let Some(key) = keys.next_item().await {
+            match key.strip_prefix("signal:").and_then(decode_key_component) {
+                Some(symbol) => symbols.push(symbol),
+                None => tracing::warn!("Skipping unrecognized signal key {}", key),
+            }
+        }
+        Ok(symbols)
+    }
+
+    /// Current signals for `symbols`, strongest first
+    ///
+    /// Symbols without a signal are skipped. Equal strengths keep the order
+    /// of `symbols`, and a NaN strength sorts last.
+    pub async fn drain_by_strength(&self, symbols: &[&str]) -> Result<Vec<TradingSignal>> {
+        let mut signals = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            if let Some(signal) = self.get_signal(symbol).await? {
+                signals.push(signal);
+            }
+        }
+        sort_by_strength(&mut signals);
+        Ok(signals)
+    }
+
+    /// Publish a signal unless an equivalent one was published within `window`
+    ///
+    /// Signals are equivalent when symbol, type and strength (rounded to
+    /// two decimal places) match. A `dedup:` marker key with a TTL of
+    /// `window` records the last publish; it is claimed before publishing,
+    /// so concurrent duplicates publish once, and removed again if the
+    /// publish fails so a retry is not suppressed. Returns whether the
+    /// signal was published.
+    pub async fn publish_dedup(
+        &mut self,
+        signal: &TradingSignal,
+        window: Duration,
+    ) -> Result<bool> {
+        let millis = window.as_millis() as u64;
+        if millis == 0 {
+            self.publish_signal(signal).await?;
+            return Ok(true);
+        }
+
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(millis as usize));
+        let marked: Option<String> = self
+            .client
+            .set_options(dedup_key(signal), 1, options)
+            .await?;
+        if marked.is_none() {
+            return Ok(false);
+        }
+
+        if let Err(e) = self.publish_signal(signal).await {
+            if let Err(del) = self.client.del::<_, ()>(dedup_key(signal)).await {
+                tracing::warn!("Failed to clear dedup marker after failed publish: {}", del);
+            }
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Claim `nonce` for the signer `key_id`, returning false if it was
+    /// already claimed (a replay)
+    ///
+    /// Each claim is its own `nonce:{key_id}:{nonce}` key, set with `NX` so
+    /// that when several engine replicas share Redis exactly one of them
+    /// gets `true` for a given nonce, whatever order the nonces arrive in.
+    /// Claims expire after a day.
+    pub async fn reserve_nonce(&mut self, key_id: &str, nonce: u64) -> Result<bool> {
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(NONCE_CLAIM_TTL.as_millis() as usize));
+        let claimed: Option<String> = self
+            .client
+            .set_options(nonce_key(key_id, nonce), 1, options)
+            .await?;
+        Ok(claimed.is_some())
+    }
+
+    /// Publish a signal with its strength smoothed by a per-symbol EMA
+    ///
+    /// The moving average is kept in Redis under `ema:{symbol}` and updated
+    /// as `alpha * strength + (1 - alpha) * previous`. The published signal
+    /// carries the smoothed strength, with the raw value preserved in
+    /// `metadata.raw_strength`. Returns the signal as published.
+    ///
+    /// The update is a compare-and-set, retried if another publisher changed
+    /// the average in between, so concurrent publishers never lose a step.
+    pub async fn publish_smoothed_signal(
+        &mut self,
+        signal: &TradingSignal,
+        alpha: f64,
+    ) -> Result<TradingSignal> {
+        let key = redis_key("ema", &signal.symbol);
+        let script = redis::Script::new(SET_IF_UNCHANGED);
+        let ema = loop {
+            let current: Option<String> = self.client.get(&key).await?;
+            let previous = match &current {
+                Some(value) => Some(value.parse::<f64>().map_err(|e| {
+                    ExecutionError::Validation(format!("invalid EMA at {}: {}", key, e))
+                })?),
+                None => None,
+            };
+            let ema = smooth_strength(previous, signal.strength, alpha)?;
+            let set: i64 = script
+                .key(&key)
+                .arg(current.unwrap_or_default())
+                .arg(ema.to_string())
+                .invoke_async(&mut self.client)
+                .await?;
+            if set == 1 {
+                break ema;
+            }
+        };
+
+        let mut smoothed = signal.clone();
+        smoothed.strength = ema;
+        smoothed.metadata = match std::mem::take(&mut smoothed.metadata) {
+            serde_json::Value::Object(mut fields) => {
+                fields.insert("raw_strength".to_string(), signal.strength.into());
+                serde_json::Value::Object(fields)
+            }
+            serde_json::Value::Null => serde_json::json!({ "raw_strength": signal.strength }),
+            other => serde_json::json!({ "raw_strength": signal.strength, "metadata": other }),
+        };
+
+        self.publish_signal(&smoothed).await?;
+        Ok(smoothed)
+    }
+
+    /// Append a signal to the `signals` stream, returning its entry id
+    ///
+    /// Unlike Pub/Sub, stream entries are retained until acknowledged, so
+    /// consumers that are offline when a signal is published still see it.
+    pub async fn publish_signal_stream(&mut self, signal: &TradingSignal) -> Result<String> {
         let value = serde_json::to_string(signal)?;
+        let id: String = self
+            .client
+            .xadd(SIGNAL_STREAM, "*", &[("data", value)])
+            .await?;
+        Ok(id)
+    }
+
+    /// Read signals for `consumer` in consumer group `group`
+    ///
+    /// The group is created on first use and starts at the end of the stream.
+    /// Entries previously delivered to this consumer but not yet acknowledged
+    /// are returned first, so a restarted consumer resumes where it left off.
+    /// Acknowledge each processed entry with [`SignalManager::ack_signal`].
+    pub async fn consume_signals(
+        &mut self,
+        group: &str,
+        consumer: &str,
+    ) -> Result<Vec<(String, TradingSignal)>> {
+        self.ensure_group(group).await?;
 
-        self.client.set_ex::<_, _, ()>(&key, value, 300).await?; // Expire after 5 minutes
+        let pending = self.read_group(group, consumer, "0").await?;
+        if !pending.is_empty() {
+            return Ok(pending);
+        }
+        self.read_group(group, consumer, ">").await
+    }
+
+    /// Acknowledge a stream entry so it is not redelivered
+    pub async fn ack_signal(&mut self, group: &str, id: &str) -> Result<()> {
         self.client
-            .publish::<_, _, ()>("trading_signals", &key)
+            .xack::<_, _, _, ()>(SIGNAL_STREAM, group, &[id])
+            .await?;
+        Ok(())
+    }
+
+    async fn ensure_group(&mut self, group: &str) -> Result<()> {
+        let created: redis::RedisResult<()> = self
+            .client
+            .xgroup_create_mkstream(SIGNAL_STREAM, group, "$")
+            .await;
+        match created {
+            Err(e) if e.code() != Some("BUSYGROUP") => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn read_group(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        start: &str,
+    ) -> Result<Vec<(String, TradingSignal)>> {
+        let options = StreamReadOptions::default()
+            .group(group, consumer)
+            .count(STREAM_BATCH_SIZE);
+        let reply: Option<StreamReadReply> = self
+            .client
+            .xread_options(&[SIGNAL_STREAM], &[start], &options)
             .await?;
 
+        let mut signals = Vec::new();
+        for key in reply.map(|r| r.keys).unwrap_or_default() {
+            for entry in key.ids {
+                let Some(data) = entry.get::<String>("data") else {
+                    continue;
+                };
+                signals.push((entry.id, serde_json::from_str(&data)?));
+            }
+        }
+        Ok(signals)
+    }
+
+    /// Store the latest price for a symbol under `price:{symbol}`
+    pub async fn publish_price(&mut self, symbol: &str, price: f64) -> Result<()> {
+        self.client
+            .set::<_, _, ()>(redis_key("price", symbol), price)
+            .await?;
         Ok(())
     }
 
-    /// Get the latest signal for a symbol
-    pub async fn get_signal(&mut self, symbol: &str) -> Result<Option<TradingSignal>> {
-        let key = format!("signal:{}", symbol);
-        let value: Option<String> = self.client.get(&key).await?;
+    /// Store an order template under `template:{name}`, replacing any
+    /// template of that name
+    ///
+    /// The template is validated first, so an invalid one is never stored.
+    /// Templates do not expire.
+    pub async fn save_template(&mut self, name: &str, template: &OrderTemplate) -> Result<()> {
+        template.validate()?;
+        let value = serde_json::to_string(template)?;
+        self.client
+            .set::<_, _, ()>(redis_key("template", name), value)
+            .await?;
+        Ok(())
+    }
 
+    /// Load the order template saved under `name`
+    pub async fn load_template(&self, name: &str) -> Result<Option<OrderTemplate>> {
+        let value: Option<String> = self.client.clone().get(redis_key("template", name)).await?;
         match value {
-            Some(v) => {
-                let signal = serde_json::from_str(&v)?;
-                Ok(Some(signal))
-            }
+            Some(v) => Ok(Some(serde_json::from_str(&v)?)),
             None => Ok(None),
         }
     }
 
+    /// Price source reading `price:{symbol}` over this manager's connection
+    pub fn price_source(&self) -> RedisPriceSource {
+        RedisPriceSource {
+            client: self.client.clone(),
+        }
+    }
+
+    /// Symbols whose `signal:{symbol}` key has expired, as Redis expires them
+    ///
+    /// Relies on keyspace notifications, which Redis leaves off by default:
+    /// the server needs `notify-keyspace-events` to include `Ex` (for
+    /// example `CONFIG SET notify-keyspace-events Ex`). If the server reports
+    /// them disabled, a warning is logged and the stream ends immediately.
+    /// Notifications are fire-and-forget, so expiries while the stream is not
+    /// connected are missed; Redis may also report an expiry some time after
+    /// the TTL passed.
+    ///
+    /// Nothing is subscribed until the stream is first polled. Failing to
+    /// connect or subscribe is yielded as the stream's only item.
+    pub fn subscribe_expirations(&self) -> impl Stream<Item = Result<String>> {
+        let redis = self.redis.clone();
+        let client = self.client.clone();
+        stream::once(expirations(redis, client)).flat_map(|subscribed| {
+            subscribed.unwrap_or_else(|e| stream::once(future::ready(Err(e))).boxed())
+        })
+    }
+
     /// Subscribe to trading signals (returns channel for receiving signals)
     pub async fn subscribe(&self) -> Result<redis::aio::PubSub> {
         // Note: PubSub requires a separate connection, not ConnectionManager
@@ -66,6 +598,143 @@ impl SignalManager {
     }
 }
 
+/// Subscribe to expiry events on the client's database, keeping those for
+/// `signal:` keys; empty if the server has expiry notifications turned off
+async fn expirations(
+    redis: Client,
+    mut client: ConnectionManager,
+) -> Result<BoxStream<'static, Result<String>>> {
+    // CONFIG is often disabled on managed servers; then subscribe anyway
+    let config: redis::RedisResult<Vec<String>> = redis::cmd("CONFIG")
+        .arg("GET")
+        .arg("notify-keyspace-events")
+        .query_async(&mut client)
+        .await;
+    if let Ok(config) = config {
+        let flags = config.get(1).map(String::as_str).unwrap_or_default();
+        if !expiry_events_enabled(flags) {
+            tracing::warn!(
+                "notify-keyspace-events is '{}'; signal expirations will not be reported",
+                flags
+            );
+            return Ok(stream::empty().boxed());
+        }
+    }
+
+    let channel = format!(
+        "__keyevent@{}__:expired",
+        redis.get_connection_info().redis.db
+    );
+    let mut pubsub = redis.get_async_connection().await?.into_pubsub();
+    pubsub.subscribe(channel).await?;
+
+    let symbols = pubsub.into_on_message().filter_map(|message| {
+        let symbol = match message.get_payload::<String>() {
+            Ok(key) => key
+                .strip_prefix("signal:")
+                .and_then(decode_key_component)
+                .map(Ok),
+            Err(e) => Some(Err(e.into())),
+        };
+        future::ready(symbol)
+    });
+    Ok(symbols.boxed())
+}
+
+/// Whether `notify-keyspace-events` flags include keyevent (`E`) expiry
+/// (`x`, or `A` for all) notifications
+fn expiry_events_enabled(flags: &str) -> bool {
+    flags.contains('E') && (flags.contains('x') || flags.contains('A'))
+}
+
+/// Marker key identifying equivalent signals for [`SignalManager::publish_dedup`]
+fn dedup_key(signal: &TradingSignal) -> String {
+    let scale = 10f64.powi(DEDUP_STRENGTH_DECIMALS);
+    format!(
+        "dedup:{}:{}:{}",
+        encode_key_component(&signal.symbol),
+        encode_key_component(&signal.signal_type),
+        (signal.strength * scale).round() as i64
+    )
+}
+
+/// Redis key recording that `key_id` claimed `nonce`
+fn nonce_key(key_id: &str, nonce: u64) -> String {
+    format!("{}:{}", redis_key("nonce", key_id), nonce)
+}
+
+/// `{prefix}:{component}` with the component encoded so that it cannot
+/// contain `:` or glob characters
+fn redis_key(prefix: &str, component: &str) -> String {
+    format!("{}:{}", prefix, encode_key_component(component))
+}
+
+/// Percent-encode every byte outside `[A-Za-z0-9/._-]`
+///
+/// Ordinary symbols such as `BTC/USD` are left unchanged, so their keys are
+/// the same as before encoding was introduced.
+fn encode_key_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'/' | b'.' | b'_' | b'-') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Reverse [`encode_key_component`]; `None` if `encoded` is not its output
+fn decode_key_component(encoded: &str) -> Option<String> {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = encoded.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// One exponential moving average step; the first value seeds the average
+pub fn smooth_strength(previous: Option<f64>, strength: f64, alpha: f64) -> Result<f64> {
+    if !(alpha > 0.0 && alpha <= 1.0) {
+        return Err(ExecutionError::Validation(format!(
+            "EMA alpha must be in (0, 1], got {}",
+            alpha
+        ))
+        .into());
+    }
+    Ok(match previous {
+        Some(prev) => alpha * strength + (1.0 - alpha) * prev,
+        None => strength,
+    })
+}
+
+/// [`PriceSource`] backed by `price:{symbol}` keys in Redis
+#[derive(Clone)]
+pub struct RedisPriceSource {
+    client: ConnectionManager,
+}
+
+#[async_trait::async_trait]
+impl PriceSource for RedisPriceSource {
+    async fn price(&self, symbol: &str) -> Result<f64> {
+        let mut client = self.client.clone();
+        let price: Option<f64> = client.get(redis_key("price", symbol)).await?;
+        price.ok_or_else(|| {
+            ExecutionError::Validation(format!("no price available for {}", symbol)).into()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +752,463 @@ mod tests {
         assert_eq!(signal.symbol, "BTC/USD");
         assert_eq!(signal.strength, 0.85);
     }
+
+    fn signal(symbol: &str, strength: f64) -> TradingSignal {
+        TradingSignal {
+            symbol: symbol.to_string(),
+            signal_type: "momentum".to_string(),
+            strength,
+            timestamp: 0,
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_typed_metadata() {
+        let mut s = signal("BTC/USD", 0.5);
+        assert_eq!(s.get_meta::<String>("source").unwrap(), None);
+
+        s.set_meta("source", "momentum-agent").unwrap();
+        s.set_meta("model_version", 3).unwrap();
+        assert_eq!(
+            s.get_meta::<String>("source").unwrap().as_deref(),
+            Some("momentum-agent")
+        );
+        assert_eq!(s.get_meta::<u32>("model_version").unwrap(), Some(3));
+        assert!(s.get_meta::<u32>("source").is_err());
+
+        // Non-object metadata is replaced rather than merged
+        s.metadata = serde_json::json!([1, 2]);
+        assert_eq!(s.get_meta::<u32>("model_version").unwrap(), None);
+        s.set_meta("source", "other").unwrap();
+        assert_eq!(s.metadata, serde_json::json!({"source": "other"}));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_signal_bincode_roundtrip() {
+        let mut s = signal("BTC/USD", 0.75);
+        s.set_meta("source", "momentum-agent").unwrap();
+
+        let binary = s.to_bincode().unwrap();
+        assert!(binary.len() < serde_json::to_vec(&s).unwrap().len());
+        let decoded = TradingSignal::from_bincode(&binary).unwrap();
+        assert_eq!(decoded.symbol, s.symbol);
+        assert_eq!(decoded.strength, s.strength);
+        assert_eq!(decoded.metadata, s.metadata);
+    }
+
+    #[test]
+    fn test_signal_codecs_roundtrip() {
+        let mut s = signal("BTC/USD", 0.75);
+        s.set_meta("source", "momentum-agent").unwrap();
+
+        let codecs = [
+            SignalCodec::Json,
+            #[cfg(feature = "bincode")]
+            SignalCodec::Bincode,
+            #[cfg(feature = "msgpack")]
+            SignalCodec::MessagePack,
+        ];
+        let json_len = SignalCodec::Json.encode(&s).unwrap().len();
+        for codec in codecs {
+            let bytes = codec.encode(&s).unwrap();
+            // The binary codecs exist to be smaller than JSON
+            assert!(
+                codec == SignalCodec::Json || bytes.len() < json_len,
+                "{:?}: {} bytes",
+                codec,
+                bytes.len()
+            );
+            let decoded = codec.decode(&bytes).unwrap();
+            assert_eq!(decoded.symbol, s.symbol);
+            assert_eq!(decoded.strength, s.strength);
+            assert_eq!(decoded.timestamp, s.timestamp);
+            assert_eq!(decoded.metadata, s.metadata);
+        }
+    }
+
+    #[test]
+    fn test_shared_read_errors_keep_their_kind() {
+        let original = redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "Response was of incompatible type",
+            "WRONGTYPE".to_string(),
+        ));
+        let rebuilt = unshare_read_error(share_read_error(original.into()));
+        assert_eq!(rebuilt.kind(), redis::ErrorKind::TypeError);
+        assert_eq!(rebuilt.detail(), Some("WRONGTYPE"));
+    }
+
+    #[test]
+    fn test_dedup_key_rounds_strength() {
+        assert_eq!(
+            dedup_key(&signal("BTC/USD", 0.7501)),
+            dedup_key(&signal("BTC/USD", 0.7499))
+        );
+        assert_ne!(
+            dedup_key(&signal("BTC/USD", 0.75)),
+            dedup_key(&signal("BTC/USD", 0.76))
+        );
+        assert_ne!(
+            dedup_key(&signal("BTC/USD", 0.75)),
+            dedup_key(&signal("ETH/USD", 0.75))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_dedup_within_window() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let s = signal(&format!("TEST{}/USD", uuid::Uuid::new_v4().simple()), 0.5);
+        let window = Duration::from_millis(200);
+
+        assert!(manager.publish_dedup(&s, window).await.unwrap());
+        assert!(!manager.publish_dedup(&s, window).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(manager.publish_dedup(&s, window).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reserve_nonce_once_across_replicas() {
+        let Some(manager) = test_manager().await else {
+            return;
+        };
+        let key_id = format!("test-{}", uuid::Uuid::new_v4());
+
+        // Clones stand in for replicas racing to claim the same nonce
+        let claims = (0..8).map(|_| {
+            let mut replica = manager.clone();
+            let key_id = key_id.clone();
+            tokio::spawn(async move { replica.reserve_nonce(&key_id, 7).await.unwrap() })
+        });
+        let mut won = 0;
+        for claim in claims.collect::<Vec<_>>() {
+            if claim.await.unwrap() {
+                won += 1;
+            }
+        }
+        assert_eq!(won, 1);
+
+        // Nonces may be claimed in any order, each only once, and other
+        // signers are independent
+        let mut manager = manager;
+        let other = format!("{}-other", key_id);
+        assert!(manager.reserve_nonce(&key_id, 9).await.unwrap());
+        assert!(manager.reserve_nonce(&key_id, 5).await.unwrap());
+        assert!(manager.reserve_nonce(&other, 7).await.unwrap());
+        assert!(!manager.reserve_nonce(&key_id, 9).await.unwrap());
+        assert!(!manager.reserve_nonce(&key_id, 5).await.unwrap());
+
+        let keys: Vec<String> = [(&key_id, 5), (&key_id, 7), (&key_id, 9), (&other, 7)]
+            .iter()
+            .map(|(key_id, nonce)| nonce_key(key_id, *nonce))
+            .collect();
+        let _: () = manager.client.del(&keys).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_engines_share_nonces() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        // Two replicas signing with the same key
+        let key = crate::SigningKey::generate();
+        let replica =
+            || crate::ExecutionEngine::new(key.clone()).with_shared_nonces(manager.clone());
+        let (a, b) = (replica(), replica());
+
+        let order = || {
+            crate::Order::new(
+                "BTC/USD".to_string(),
+                crate::execution::OrderSide::Buy,
+                crate::execution::OrderType::Market,
+                0.1,
+            )
+        };
+        let first = order();
+        a.execute_order(first.clone()).await.unwrap();
+        let err = b.execute_order(first.clone()).await.unwrap_err();
+        assert!(matches!(err, Error::Execution(ExecutionError::Replay)));
+
+        // Executed out of creation order, both are still admitted
+        let (older, newer) = (order(), order());
+        b.execute_order(newer.clone()).await.unwrap();
+        a.execute_order(older.clone()).await.unwrap();
+
+        let keys: Vec<String> = [first, older, newer]
+            .iter()
+            .map(|order| nonce_key(&key.key_id(), order.nonce))
+            .collect();
+        let _: () = manager.client.del(&keys).await.unwrap();
+    }
+
+    #[test]
+    fn test_smoothing_converges_and_validates_alpha() {
+        let mut ema = smooth_strength(None, 0.0, 0.5).unwrap();
+        for _ in 0..50 {
+            ema = smooth_strength(Some(ema), 1.0, 0.5).unwrap();
+        }
+        assert!((ema - 1.0).abs() < 1e-9);
+        assert_eq!(smooth_strength(Some(0.2), 0.8, 1.0).unwrap(), 0.8);
+
+        for alpha in [0.0, -0.1, 1.5, f64::NAN] {
+            assert!(smooth_strength(Some(0.5), 1.0, alpha).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_smoothed_signal() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let signal = TradingSignal {
+            symbol: format!("TEST{}/USD", uuid::Uuid::new_v4().simple()),
+            signal_type: "momentum".to_string(),
+            strength: 1.0,
+            timestamp: 0,
+            metadata: serde_json::Value::Null,
+        };
+
+        manager.publish_smoothed_signal(&signal, 0.5).await.unwrap();
+        let raw = TradingSignal {
+            strength: 0.0,
+            ..signal.clone()
+        };
+        let smoothed = manager.publish_smoothed_signal(&raw, 0.5).await.unwrap();
+        assert_eq!(smoothed.strength, 0.5);
+        assert_eq!(smoothed.metadata["raw_strength"], 0.0);
+
+        let stored = manager.get_signal(&signal.symbol).await.unwrap().unwrap();
+        assert_eq!(stored.strength, 0.5);
+
+        // Concurrent steps all land: eight halvings towards 1.0, in any order
+        let publishes = (0..8).map(|_| {
+            let mut manager = manager.clone();
+            let signal = signal.clone();
+            async move { manager.publish_smoothed_signal(&signal, 0.5).await }
+        });
+        for result in future::join_all(publishes).await {
+            result.unwrap();
+        }
+        let ema: f64 = manager
+            .client
+            .get(redis_key("ema", &signal.symbol))
+            .await
+            .unwrap();
+        assert!((ema - (1.0 - 0.5f64.powi(9))).abs() < 1e-12);
+
+        let _: () = manager
+            .client
+            .del(&[
+                redis_key("ema", &signal.symbol),
+                redis_key("signal", &signal.symbol),
+            ])
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sort_by_strength_is_stable() {
+        let mut signals = vec![
+            signal("A/USD", 0.2),
+            signal("B/USD", f64::NAN),
+            signal("C/USD", 0.9),
+            signal("D/USD", 0.2),
+            signal("E/USD", -0.5),
+        ];
+        sort_by_strength(&mut signals);
+        let order: Vec<_> = signals.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(order, vec!["C/USD", "A/USD", "D/USD", "E/USD", "B/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_drain_by_strength() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let weak = format!("WEAK{}/USD", uuid::Uuid::new_v4().simple());
+        let strong = format!("STRONG{}/USD", uuid::Uuid::new_v4().simple());
+        manager.publish_signal(&signal(&weak, 0.1)).await.unwrap();
+        manager.publish_signal(&signal(&strong, 0.8)).await.unwrap();
+
+        let signals = manager
+            .drain_by_strength(&[&weak, "MISSING/USD", &strong])
+            .await
+            .unwrap();
+        let order: Vec<_> = signals.iter().map(|s| s.symbol.clone()).collect();
+        assert_eq!(order, vec![strong, weak]);
+    }
+
+    #[test]
+    fn test_key_components_are_encoded() {
+        assert_eq!(redis_key("signal", "BTC/USD"), "signal:BTC/USD");
+        assert_eq!(redis_key("signal", "BTC:PERP"), "signal:BTC%3APERP");
+        assert_eq!(redis_key("signal", "BTC*"), "signal:BTC%2A");
+
+        for symbol in ["BTC/USD", "BTC:PERP", "BTC%3APERP", "a b:*?[]", "ÉTH"] {
+            let encoded = encode_key_component(symbol);
+            assert!(!encoded.contains(':'));
+            assert_eq!(decode_key_component(&encoded).as_deref(), Some(symbol));
+        }
+        assert_ne!(
+            encode_key_component("BTC:PERP"),
+            encode_key_component("BTC%3APERP")
+        );
+        assert_eq!(decode_key_component("BTC%3"), None);
+
+        // A colon in the symbol cannot shift the type into the symbol slot
+        let mut a = signal("A:buy", 0.5);
+        a.signal_type = "x".to_string();
+        let mut b = signal("A", 0.5);
+        b.signal_type = "buy:x".to_string();
+        assert_ne!(dedup_key(&a), dedup_key(&b));
+    }
+
+    #[tokio::test]
+    async fn test_colon_symbols_do_not_collide() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let base = format!("TEST{}", uuid::Uuid::new_v4().simple());
+        let perp = format!("{}:PERP", base);
+        let lookalike = format!("{}%3APERP", base);
+        manager.publish_signal(&signal(&perp, 0.7)).await.unwrap();
+        manager
+            .publish_signal(&signal(&lookalike, 0.2))
+            .await
+            .unwrap();
+
+        let stored = manager.get_signal(&perp).await.unwrap().unwrap();
+        assert_eq!(stored.symbol, perp);
+        assert_eq!(stored.strength, 0.7);
+        let other = manager.get_signal(&lookalike).await.unwrap().unwrap();
+        assert_eq!(other.strength, 0.2);
+        assert!(manager.get_signal(&base).await.unwrap().is_none());
+
+        let symbols = manager.signal_symbols().await.unwrap();
+        assert!(symbols.contains(&perp));
+        assert!(symbols.contains(&lookalike));
+    }
+
+    #[tokio::test]
+    async fn test_order_templates_round_trip() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let name = format!("test-{}", uuid::Uuid::new_v4());
+        let template = OrderTemplate {
+            symbol: "ETH/USD".to_string(),
+            side: crate::execution::OrderSide::Buy,
+            order_type: crate::execution::OrderType::Limit { price: 3000.0 },
+            quantity: 1.5,
+        };
+
+        assert_eq!(manager.load_template(&name).await.unwrap(), None);
+        manager.save_template(&name, &template).await.unwrap();
+        assert_eq!(
+            manager.load_template(&name).await.unwrap(),
+            Some(template.clone())
+        );
+
+        // An invalid template leaves the saved one in place
+        let invalid = OrderTemplate {
+            quantity: -1.0,
+            ..template.clone()
+        };
+        assert!(manager.save_template(&name, &invalid).await.is_err());
+        assert_eq!(manager.load_template(&name).await.unwrap(), Some(template));
+    }
+
+    #[test]
+    fn test_expiry_events_enabled() {
+        assert!(expiry_events_enabled("Ex"));
+        assert!(expiry_events_enabled("KEA"));
+        assert!(!expiry_events_enabled(""));
+        assert!(!expiry_events_enabled("Kx"));
+        assert!(!expiry_events_enabled("E$"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_expirations() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let _: () = redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("notify-keyspace-events")
+            .arg("Ex")
+            .query_async(&mut manager.client)
+            .await
+            .unwrap();
+
+        let mut expirations = Box::pin(manager.subscribe_expirations());
+        // The stream subscribes on its first poll, well inside the TTL
+        let symbol = format!("EXPIRE:{}", uuid::Uuid::new_v4());
+        let _: () = manager
+            .client
+            .set_ex(redis_key("signal", &symbol), "{}", 1)
+            .await
+            .unwrap();
+
+        let expired = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let expired = expirations.next().await.unwrap().unwrap();
+                if expired == symbol {
+                    return expired;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(expired, symbol);
+    }
+
+    /// Connect to the Redis at `REDIS_URL`, or `None` to skip the test
+    async fn test_manager() -> Option<SignalManager> {
+        let url = std::env::var("REDIS_URL").ok()?;
+        Some(SignalManager::connect(&url).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_stream_consumer_group_redelivers_unacked() {
+        let Some(mut manager) = test_manager().await else {
+            return;
+        };
+        let group = format!("test-{}", uuid::Uuid::new_v4());
+
+        // Creating the group first means it only sees entries added after it
+        assert!(manager
+            .consume_signals(&group, "a")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let signal = TradingSignal {
+            symbol: "BTC/USD".to_string(),
+            signal_type: "buy".to_string(),
+            strength: 0.9,
+            timestamp: 1234567890,
+            metadata: serde_json::Value::Null,
+        };
+        let id = manager.publish_signal_stream(&signal).await.unwrap();
+
+        let delivered = manager.consume_signals(&group, "a").await.unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].0, id);
+        assert_eq!(delivered[0].1.strength, 0.9);
+
+        // Not acknowledged: a restarted consumer gets it again
+        let redelivered = manager.consume_signals(&group, "a").await.unwrap();
+        assert_eq!(redelivered[0].0, id);
+
+        manager.ack_signal(&group, &id).await.unwrap();
+        assert!(manager
+            .consume_signals(&group, "a")
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
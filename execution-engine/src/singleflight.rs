@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OnceCell;
+
+/// Coalesces concurrent calls for the same key into one execution
+///
+/// While a call for a key is in flight, further calls for that key wait for
+/// it and receive a clone of its output instead of running their own. Once
+/// the call completes the key is forgotten, so later calls run afresh.
+pub(crate) struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> SingleFlight<K, V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) async fn run<F, Fut>(&self, key: K, f: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let cell = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
+
+        let value = cell.get_or_init(f).await.clone();
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            in_flight.remove(&key);
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_fetch() {
+        let flight = Arc::new(SingleFlight::<String, u64>::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let flight = flight.clone();
+                let fetches = fetches.clone();
+                tokio::spawn(async move {
+                    flight
+                        .run("BTC/USD".to_string(), || async move {
+                            fetches.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            42
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), 42);
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Once the flight lands, the next call fetches again
+        let value = flight
+            .run("BTC/USD".to_string(), || async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                7
+            })
+            .await;
+        assert_eq!(value, 7);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,14 +1,111 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{postgres::PgPoolOptions, Executor, PgPool, Postgres, QueryBuilder};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::{
-    execution::{OrderResult, OrderStatus},
-    Result,
+    book::OrderBook,
+    crypto::{self, HashAlgorithm, Signature, VerificationKey},
+    dead_letter,
+    decision::{self, Decision},
+    execution::{Order, OrderResult, OrderSide, OrderStatus, OrderType, TimeInForce, Urgency},
+    retry, Error, ExecutionError, Result,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+/// Every [`OrderRow`] column from `orders`; order queries append their
+/// filters to this so the column lists cannot drift apart
+const SELECT_ORDERS: &str = r#"
+    SELECT id, symbol, side, order_type, quantity, price, status,
+           execution_price, executed_quantity, fee, signature, book_snapshot, exchange_order_id,
+           nonce, time_in_force, parent_id, source_timestamp, reduce_only, correlation_id,
+           client_order_id, tags, urgency, quote_quantity, created_at, updated_at
+    FROM orders
+"#;
+
+/// Follows [`SELECT_ORDERS`]; the predicate must match the `idx_orders_open`
+/// partial index
+const OPEN_ORDERS_FILTER: &str = r#"
+    WHERE status IN ('pending', 'partially_filled')
+    ORDER BY created_at
+"#;
+
+/// Creates the unique `idx_orders_exchange_order_id` index, first clearing
+/// the venue id from all but the most recently updated of any rows written
+/// before it existed that share one; does nothing once the index exists
+const UNIQUE_EXCHANGE_ORDER_ID: &str = r#"
+    DO $$
+    BEGIN
+        IF to_regclass('idx_orders_exchange_order_id') IS NULL THEN
+            UPDATE orders SET exchange_order_id = NULL
+            WHERE id IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY exchange_order_id ORDER BY updated_at DESC, id DESC
+                    ) AS position
+                    FROM orders
+                    WHERE exchange_order_id IS NOT NULL
+                ) ranked
+                WHERE position > 1
+            );
+            CREATE UNIQUE INDEX idx_orders_exchange_order_id
+                ON orders(exchange_order_id) WHERE exchange_order_id IS NOT NULL;
+        END IF;
+    END
+    $$;
+"#;
+
+/// Adds the `seq` column that orders the decision chain, numbering rows
+/// written before it existed by `created_at` so they keep their chain
+/// order; does nothing once the column exists
+const DECISIONS_SEQ: &str = r#"
+    DO $$
+    BEGIN
+        IF NOT EXISTS (
+            SELECT 1 FROM information_schema.columns
+            WHERE table_schema = current_schema()
+              AND table_name = 'decisions'
+              AND column_name = 'seq'
+        ) THEN
+            ALTER TABLE decisions ADD COLUMN seq BIGINT;
+            UPDATE decisions SET seq = numbered.seq
+            FROM (
+                SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, id) AS seq
+                FROM decisions
+            ) numbered
+            WHERE decisions.id = numbered.id;
+            CREATE SEQUENCE decisions_seq_seq OWNED BY decisions.seq;
+            PERFORM setval('decisions_seq_seq', COALESCE(MAX(seq), 0) + 1, false)
+            FROM decisions;
+            ALTER TABLE decisions
+                ALTER COLUMN seq SET DEFAULT nextval('decisions_seq_seq'),
+                ALTER COLUMN seq SET NOT NULL;
+        END IF;
+    END
+    $$;
+"#;
+
+/// How long each `connect_with_retry` attempt waits for a connection
+///
+/// The pool itself keeps retrying refused connections until its acquire
+/// timeout (30 seconds by default), which would hide the outer backoff.
+const CONNECT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Length of an Ed25519 signature as stored in `BYTEA` columns
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Position in the order history, as the `(created_at, id)` of the last
+/// order on a page; see [`Database::orders_after`]
+pub type Cursor = (DateTime<Utc>, Uuid);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderRecord {
     pub id: Uuid,
     pub symbol: String,
@@ -19,10 +116,540 @@ pub struct OrderRecord {
     pub status: String,
     pub execution_price: Option<f64>,
     pub executed_quantity: Option<f64>,
+    /// See [`OrderResult::fee`]
+    #[serde(default)]
+    pub fee: Option<f64>,
+    /// The engine's signature over the order
+    pub signature: Option<Signature>,
+    /// Book the order filled against, if the venue reported one
+    pub book_snapshot: Option<OrderBook>,
+    /// The venue's id for the order, unique across rows
+    pub exchange_order_id: Option<String>,
+    // The remaining signed fields, so the order can be rebuilt and its
+    // signature checked (see `TryFrom<OrderRecord> for Order`)
+    #[serde(default)]
+    pub nonce: u64,
+    #[serde(default = "default_time_in_force")]
+    pub time_in_force: String,
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub source_timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(default = "default_urgency")]
+    pub urgency: String,
+    #[serde(default)]
+    pub quote_quantity: bool,
+    /// See [`Order::correlation_id`]; for joining logs to rows
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// See [`Order::client_order_id`]; for reconciling against venue reports
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    /// See [`Order::tags`]
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub id: Uuid,
+    pub order_id: Option<Uuid>,
+    pub decision_data: serde_json::Value,
+    pub proof_hash: Vec<u8>,
+    /// `proof_hash` of the decision stored before this one, which is hashed
+    /// in ahead of the data; `None` for the first decision and for
+    /// decisions stored before the chain existed
+    #[serde(default)]
+    pub proof_prev_hash: Option<Vec<u8>>,
+    pub hash_algorithm: String,
+    pub signature: Signature,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `orders` row as read from Postgres, before the signature is decoded
+#[derive(sqlx::FromRow)]
+struct OrderRow {
+    id: Uuid,
+    symbol: String,
+    side: String,
+    order_type: String,
+    quantity: f64,
+    price: Option<f64>,
+    status: String,
+    execution_price: Option<f64>,
+    executed_quantity: Option<f64>,
+    fee: Option<f64>,
+    signature: Option<Vec<u8>>,
+    book_snapshot: Option<sqlx::types::Json<OrderBook>>,
+    exchange_order_id: Option<String>,
+    nonce: i64,
+    time_in_force: String,
+    parent_id: Option<Uuid>,
+    source_timestamp: Option<DateTime<Utc>>,
+    reduce_only: bool,
+    correlation_id: Option<String>,
+    client_order_id: Option<String>,
+    tags: Vec<String>,
+    urgency: String,
+    quote_quantity: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<OrderRow> for OrderRecord {
+    type Error = Error;
+
+    fn try_from(row: OrderRow) -> Result<Self> {
+        let signature = row
+            .signature
+            .map(|bytes| decode_signature(&bytes, "order", row.id))
+            .transpose()?;
+        Ok(Self {
+            id: row.id,
+            symbol: row.symbol,
+            side: row.side,
+            order_type: row.order_type,
+            quantity: row.quantity,
+            price: row.price,
+            status: row.status,
+            execution_price: row.execution_price,
+            executed_quantity: row.executed_quantity,
+            fee: row.fee,
+            signature,
+            book_snapshot: row.book_snapshot.map(|book| book.0),
+            exchange_order_id: row.exchange_order_id,
+            // BIGINT holds the nonce's bits; see `store_order`
+            nonce: row.nonce as u64,
+            time_in_force: row.time_in_force,
+            parent_id: row.parent_id,
+            source_timestamp: row.source_timestamp,
+            reduce_only: row.reduce_only,
+            correlation_id: row.correlation_id,
+            client_order_id: row.client_order_id,
+            tags: row.tags,
+            urgency: row.urgency,
+            quote_quantity: row.quote_quantity,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+fn default_time_in_force() -> String {
+    TimeInForce::default().as_str().to_string()
+}
+
+fn default_urgency() -> String {
+    Urgency::default().as_str().to_string()
+}
+
+/// Rebuild the domain order from its row, for replay and re-verification
+///
+/// Fails with [`ExecutionError::Validation`] if a stored string is not one
+/// the engine writes. Approval signatures are not stored, so `signatures` is
+/// empty.
+impl TryFrom<OrderRecord> for Order {
+    type Error = Error;
+
+    fn try_from(record: OrderRecord) -> Result<Self> {
+        let malformed = |field: &str, value: &str| -> Error {
+            ExecutionError::Validation(format!(
+                "order {} has malformed {} '{}'",
+                record.id, field, value
+            ))
+            .into()
+        };
+
+        let side = match record.side.as_str() {
+            "buy" => OrderSide::Buy,
+            "sell" => OrderSide::Sell,
+            other => return Err(malformed("side", other)),
+        };
+        let order_type = match (record.order_type.as_str(), record.price) {
+            ("market", None) => OrderType::Market,
+            ("limit", Some(price)) => OrderType::Limit { price },
+            (other, _) => return Err(malformed("order type", other)),
+        };
+        let time_in_force = match record.time_in_force.as_str() {
+            "gtc" => TimeInForce::GoodTilCancelled,
+            "ioc" => TimeInForce::ImmediateOrCancel,
+            "fok" => TimeInForce::FillOrKill,
+            other => return Err(malformed("time in force", other)),
+        };
+        let urgency = match record.urgency.as_str() {
+            "passive" => Urgency::Passive,
+            "normal" => Urgency::Normal,
+            "urgent" => Urgency::Urgent,
+            other => return Err(malformed("urgency", other)),
+        };
+
+        Ok(Order {
+            id: record.id,
+            symbol: record.symbol,
+            side,
+            order_type,
+            quantity: record.quantity,
+            timestamp: record.created_at,
+            nonce: record.nonce,
+            time_in_force,
+            signature: record.signature,
+            signatures: Vec::new(),
+            parent_id: record.parent_id,
+            source_timestamp: record.source_timestamp,
+            reduce_only: record.reduce_only,
+            urgency,
+            quote_quantity: record.quote_quantity,
+            correlation_id: record.correlation_id,
+            client_order_id: record.client_order_id,
+            tags: record.tags,
+        })
+    }
+}
+
+/// `decisions` row as read from Postgres, before the signature is decoded
+#[derive(sqlx::FromRow)]
+struct DecisionRow {
+    id: Uuid,
+    order_id: Option<Uuid>,
+    decision_data: serde_json::Value,
+    proof_hash: Vec<u8>,
+    proof_prev_hash: Option<Vec<u8>>,
+    hash_algorithm: String,
+    signature: Vec<u8>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DecisionRow> for DecisionRecord {
+    type Error = Error;
+
+    fn try_from(row: DecisionRow) -> Result<Self> {
+        Ok(Self {
+            signature: decode_signature(&row.signature, "decision", row.id)?,
+            id: row.id,
+            order_id: row.order_id,
+            decision_data: row.decision_data,
+            proof_hash: row.proof_hash,
+            proof_prev_hash: row.proof_prev_hash,
+            hash_algorithm: row.hash_algorithm,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// Encode a signature for a `BYTEA` column
+///
+/// A [`Signature`] is always [`SIGNATURE_LENGTH`] bytes (malformed ones are
+/// rejected when parsed), so only reads need checking.
+fn encode_signature(signature: &Signature) -> Vec<u8> {
+    signature.to_bytes().to_vec()
+}
+
+fn decode_signature(bytes: &[u8], kind: &str, id: Uuid) -> Result<Signature> {
+    Signature::from_bytes(bytes).map_err(|_| {
+        Error::Crypto(format!(
+            "stored signature for {} {} is {} bytes, expected {}",
+            kind,
+            id,
+            bytes.len(),
+            SIGNATURE_LENGTH
+        ))
+    })
+}
+
+impl OrderRecord {
+    /// Row for an order and its latest result
+    pub fn new(order: &Order, result: &OrderResult) -> Self {
+        Self {
+            id: order.id,
+            symbol: order.symbol.clone(),
+            side: order.side.as_str().to_string(),
+            order_type: order.order_type.as_str().to_string(),
+            quantity: order.quantity,
+            price: order.order_type.limit_price(),
+            status: result.status.as_str().to_string(),
+            execution_price: result.execution_price,
+            executed_quantity: result.executed_quantity,
+            fee: result.fee,
+            signature: order.signature.clone(),
+            book_snapshot: result.book_snapshot.clone(),
+            exchange_order_id: result.exchange_order_id.clone(),
+            nonce: order.nonce,
+            time_in_force: order.time_in_force.as_str().to_string(),
+            parent_id: order.parent_id,
+            source_timestamp: order.source_timestamp,
+            reduce_only: order.reduce_only,
+            correlation_id: order.correlation_id.clone(),
+            client_order_id: order.client_order_id.clone(),
+            tags: order.tags.clone(),
+            urgency: order.urgency.as_str().to_string(),
+            quote_quantity: order.quote_quantity,
+            created_at: order.timestamp,
+            updated_at: result.timestamp,
+        }
+    }
+
+    fn matches(&self, query: &OrderQuery) -> bool {
+        if let Some(symbol) = &query.symbol {
+            if *symbol != self.symbol {
+                return false;
+            }
+        }
+        if let Some(status) = query.status {
+            if status.as_str() != self.status {
+                return false;
+            }
+        }
+        if let Some(tag) = &query.tag {
+            if !self.tags.contains(tag) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl DecisionRecord {
+    /// Row for a signed decision, with its proof hash computed by `algorithm`
+    ///
+    /// `prev_hash` is the proof hash of the decision stored before it. The
+    /// proof hash covers `prev_hash || canonical bytes`, or just the
+    /// canonical bytes (as [`Decision::proof_hash_with`]) without one.
+    pub fn new(
+        decision: &Decision,
+        algorithm: HashAlgorithm,
+        prev_hash: Option<Vec<u8>>,
+    ) -> Result<Self> {
+        let signature = decision
+            .signature
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("Decision is not signed".to_string()))?;
+        Ok(Self {
+            id: decision.decision_id,
+            order_id: Some(decision.order_id),
+            decision_data: decision.decision_data.clone(),
+            proof_hash: chained_hash(algorithm, prev_hash.as_deref(), &decision.canonical_bytes()),
+            proof_prev_hash: prev_hash,
+            hash_algorithm: algorithm.as_str().to_string(),
+            signature: signature.clone(),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Recompute the proof hash with the recorded algorithm and previous
+    /// hash and compare
+    pub fn verify_proof(&self) -> Result<()> {
+        let algorithm: HashAlgorithm = self.hash_algorithm.parse()?;
+        let order_id = self.order_id.ok_or_else(|| {
+            Error::Crypto(format!("decision {} is not linked to an order", self.id))
+        })?;
+        let expected = chained_hash(
+            algorithm,
+            self.proof_prev_hash.as_deref(),
+            &decision::canonical_bytes(self.id, order_id, &self.decision_data),
+        );
+        if expected != self.proof_hash {
+            return Err(Error::Crypto(format!(
+                "proof hash mismatch for decision {}",
+                self.id
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn chained_hash(algorithm: HashAlgorithm, prev_hash: Option<&[u8]>, data: &[u8]) -> Vec<u8> {
+    match prev_hash {
+        Some(prev) => crypto::hash_data_with(algorithm, &[prev, data].concat()),
+        None => crypto::hash_data_with(algorithm, data),
+    }
+}
+
+/// Check decisions, in the order they were stored, form an unbroken chain
+///
+/// Every proof hash must match its row, and every previous hash the proof
+/// hash of the row before. Unchained rows (no previous hash) are only
+/// accepted before the chain starts, where they are pre-chain history.
+fn verify_chain(records: &[DecisionRecord]) -> Result<()> {
+    let mut chain = ChainVerifier::default();
+    records.iter().try_for_each(|record| chain.push(record))
+}
+
+/// Checks decisions one at a time, in chain order
+#[derive(Default)]
+struct ChainVerifier {
+    prev_hash: Option<Vec<u8>>,
+    chained: bool,
+}
+
+impl ChainVerifier {
+    fn push(&mut self, record: &DecisionRecord) -> Result<()> {
+        record.verify_proof()?;
+        match (&record.proof_prev_hash, &self.prev_hash) {
+            (Some(hash), Some(prev)) if hash == prev => {}
+            (None, _) if !self.chained => {}
+            _ => {
+                return Err(Error::Crypto(format!(
+                    "decision chain broken at decision {}",
+                    record.id
+                )))
+            }
+        }
+        self.chained |= record.proof_prev_hash.is_some();
+        self.prev_hash = Some(record.proof_hash.clone());
+        Ok(())
+    }
+}
+
+/// As [`Database::verify_decision_chain`], on any connection
+async fn verify_decision_chain<'e, E>(executor: E) -> Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let mut rows = sqlx::query_as::<_, DecisionRow>(
+        r#"
+        SELECT id, order_id, decision_data, proof_hash, proof_prev_hash, hash_algorithm,
+               signature, created_at
+        FROM decisions
+        ORDER BY seq
+        "#,
+    )
+    .fetch(executor);
+
+    let mut chain = ChainVerifier::default();
+    while let Some(row) = rows.try_next().await? {
+        chain.push(&DecisionRecord::try_from(row)?)?;
+    }
+    Ok(())
+}
+
+/// Raw responses at least this many bytes are gzip-compressed when stored
+pub const RAW_RESPONSE_COMPRESSION_THRESHOLD: usize = 1024;
+
+/// gzip magic bytes; JSON text never starts with them
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Serialize a raw exchange response, gzip-compressing it if it is at
+/// least `threshold` bytes
+fn encode_raw_response(raw: &serde_json::Value, threshold: usize) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(raw)?;
+    if json.len() < threshold {
+        return Ok(json);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json).map_err(compression_error)?;
+    encoder.finish().map_err(compression_error)
+}
+
+/// Decode a stored raw response, decompressing it if it is gzip data;
+/// uncompressed (legacy) rows are plain JSON
+fn decode_raw_response(bytes: &[u8]) -> Result<serde_json::Value> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(serde_json::from_slice(bytes)?);
+    }
+    let mut json = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut json)
+        .map_err(compression_error)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+fn compression_error(e: std::io::Error) -> Error {
+    Error::Serialization(serde_json::Error::io(e))
+}
+
+/// Filter for [`Store::query_orders`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct OrderQuery {
+    pub symbol: Option<String>,
+    pub status: Option<OrderStatus>,
+    /// Only orders carrying this tag (see [`Order::tags`])
+    pub tag: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Totals over the orders matching an [`OrderQuery`]; see
+/// [`Database::order_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderStats {
+    pub count: i64,
+    /// Ordered base quantity of base-sized orders, filled or not
+    pub total_quantity: f64,
+    /// Ordered amount of quote-sized orders, filled or not; kept apart from
+    /// `total_quantity` since it is in the quote currency
+    pub total_quote_amount: f64,
+    /// Executed quantity times execution price, over every order
+    pub total_notional: f64,
+    /// Executed quantity of base-sized orders as a fraction of
+    /// `total_quantity`; 0 when no base quantity was ordered
+    pub fill_rate: f64,
+    /// Mean execution price of the orders that have one, unweighted by
+    /// quantity (`total_notional` over executed quantity gives the VWAP)
+    pub avg_execution_price: Option<f64>,
+}
+
+/// Persistence for orders and decisions
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Insert an order, or update its status and fills if already stored
+    ///
+    /// An update whose result is older than the stored row's is ignored, so
+    /// a late or replayed write cannot roll back a newer status.
+    async fn store_order(&self, order: &Order, result: &OrderResult) -> Result<()>;
+
+    /// [`Store::store_order`], but only if the order carries a valid
+    /// signature from `key` over its binary canonical encoding
+    ///
+    /// Fails with [`Error::Crypto`] without writing anything otherwise.
+    async fn verify_and_store(
+        &self,
+        order: &Order,
+        result: &OrderResult,
+        key: &VerificationKey,
+    ) -> Result<()> {
+        order.verify(key)?;
+        self.store_order(order, result).await
+    }
+
+    /// Like [`Store::store_order`], also keeping the venue's raw response
+    ///
+    /// Responses of [`RAW_RESPONSE_COMPRESSION_THRESHOLD`] bytes or more are
+    /// stored gzip-compressed.
+    async fn store_order_with_response(
+        &self,
+        order: &Order,
+        result: &OrderResult,
+        raw_response: &serde_json::Value,
+    ) -> Result<()>;
+
+    /// The raw venue response stored for an order, decompressed if needed
+    async fn get_raw_response(&self, order_id: Uuid) -> Result<Option<serde_json::Value>>;
+
+    /// Most recent orders, newest first
+    async fn get_order_history(&self, limit: i64) -> Result<Vec<OrderRecord>>;
+
+    /// Orders matching a filter, newest first
+    async fn query_orders(&self, query: &OrderQuery) -> Result<Vec<OrderRecord>>;
+
+    /// Store a signed decision, hashing its canonical form with `algorithm`
+    ///
+    /// The hash is chained to the previously stored decision's (see
+    /// [`DecisionRecord::new`]). Returns the proof hash. The algorithm and
+    /// previous hash are recorded with the row so
+    /// [`DecisionRecord::verify_proof`] recomputes it the same way.
+    async fn store_decision(
+        &self,
+        decision: &Decision,
+        algorithm: HashAlgorithm,
+    ) -> Result<Vec<u8>>;
+
+    /// A stored decision by id
+    async fn get_decision(&self, id: Uuid) -> Result<Option<DecisionRecord>>;
+}
+
 pub struct Database {
     pool: PgPool,
 }
@@ -30,18 +657,41 @@ pub struct Database {
 impl Database {
     /// Connect to the database
     pub async fn connect(database_url: &str) -> Result<Self> {
-        let pool = PgPoolOptions::new()
-            .max_connections(5)
-            .connect(database_url)
-            .await?;
+        Self::connect_options(database_url, PgPoolOptions::new()).await
+    }
+
+    async fn connect_options(database_url: &str, options: PgPoolOptions) -> Result<Self> {
+        let pool = options.max_connections(5).connect(database_url).await?;
 
         Ok(Self { pool })
     }
 
+    /// Connect, retrying transient failures with exponential backoff
+    ///
+    /// Useful at startup when the database may not be accepting connections
+    /// yet. Returns the last error if all `max_attempts` fail.
+    pub async fn connect_with_retry(
+        database_url: &str,
+        max_attempts: u32,
+        base_delay: Duration,
+    ) -> Result<Self> {
+        retry::with_backoff(max_attempts, base_delay, || {
+            Self::connect_options(
+                database_url,
+                PgPoolOptions::new().acquire_timeout(CONNECT_ATTEMPT_TIMEOUT),
+            )
+        })
+        .await
+    }
+
     /// Initialize database schema
+    ///
+    /// Each block holds several statements, so it runs as a simple
+    /// (unprepared) query.
     pub async fn initialize(&self) -> Result<()> {
-        sqlx::query(
-            r#"
+        self.pool
+            .execute(
+                r#"
             CREATE TABLE IF NOT EXISTS orders (
                 id UUID PRIMARY KEY,
                 symbol VARCHAR(50) NOT NULL,
@@ -52,96 +702,1498 @@ impl Database {
                 status VARCHAR(20) NOT NULL,
                 execution_price DOUBLE PRECISION,
                 executed_quantity DOUBLE PRECISION,
+                fee DOUBLE PRECISION,
                 signature BYTEA,
+                raw_response BYTEA,
+                book_snapshot JSONB,
+                exchange_order_id VARCHAR(100),
+                nonce BIGINT NOT NULL DEFAULT 0,
+                time_in_force VARCHAR(10) NOT NULL DEFAULT 'gtc',
+                parent_id UUID,
+                source_timestamp TIMESTAMPTZ,
+                reduce_only BOOLEAN NOT NULL DEFAULT FALSE,
+                correlation_id VARCHAR(100),
+                client_order_id VARCHAR(100),
+                tags TEXT[] NOT NULL DEFAULT '{}',
+                urgency VARCHAR(10) NOT NULL DEFAULT 'normal',
+                quote_quantity BOOLEAN NOT NULL DEFAULT FALSE,
                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
                 updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
             );
 
+            ALTER TABLE orders ADD COLUMN IF NOT EXISTS raw_response BYTEA;
+            ALTER TABLE orders ADD COLUMN IF NOT EXISTS book_snapshot JSONB;
+            ALTER TABLE orders ADD COLUMN IF NOT EXISTS exchange_order_id VARCHAR(100);
+            ALTER TABLE orders
+                ADD COLUMN IF NOT EXISTS nonce BIGINT NOT NULL DEFAULT 0,
+                ADD COLUMN IF NOT EXISTS time_in_force VARCHAR(10) NOT NULL DEFAULT 'gtc',
+                ADD COLUMN IF NOT EXISTS parent_id UUID,
+                ADD COLUMN IF NOT EXISTS source_timestamp TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS reduce_only BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS correlation_id VARCHAR(100),
+                ADD COLUMN IF NOT EXISTS client_order_id VARCHAR(100),
+                ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}',
+                ADD COLUMN IF NOT EXISTS urgency VARCHAR(10) NOT NULL DEFAULT 'normal',
+                ADD COLUMN IF NOT EXISTS quote_quantity BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS fee DOUBLE PRECISION;
+
             CREATE INDEX IF NOT EXISTS idx_orders_symbol ON orders(symbol);
             CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
             CREATE INDEX IF NOT EXISTS idx_orders_created_at ON orders(created_at);
+            CREATE INDEX IF NOT EXISTS idx_orders_created_at_id ON orders(created_at, id);
+            CREATE INDEX IF NOT EXISTS idx_orders_open ON orders(created_at)
+                WHERE status IN ('pending', 'partially_filled');
+            CREATE INDEX IF NOT EXISTS idx_orders_correlation_id
+                ON orders(correlation_id) WHERE correlation_id IS NOT NULL;
+            CREATE INDEX IF NOT EXISTS idx_orders_client_order_id
+                ON orders(client_order_id) WHERE client_order_id IS NOT NULL;
+            CREATE INDEX IF NOT EXISTS idx_orders_tags ON orders USING GIN (tags);
             "#,
-        )
-        .execute(&self.pool)
-        .await?;
+            )
+            .await?;
+        self.pool.execute(UNIQUE_EXCHANGE_ORDER_ID).await?;
 
-        sqlx::query(
-            r#"
+        self.pool
+            .execute(
+                r#"
             CREATE TABLE IF NOT EXISTS decisions (
                 id UUID PRIMARY KEY,
                 order_id UUID REFERENCES orders(id),
                 decision_data JSONB NOT NULL,
                 proof_hash BYTEA NOT NULL,
+                proof_prev_hash BYTEA,
+                hash_algorithm VARCHAR(10) NOT NULL DEFAULT 'sha256',
                 signature BYTEA NOT NULL,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                seq BIGSERIAL
             );
 
+            ALTER TABLE decisions
+                ADD COLUMN IF NOT EXISTS hash_algorithm VARCHAR(10) NOT NULL DEFAULT 'sha256',
+                ADD COLUMN IF NOT EXISTS proof_prev_hash BYTEA;
+
             CREATE INDEX IF NOT EXISTS idx_decisions_order_id ON decisions(order_id);
             "#,
-        )
-        .execute(&self.pool)
-        .await?;
+            )
+            .await?;
+        self.pool.execute(DECISIONS_SEQ).await?;
+        self.pool
+            .execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_decisions_seq ON decisions(seq)")
+            .await?;
 
         Ok(())
     }
 
-    /// Store an order result
+    /// Orders not yet in a terminal state, oldest first
+    ///
+    /// Served from the `idx_orders_open` partial index, so the cost tracks
+    /// the number of open orders rather than the size of the history.
+    pub async fn open_orders(&self) -> Result<Vec<OrderRecord>> {
+        let rows =
+            sqlx::query_as::<_, OrderRow>(&format!("{}{}", SELECT_ORDERS, OPEN_ORDERS_FILTER))
+                .fetch_all(&self.pool)
+                .await?;
+
+        rows.into_iter().map(OrderRecord::try_from).collect()
+    }
+
+    /// One page of the order history, newest first, starting after `cursor`
     ///
-    /// Note: This is a simplified implementation. In production, you would need to either:
-    /// 1. Add order details (symbol, side, type, quantity) to OrderResult, or
-    /// 2. Pass both the original Order and OrderResult to this function
-    pub async fn store_order(&self, result: &OrderResult) -> Result<()> {
-        let status_str = match result.status {
-            OrderStatus::Pending => "pending",
-            OrderStatus::Executed => "executed",
-            OrderStatus::Failed => "failed",
-            OrderStatus::Cancelled => "cancelled",
-        };
-
-        // TODO: Currently using placeholder values for order details
-        // In production, pass the complete order information
+    /// Pages by `(created_at, id)` rather than `OFFSET`, so each page is an
+    /// index range scan on `idx_orders_created_at_id` however deep it is,
+    /// and orders sharing a timestamp are neither skipped nor repeated at
+    /// page boundaries. Pass `None` for the first page and then the returned
+    /// cursor; it is `None` once the history is exhausted.
+    pub async fn orders_after(
+        &self,
+        cursor: Option<Cursor>,
+        limit: i64,
+    ) -> Result<(Vec<OrderRecord>, Option<Cursor>)> {
+        let mut builder = QueryBuilder::<Postgres>::new(SELECT_ORDERS);
+        if let Some((created_at, id)) = cursor {
+            builder
+                .push(" WHERE (created_at, id) < (")
+                .push_bind(created_at)
+                .push(", ")
+                .push_bind(id)
+                .push(")");
+        }
+        builder
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let records = builder
+            .build_query_as::<OrderRow>()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(OrderRecord::try_from)
+            .collect::<Result<Vec<_>>>()?;
+
+        // A short page means there is nothing after it
+        let next = match records.last() {
+            Some(last) if records.len() as i64 == limit => Some((last.created_at, last.id)),
+            _ => None,
+        };
+        Ok((records, next))
+    }
+
+    /// Walk every stored decision in order, checking each proof hash and
+    /// its link to the decision before
+    ///
+    /// Fails with [`Error::Crypto`] naming the first decision whose data no
+    /// longer matches its hash or whose previous hash does not match, as
+    /// when a row is edited, removed or inserted out of band. Rows are
+    /// streamed, so memory use does not grow with the chain.
+    pub async fn verify_decision_chain(&self) -> Result<()> {
+        verify_decision_chain(&self.pool).await
+    }
+
+    /// Aggregate statistics over the orders matching `query`
+    ///
+    /// Computed in SQL, so no rows are fetched. `query.limit` is ignored.
+    pub async fn order_stats(&self, query: &OrderQuery) -> Result<OrderStats> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT COUNT(*),
+                   COALESCE(SUM(quantity) FILTER (WHERE NOT quote_quantity), 0),
+                   COALESCE(SUM(quantity) FILTER (WHERE quote_quantity), 0),
+                   COALESCE(SUM(executed_quantity * execution_price), 0),
+                   COALESCE(SUM(executed_quantity) FILTER (WHERE NOT quote_quantity), 0),
+                   AVG(execution_price)
+            FROM orders
+            WHERE TRUE
+            "#,
+        );
+        push_filters(&mut builder, query);
+
+        let (
+            count,
+            total_quantity,
+            total_quote_amount,
+            total_notional,
+            executed_quantity,
+            avg_execution_price,
+        ): (i64, f64, f64, f64, f64, Option<f64>) =
+            builder.build_query_as().fetch_one(&self.pool).await?;
+
+        let fill_rate = if total_quantity > 0.0 {
+            executed_quantity / total_quantity
+        } else {
+            0.0
+        };
+        Ok(OrderStats {
+            count,
+            total_quantity,
+            total_quote_amount,
+            total_notional,
+            fill_rate,
+            avg_execution_price,
+        })
+    }
+
+    /// The order the venue knows as `exchange_order_id`
+    ///
+    /// Backed by the unique `idx_orders_exchange_order_id` index, so at most
+    /// one order matches. When [`initialize`](Self::initialize) creates the
+    /// index on an existing table, ids shared by several legacy rows are
+    /// kept only on the most recently updated one.
+    pub async fn get_order_by_exchange_id(
+        &self,
+        exchange_order_id: &str,
+    ) -> Result<Option<OrderRecord>> {
+        let row = sqlx::query_as::<_, OrderRow>(&format!(
+            "{} WHERE exchange_order_id = $1",
+            SELECT_ORDERS
+        ))
+        .bind(exchange_order_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(OrderRecord::try_from).transpose()
+    }
+
+    /// Re-insert orders written to a dead-letter file by
+    /// [`FileDeadLetterSink`](crate::FileDeadLetterSink)
+    ///
+    /// Inserts are upserts that never overwrite a newer result (see
+    /// [`Store::store_order`]), so replaying the same file twice, or after
+    /// the orders moved on, is harmless; the file is left in place for the
+    /// caller to remove once satisfied.
+    /// Returns the number of orders replayed.
+    pub async fn replay_dead_letters(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let letters = dead_letter::read_dead_letters(path).await?;
+        for letter in &letters {
+            self.store_order(&letter.order, &letter.result).await?;
+        }
+        Ok(letters.len())
+    }
+}
+
+#[async_trait]
+impl Store for Database {
+    async fn store_order(&self, order: &Order, result: &OrderResult) -> Result<()> {
+        let record = OrderRecord::new(order, result);
+        let signature = record.signature.as_ref().map(encode_signature);
+
         sqlx::query(
             r#"
-            INSERT INTO orders (id, symbol, side, order_type, quantity, status, execution_price, executed_quantity, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO orders (id, symbol, side, order_type, quantity, price, status, execution_price, executed_quantity, fee, signature, book_snapshot, exchange_order_id, nonce, time_in_force, parent_id, source_timestamp, reduce_only, correlation_id, client_order_id, tags, urgency, quote_quantity, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
             ON CONFLICT (id) DO UPDATE SET
                 status = EXCLUDED.status,
                 execution_price = EXCLUDED.execution_price,
                 executed_quantity = EXCLUDED.executed_quantity,
+                fee = EXCLUDED.fee,
+                signature = COALESCE(EXCLUDED.signature, orders.signature),
+                book_snapshot = COALESCE(EXCLUDED.book_snapshot, orders.book_snapshot),
+                exchange_order_id = COALESCE(EXCLUDED.exchange_order_id, orders.exchange_order_id),
                 updated_at = EXCLUDED.updated_at
+            WHERE orders.updated_at <= EXCLUDED.updated_at
             "#
         )
-        .bind(result.order_id)
-        .bind("PLACEHOLDER") // symbol - should come from Order
-        .bind("PLACEHOLDER") // side - should come from Order
-        .bind("PLACEHOLDER") // order_type - should come from Order
-        .bind(0.0) // quantity - should come from Order
-        .bind(status_str)
-        .bind(result.execution_price)
-        .bind(result.executed_quantity)
-        .bind(result.timestamp)
-        .bind(result.timestamp)
+        .bind(record.id)
+        .bind(&record.symbol)
+        .bind(&record.side)
+        .bind(&record.order_type)
+        .bind(record.quantity)
+        .bind(record.price)
+        .bind(&record.status)
+        .bind(record.execution_price)
+        .bind(record.executed_quantity)
+        .bind(record.fee)
+        .bind(signature)
+        .bind(record.book_snapshot.as_ref().map(sqlx::types::Json))
+        .bind(&record.exchange_order_id)
+        // Postgres has no unsigned BIGINT; store the nonce's bits
+        .bind(record.nonce as i64)
+        .bind(&record.time_in_force)
+        .bind(record.parent_id)
+        .bind(record.source_timestamp)
+        .bind(record.reduce_only)
+        .bind(&record.correlation_id)
+        .bind(&record.client_order_id)
+        .bind(&record.tags)
+        .bind(&record.urgency)
+        .bind(record.quote_quantity)
+        .bind(record.created_at)
+        .bind(record.updated_at)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// Get order history
-    pub async fn get_order_history(&self, limit: i64) -> Result<Vec<OrderRecord>> {
-        let records = sqlx::query_as::<_, OrderRecord>(
+    async fn store_order_with_response(
+        &self,
+        order: &Order,
+        result: &OrderResult,
+        raw_response: &serde_json::Value,
+    ) -> Result<()> {
+        let raw = encode_raw_response(raw_response, RAW_RESPONSE_COMPRESSION_THRESHOLD)?;
+        self.store_order(order, result).await?;
+
+        sqlx::query("UPDATE orders SET raw_response = $2 WHERE id = $1")
+            .bind(order.id)
+            .bind(raw)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_raw_response(&self, order_id: Uuid) -> Result<Option<serde_json::Value>> {
+        let raw: Option<Option<Vec<u8>>> =
+            sqlx::query_scalar("SELECT raw_response FROM orders WHERE id = $1")
+                .bind(order_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        raw.flatten()
+            .map(|bytes| decode_raw_response(&bytes))
+            .transpose()
+    }
+
+    async fn get_order_history(&self, limit: i64) -> Result<Vec<OrderRecord>> {
+        let rows = sqlx::query_as::<_, OrderRow>(&format!(
+            "{} ORDER BY created_at DESC LIMIT $1",
+            SELECT_ORDERS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(OrderRecord::try_from).collect()
+    }
+
+    async fn store_decision(
+        &self,
+        decision: &Decision,
+        algorithm: HashAlgorithm,
+    ) -> Result<Vec<u8>> {
+        // Writers queue on the lock so two decisions cannot both link to
+        // the same predecessor; readers are not blocked
+        let mut tx = self.pool.begin().await?;
+        tx.execute("LOCK TABLE decisions IN EXCLUSIVE MODE").await?;
+        let prev_hash: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT proof_hash FROM decisions ORDER BY seq DESC LIMIT 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let record = DecisionRecord::new(decision, algorithm, prev_hash)?;
+        let signature = encode_signature(&record.signature);
+
+        sqlx::query(
             r#"
-            SELECT id, symbol, side, order_type, quantity, price, status,
-                   execution_price, executed_quantity, created_at, updated_at
-            FROM orders
-            ORDER BY created_at DESC
-            LIMIT $1
+            INSERT INTO decisions (id, order_id, decision_data, proof_hash, proof_prev_hash, hash_algorithm, signature, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
         )
-        .bind(limit)
-        .fetch_all(&self.pool)
+        .bind(record.id)
+        .bind(record.order_id)
+        .bind(&record.decision_data)
+        .bind(&record.proof_hash)
+        .bind(&record.proof_prev_hash)
+        .bind(&record.hash_algorithm)
+        .bind(signature)
+        .bind(record.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(record.proof_hash)
+    }
+
+    async fn get_decision(&self, id: Uuid) -> Result<Option<DecisionRecord>> {
+        let row = sqlx::query_as::<_, DecisionRow>(
+            r#"
+            SELECT id, order_id, decision_data, proof_hash, proof_prev_hash, hash_algorithm,
+                   signature, created_at
+            FROM decisions
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
         .await?;
 
+        row.map(DecisionRecord::try_from).transpose()
+    }
+
+    async fn query_orders(&self, query: &OrderQuery) -> Result<Vec<OrderRecord>> {
+        let mut builder = QueryBuilder::<Postgres>::new(SELECT_ORDERS);
+        builder.push(" WHERE TRUE");
+        push_filters(&mut builder, query);
+        builder.push(" ORDER BY created_at DESC");
+        if let Some(limit) = query.limit {
+            builder.push(" LIMIT ").push_bind(limit);
+        }
+
+        let rows = builder
+            .build_query_as::<OrderRow>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter().map(OrderRecord::try_from).collect()
+    }
+}
+
+/// Append `AND` clauses for the set fields of `query` (other than `limit`)
+/// to a query ending in a `WHERE` clause
+fn push_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &OrderQuery) {
+    if let Some(symbol) = &query.symbol {
+        builder.push(" AND symbol = ").push_bind(symbol.clone());
+    }
+    if let Some(status) = query.status {
+        builder.push(" AND status = ").push_bind(status.as_str());
+    }
+    if let Some(tag) = &query.tag {
+        // Containment rather than `= ANY(tags)`, so `idx_orders_tags` applies
+        builder
+            .push(" AND tags @> ARRAY[")
+            .push_bind(tag.clone())
+            .push("]::TEXT[]");
+    }
+}
+
+/// [`Store`] kept in process memory, for tests and local development
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    orders: Mutex<Vec<OrderRecord>>,
+    raw_responses: Mutex<HashMap<Uuid, Vec<u8>>>,
+    decisions: Mutex<Vec<DecisionRecord>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// As [`Database::verify_decision_chain`]
+    pub fn verify_decision_chain(&self) -> Result<()> {
+        verify_chain(&self.decisions.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl Store for InMemoryStore {
+    async fn store_order(&self, order: &Order, result: &OrderResult) -> Result<()> {
+        let record = OrderRecord::new(order, result);
+        let mut orders = self.orders.lock().unwrap();
+        match orders.iter_mut().find(|r| r.id == record.id) {
+            Some(existing) if existing.updated_at > record.updated_at => {}
+            Some(existing) => {
+                existing.status = record.status;
+                existing.execution_price = record.execution_price;
+                existing.executed_quantity = record.executed_quantity;
+                existing.fee = record.fee;
+                existing.updated_at = record.updated_at;
+            }
+            None => orders.push(record),
+        }
+        Ok(())
+    }
+
+    async fn store_order_with_response(
+        &self,
+        order: &Order,
+        result: &OrderResult,
+        raw_response: &serde_json::Value,
+    ) -> Result<()> {
+        let raw = encode_raw_response(raw_response, RAW_RESPONSE_COMPRESSION_THRESHOLD)?;
+        self.store_order(order, result).await?;
+        self.raw_responses.lock().unwrap().insert(order.id, raw);
+        Ok(())
+    }
+
+    async fn get_raw_response(&self, order_id: Uuid) -> Result<Option<serde_json::Value>> {
+        self.raw_responses
+            .lock()
+            .unwrap()
+            .get(&order_id)
+            .map(|bytes| decode_raw_response(bytes))
+            .transpose()
+    }
+
+    async fn get_order_history(&self, limit: i64) -> Result<Vec<OrderRecord>> {
+        self.query_orders(&OrderQuery {
+            limit: Some(limit),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn query_orders(&self, query: &OrderQuery) -> Result<Vec<OrderRecord>> {
+        let mut records: Vec<OrderRecord> = self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.matches(query))
+            .cloned()
+            .collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        if let Some(limit) = query.limit {
+            records.truncate(limit.max(0) as usize);
+        }
         Ok(records)
     }
+
+    async fn store_decision(
+        &self,
+        decision: &Decision,
+        algorithm: HashAlgorithm,
+    ) -> Result<Vec<u8>> {
+        let mut decisions = self.decisions.lock().unwrap();
+        let prev_hash = decisions.last().map(|prev| prev.proof_hash.clone());
+        let record = DecisionRecord::new(decision, algorithm, prev_hash)?;
+        let proof_hash = record.proof_hash.clone();
+        decisions.push(record);
+        Ok(proof_hash)
+    }
+
+    async fn get_decision(&self, id: Uuid) -> Result<Option<DecisionRecord>> {
+        Ok(self
+            .decisions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id == id)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution::OrderQuantity, SigningKey};
+
+    fn executed(order: &Order) -> OrderResult {
+        OrderResult {
+            order_id: order.id,
+            status: OrderStatus::Executed,
+            execution_price: order.order_type.limit_price(),
+            executed_quantity: Some(order.quantity),
+            timestamp: Utc::now(),
+            message: None,
+            timings: None,
+            book_snapshot: None,
+            exchange_order_id: None,
+            fee: None,
+        }
+    }
+
+    /// Connect to the database named by `DATABASE_URL`, or skip the test
+    async fn test_db() -> Option<Database> {
+        let url = std::env::var("DATABASE_URL").ok()?;
+        let db = Database::connect(&url).await.unwrap();
+        db.initialize().await.unwrap();
+        Some(db)
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_orders() {
+        let store = InMemoryStore::new();
+        let btc = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit { price: 50000.0 },
+            0.1,
+        );
+        let mut eth = Order::new(
+            "ETH/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Market,
+            2.0,
+        );
+        eth.timestamp = btc.timestamp + chrono::Duration::seconds(1);
+
+        let pending = OrderResult {
+            status: OrderStatus::Pending,
+            executed_quantity: None,
+            ..executed(&btc)
+        };
+        store.store_order(&btc, &pending).await.unwrap();
+        store.store_order(&eth, &executed(&eth)).await.unwrap();
+        // Storing again updates the existing row
+        store.store_order(&btc, &executed(&btc)).await.unwrap();
+
+        let history = store.get_order_history(10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, eth.id);
+        assert_eq!(history[1].status, "executed");
+        assert_eq!(history[1].price, Some(50000.0));
+        assert_eq!(store.get_order_history(1).await.unwrap().len(), 1);
+
+        let query = OrderQuery {
+            symbol: Some("BTC/USD".to_string()),
+            ..Default::default()
+        };
+        let btc_orders = store.query_orders(&query).await.unwrap();
+        assert_eq!(btc_orders.len(), 1);
+        assert_eq!(btc_orders[0].side, "buy");
+    }
+
+    #[test]
+    fn test_order_from_record() {
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Limit { price: 50000.0 },
+            0.5,
+        );
+        order.time_in_force = TimeInForce::ImmediateOrCancel;
+        order.reduce_only = true;
+        order.parent_id = Some(Uuid::new_v4());
+        order.correlation_id = Some("request-1".to_string());
+        order.tags = vec!["momentum-v2".to_string()];
+        order.urgency = Urgency::Urgent;
+        order.quote_quantity = true;
+        order.sign(&key).unwrap();
+
+        let record = OrderRecord::new(&order, &executed(&order));
+        let rebuilt = Order::try_from(record.clone()).unwrap();
+        assert_eq!(rebuilt, order);
+        rebuilt.verify(&key.verification_key()).unwrap();
+
+        let malformed = [
+            OrderRecord {
+                side: "hold".to_string(),
+                ..record.clone()
+            },
+            OrderRecord {
+                order_type: "stop".to_string(),
+                ..record.clone()
+            },
+            // A limit order must carry its price
+            OrderRecord {
+                price: None,
+                ..record.clone()
+            },
+            OrderRecord {
+                time_in_force: "day".to_string(),
+                ..record
+            },
+        ];
+        for record in malformed {
+            let err = Order::try_from(record).unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Execution(ExecutionError::Validation(_))
+            ));
+        }
+    }
+
+    /// Checks `verify_and_store` against any store; `symbol` keeps rows
+    /// from other tests out of the query
+    async fn assert_only_verified_orders_stored(store: &dyn Store, symbol: &str) {
+        let key = SigningKey::generate();
+        let order = || Order::new(symbol.to_string(), OrderSide::Buy, OrderType::Market, 0.1);
+
+        let mut signed = order();
+        signed.sign(&key).unwrap();
+        store
+            .verify_and_store(&signed, &executed(&signed), &key.verification_key())
+            .await
+            .unwrap();
+
+        let unsigned = order();
+        let mut forged = order();
+        forged.sign(&SigningKey::generate()).unwrap();
+        for bad in [&unsigned, &forged] {
+            let err = store
+                .verify_and_store(bad, &executed(bad), &key.verification_key())
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::Crypto(_)));
+        }
+
+        let query = OrderQuery {
+            symbol: Some(symbol.to_string()),
+            ..Default::default()
+        };
+        let ids: Vec<_> = store
+            .query_orders(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert!(ids.contains(&signed.id));
+        assert!(!ids.contains(&unsigned.id));
+        assert!(!ids.contains(&forged.id));
+    }
+
+    #[tokio::test]
+    async fn test_verify_and_store() {
+        assert_only_verified_orders_stored(&InMemoryStore::new(), "BTC/USD").await;
+        if let Some(db) = test_db().await {
+            assert_only_verified_orders_stored(&db, "VERIFY/USD").await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_decisions() {
+        let store = InMemoryStore::new();
+        let mut decision = Decision::new(Uuid::new_v4(), serde_json::json!({"action": "buy"}));
+        assert!(store
+            .store_decision(&decision, HashAlgorithm::Blake3)
+            .await
+            .is_err());
+
+        decision.sign(&SigningKey::generate());
+        let proof_hash = store
+            .store_decision(&decision, HashAlgorithm::Blake3)
+            .await
+            .unwrap();
+        let record = store
+            .get_decision(decision.decision_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.proof_hash, proof_hash);
+        record.verify_proof().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_detects_tampering() {
+        let key = SigningKey::generate();
+        let decide = |action: &str| {
+            let mut decision =
+                Decision::new(Uuid::new_v4(), serde_json::json!({ "action": action }));
+            decision.sign(&key);
+            decision
+        };
+
+        let store = InMemoryStore::new();
+        let mut hashes = Vec::new();
+        for action in ["buy", "hold", "sell"] {
+            let hash = store
+                .store_decision(&decide(action), HashAlgorithm::Sha256)
+                .await
+                .unwrap();
+            hashes.push(hash);
+        }
+        store.verify_decision_chain().unwrap();
+        {
+            let decisions = store.decisions.lock().unwrap();
+            assert_eq!(decisions[0].proof_prev_hash, None);
+            assert_eq!(decisions[1].proof_prev_hash.as_ref(), Some(&hashes[0]));
+            assert_eq!(decisions[2].proof_prev_hash.as_ref(), Some(&hashes[1]));
+        }
+
+        // Editing the data breaks the row's own hash
+        store.decisions.lock().unwrap()[1].decision_data = serde_json::json!({"action": "buy"});
+        assert!(store.verify_decision_chain().is_err());
+
+        // Re-hashing the edited row instead breaks the next row's link
+        {
+            let mut decisions = store.decisions.lock().unwrap();
+            let edited = &mut decisions[1];
+            let bytes = decision::canonical_bytes(
+                edited.id,
+                edited.order_id.unwrap(),
+                &edited.decision_data,
+            );
+            edited.proof_hash =
+                chained_hash(HashAlgorithm::Sha256, Some(hashes[0].as_slice()), &bytes);
+        }
+        let err = store.verify_decision_chain().unwrap_err();
+        assert!(err.to_string().contains("chain broken"));
+
+        // As does dropping a row
+        let store = InMemoryStore::new();
+        for action in ["buy", "hold", "sell"] {
+            store
+                .store_decision(&decide(action), HashAlgorithm::Blake3)
+                .await
+                .unwrap();
+        }
+        store.decisions.lock().unwrap().remove(1);
+        assert!(store.verify_decision_chain().is_err());
+    }
+
+    #[test]
+    fn test_raw_response_compression_roundtrip() {
+        let small = serde_json::json!({"status": "filled"});
+        let encoded = encode_raw_response(&small, 1024).unwrap();
+        assert!(!encoded.starts_with(&GZIP_MAGIC));
+        assert_eq!(decode_raw_response(&encoded).unwrap(), small);
+
+        let fills: Vec<_> = (0..200)
+            .map(|i| serde_json::json!({"trade_id": i, "price": 50000.0, "qty": 0.001}))
+            .collect();
+        let large = serde_json::json!({ "fills": fills });
+        let plain = serde_json::to_vec(&large).unwrap();
+        let encoded = encode_raw_response(&large, 1024).unwrap();
+        assert!(encoded.starts_with(&GZIP_MAGIC));
+        assert!(encoded.len() < plain.len());
+        assert_eq!(decode_raw_response(&encoded).unwrap(), large);
+
+        // Rows written before compression hold plain JSON of any size
+        assert_eq!(decode_raw_response(&plain).unwrap(), large);
+    }
+
+    #[tokio::test]
+    async fn test_store_order_with_response() {
+        let store = InMemoryStore::new();
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        assert!(store.get_raw_response(order.id).await.unwrap().is_none());
+
+        let raw = serde_json::json!({"id": "venue-123", "status": "filled"});
+        store
+            .store_order_with_response(&order, &executed(&order), &raw)
+            .await
+            .unwrap();
+        assert_eq!(store.get_raw_response(order.id).await.unwrap(), Some(raw));
+        assert_eq!(store.get_order_history(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_stored_signature_is_crypto_error() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        order.sign(&SigningKey::generate()).unwrap();
+        db.store_order(&order, &executed(&order)).await.unwrap();
+
+        let query = OrderQuery {
+            symbol: Some("BTC/USD".to_string()),
+            ..Default::default()
+        };
+        let stored = db.query_orders(&query).await.unwrap();
+        let record = stored.iter().find(|r| r.id == order.id).unwrap();
+        assert_eq!(record.signature, order.signature);
+
+        // Corrupt the row only inside a transaction that is rolled back, so
+        // concurrent tests never read it
+        let mut tx = db.pool.begin().await.unwrap();
+        sqlx::query("UPDATE orders SET signature = $2 WHERE id = $1")
+            .bind(order.id)
+            .bind(vec![0u8; 63])
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let row: OrderRow = sqlx::query_as("SELECT * FROM orders WHERE id = $1")
+            .bind(order.id)
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+        tx.rollback().await.unwrap();
+        let err = OrderRecord::try_from(row).unwrap_err();
+        assert!(matches!(err, Error::Crypto(_)));
+        assert!(err.to_string().contains("63 bytes"));
+    }
+
+    #[test]
+    fn test_signature_column_roundtrip() {
+        let signature = SigningKey::generate().sign(b"order");
+        let bytes = encode_signature(&signature);
+        assert_eq!(bytes.len(), SIGNATURE_LENGTH);
+        let id = Uuid::new_v4();
+        assert_eq!(decode_signature(&bytes, "order", id).unwrap(), signature);
+
+        let err = decode_signature(&bytes[..63], "order", id).unwrap_err();
+        assert!(matches!(err, Error::Crypto(_)));
+        assert!(err.to_string().contains("63 bytes"));
+    }
+
+    #[tokio::test]
+    async fn test_database_raw_responses() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            0.1,
+        );
+        let raw = serde_json::json!({ "padding": "x".repeat(4096) });
+        db.store_order_with_response(&order, &executed(&order), &raw)
+            .await
+            .unwrap();
+        assert_eq!(db.get_raw_response(order.id).await.unwrap(), Some(raw));
+
+        // A legacy row holding uncompressed JSON
+        let legacy = serde_json::json!({ "status": "filled" });
+        sqlx::query("UPDATE orders SET raw_response = $2 WHERE id = $1")
+            .bind(order.id)
+            .bind(serde_json::to_vec(&legacy).unwrap())
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(db.get_raw_response(order.id).await.unwrap(), Some(legacy));
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_retry_returns_last_error() {
+        let result = Database::connect_with_retry(
+            "postgres://test@127.0.0.1:1/none",
+            2,
+            Duration::from_millis(1),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Database(_))));
+    }
+
+    #[tokio::test]
+    async fn test_book_snapshot_persisted() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let order = Order::new(
+            "BOOK/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let book = OrderBook::new("BOOK/USD", vec![(9.0, 1.0)], vec![(10.0, 2.0)], Utc::now());
+        let result = OrderResult {
+            book_snapshot: Some(book.clone()),
+            ..executed(&order)
+        };
+        db.store_order(&order, &result).await.unwrap();
+
+        // A later update without a book keeps the stored one
+        db.store_order(&order, &executed(&order)).await.unwrap();
+
+        let stored = db
+            .query_orders(&OrderQuery {
+                symbol: Some("BOOK/USD".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|r| r.id == order.id)
+            .unwrap();
+        let stored_book = stored.book_snapshot.unwrap();
+        assert_eq!(stored_book.asks, book.asks);
+        assert_eq!(stored_book.bids, book.bids);
+    }
+
+    #[tokio::test]
+    async fn test_stored_order_reverifies() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let key = SigningKey::generate();
+        let mut order = Order::new(
+            "REPLAY/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        order.time_in_force = TimeInForce::FillOrKill;
+        order.correlation_id = Some(format!("request-{}", order.id));
+        order.client_order_id = Some(format!("tw-{}", order.id.simple()));
+        order.sign(&key).unwrap();
+        db.store_order(&order, &executed(&order)).await.unwrap();
+
+        let query = OrderQuery {
+            symbol: Some("REPLAY/USD".to_string()),
+            ..Default::default()
+        };
+        let record = db
+            .query_orders(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|record| record.id == order.id)
+            .unwrap();
+        assert_eq!(record.correlation_id, order.correlation_id);
+        assert_eq!(record.client_order_id, order.client_order_id);
+        let rebuilt = Order::try_from(record).unwrap();
+        assert_eq!(rebuilt.nonce, order.nonce);
+        assert_eq!(rebuilt.time_in_force, TimeInForce::FillOrKill);
+        rebuilt.verify(&key.verification_key()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_order_by_exchange_id() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let order = Order::new(
+            "VENUE/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        let venue_id = format!("venue-{}", Uuid::new_v4());
+        let result = OrderResult {
+            exchange_order_id: Some(venue_id.clone()),
+            fee: Some(-0.25),
+            ..executed(&order)
+        };
+        db.store_order(&order, &result).await.unwrap();
+
+        let found = db
+            .get_order_by_exchange_id(&venue_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, order.id);
+        assert_eq!(found.exchange_order_id.as_deref(), Some(venue_id.as_str()));
+        assert_eq!(found.fee, Some(-0.25));
+        assert!(db
+            .get_order_by_exchange_id("no-such-id")
+            .await
+            .unwrap()
+            .is_none());
+
+        // The unique index refuses a second order with the same venue id
+        let other = Order::new(
+            "VENUE/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Market,
+            1.0,
+        );
+        let duplicate = OrderResult {
+            exchange_order_id: Some(venue_id),
+            ..executed(&other)
+        };
+        assert!(db.store_order(&other, &duplicate).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unique_exchange_order_id_dedupes_legacy_rows() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        // A pre-index table in a throwaway schema, dropped with the rollback
+        let mut tx = db.pool.begin().await.unwrap();
+        let schema = format!("legacy_{}", Uuid::new_v4().simple());
+        for statement in [
+            format!("CREATE SCHEMA {}", schema),
+            format!("SET LOCAL search_path = {}", schema),
+            "CREATE TABLE orders (LIKE public.orders INCLUDING DEFAULTS)".to_string(),
+        ] {
+            sqlx::query(&statement).execute(&mut *tx).await.unwrap();
+        }
+        let (older, newer, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for (id, venue_id, age) in [(older, "dup", 2), (newer, "dup", 1), (other, "solo", 0)] {
+            sqlx::query(
+                "INSERT INTO orders (id, symbol, side, order_type, quantity, status, \
+                 exchange_order_id, updated_at) \
+                 VALUES ($1, 'BTC/USD', 'buy', 'market', 1.0, 'executed', $2, \
+                 NOW() - make_interval(secs => $3))",
+            )
+            .bind(id)
+            .bind(venue_id)
+            .bind(age as f64)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+
+        sqlx::query(UNIQUE_EXCHANGE_ORDER_ID)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let rows: Vec<(Uuid, Option<String>)> =
+            sqlx::query_as("SELECT id, exchange_order_id FROM orders")
+                .fetch_all(&mut *tx)
+                .await
+                .unwrap();
+        let venue_ids: HashMap<_, _> = rows.into_iter().collect();
+        assert_eq!(venue_ids[&older], None);
+        assert_eq!(venue_ids[&newer].as_deref(), Some("dup"));
+        assert_eq!(venue_ids[&other].as_deref(), Some("solo"));
+
+        // Running it again is a no-op
+        sqlx::query(UNIQUE_EXCHANGE_ORDER_ID)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        tx.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_open_orders_use_partial_index() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let order = |quantity| {
+            Order::new(
+                "OPEN/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 10.0 },
+                quantity,
+            )
+        };
+        let pending = order(1.0);
+        let open = OrderResult {
+            status: OrderStatus::Pending,
+            executed_quantity: None,
+            ..executed(&pending)
+        };
+        db.store_order(&pending, &open).await.unwrap();
+        let filled = order(2.0);
+        db.store_order(&filled, &executed(&filled)).await.unwrap();
+
+        let ids: Vec<_> = db
+            .open_orders()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert!(ids.contains(&pending.id));
+        assert!(!ids.contains(&filled.id));
+
+        // Tiny test tables favour a sequential scan, so rule it out to see
+        // that the planner can answer the query from the partial index
+        let mut conn = db.pool.acquire().await.unwrap();
+        conn.execute("SET enable_seqscan = off").await.unwrap();
+        let plan: Vec<(String,)> =
+            sqlx::query_as(&format!("EXPLAIN {}{}", SELECT_ORDERS, OPEN_ORDERS_FILTER))
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap();
+        conn.execute("RESET enable_seqscan").await.unwrap();
+        assert!(plan.iter().any(|(line,)| line.contains("idx_orders_open")));
+
+        for id in [pending.id, filled.id] {
+            sqlx::query("DELETE FROM orders WHERE id = $1")
+                .bind(id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orders_after_pages_without_gaps() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        // Most orders share one timestamp, so page boundaries fall between
+        // orders ordered only by id
+        let shared = DateTime::parse_from_rfc3339("2001-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let seeded: Vec<Order> = (0..9)
+            .map(|i| {
+                let at = if i < 7 {
+                    shared
+                } else {
+                    shared - chrono::Duration::seconds(i)
+                };
+                Order::new_at(
+                    "PAGE/USD".to_string(),
+                    OrderSide::Buy,
+                    OrderType::Market,
+                    1.0,
+                    at,
+                )
+            })
+            .collect();
+        for order in &seeded {
+            db.store_order(order, &executed(order)).await.unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next) = db.orders_after(cursor, 4).await.unwrap();
+            assert!(page.len() <= 4);
+            seen.extend(page.iter().map(|r| (r.created_at, r.id)));
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        // Newest first, strictly, so nothing repeats
+        assert!(seen.windows(2).all(|pair| pair[0] > pair[1]));
+        for order in &seeded {
+            assert_eq!(seen.iter().filter(|(_, id)| *id == order.id).count(), 1);
+        }
+
+        for order in &seeded {
+            sqlx::query("DELETE FROM orders WHERE id = $1")
+                .bind(order.id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_orders_by_tag() {
+        let in_memory = InMemoryStore::new();
+        let db = test_db().await;
+        let mut stores: Vec<&dyn Store> = vec![&in_memory];
+        if let Some(db) = &db {
+            stores.push(db);
+        }
+
+        // Tagged per run so the shared test database holds no other matches
+        let strategy = format!("momentum-v2-{}", Uuid::new_v4());
+        let order = |tags: Vec<String>| Order {
+            tags,
+            ..Order::new(
+                "TAG/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                1.0,
+            )
+        };
+        let tagged = order(vec!["live".to_string(), strategy.clone()]);
+        let other = order(vec!["live".to_string()]);
+        let untagged = order(Vec::new());
+
+        for store in stores {
+            for order in [&tagged, &other, &untagged] {
+                store.store_order(order, &executed(order)).await.unwrap();
+            }
+            let query = OrderQuery {
+                tag: Some(strategy.clone()),
+                ..Default::default()
+            };
+            let found = store.query_orders(&query).await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, tagged.id);
+            assert_eq!(found[0].tags, tagged.tags);
+        }
+
+        if let Some(db) = &db {
+            for order in [&tagged, &other, &untagged] {
+                sqlx::query("DELETE FROM orders WHERE id = $1")
+                    .bind(order.id)
+                    .execute(&db.pool)
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_order_stats() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        // A symbol of its own, so other tests' rows are not counted
+        let symbol = format!("STATS{}", Uuid::new_v4().simple());
+        let order = |price, quantity| {
+            Order::new(
+                symbol.clone(),
+                OrderSide::Buy,
+                OrderType::Limit { price },
+                quantity,
+            )
+        };
+        let first = order(100.0, 1.0);
+        let second = order(200.0, 3.0);
+        let half_filled = order(300.0, 4.0);
+        let unfilled = order(400.0, 2.0);
+        db.store_order(&first, &executed(&first)).await.unwrap();
+        db.store_order(&second, &executed(&second)).await.unwrap();
+        let partial = OrderResult {
+            status: OrderStatus::PartiallyFilled,
+            executed_quantity: Some(2.0),
+            ..executed(&half_filled)
+        };
+        db.store_order(&half_filled, &partial).await.unwrap();
+        let pending = OrderResult {
+            status: OrderStatus::Pending,
+            execution_price: None,
+            executed_quantity: None,
+            ..executed(&unfilled)
+        };
+        db.store_order(&unfilled, &pending).await.unwrap();
+        // $500 spent on 2 coins: counted apart from the base quantities
+        let mut quote = order(250.0, 0.0);
+        quote.set_order_quantity(OrderQuantity::Quote(500.0));
+        let spent = OrderResult {
+            executed_quantity: Some(2.0),
+            ..executed(&quote)
+        };
+        db.store_order(&quote, &spent).await.unwrap();
+
+        let query = OrderQuery {
+            symbol: Some(symbol.clone()),
+            ..Default::default()
+        };
+        let stats = db.order_stats(&query).await.unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.total_quantity, 10.0);
+        assert_eq!(stats.total_quote_amount, 500.0);
+        assert_eq!(stats.total_notional, 100.0 + 600.0 + 600.0 + 500.0);
+        assert!((stats.fill_rate - 0.6).abs() < 1e-12);
+        assert_eq!(stats.avg_execution_price, Some(212.5));
+
+        // Filters narrow the aggregate, and an empty match has no average
+        let executed_only = OrderQuery {
+            status: Some(OrderStatus::Executed),
+            ..query.clone()
+        };
+        let stats = db.order_stats(&executed_only).await.unwrap();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.fill_rate, 1.0);
+        let none = OrderQuery {
+            status: Some(OrderStatus::Cancelled),
+            ..query
+        };
+        assert_eq!(db.order_stats(&none).await.unwrap(), OrderStats::default());
+
+        for order in [&first, &second, &half_filled, &unfilled, &quote] {
+            sqlx::query("DELETE FROM orders WHERE id = $1")
+                .bind(order.id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_dead_letters() {
+        use crate::dead_letter::{DeadLetterSink, FileDeadLetterSink};
+
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let path = std::env::temp_dir().join(format!("dead-letters-{}.jsonl", Uuid::new_v4()));
+        let sink = FileDeadLetterSink::new(&path);
+        let order = Order::new(
+            "DLQ/USD".to_string(),
+            OrderSide::Sell,
+            OrderType::Market,
+            0.3,
+        );
+        let pending = OrderResult {
+            status: OrderStatus::Pending,
+            execution_price: None,
+            executed_quantity: None,
+            ..executed(&order)
+        };
+        sink.write(&order, &pending).await.unwrap();
+
+        assert_eq!(db.replay_dead_letters(&path).await.unwrap(), 1);
+        // Replaying again upserts the same row
+        assert_eq!(db.replay_dead_letters(&path).await.unwrap(), 1);
+        let query = OrderQuery {
+            symbol: Some("DLQ/USD".to_string()),
+            ..Default::default()
+        };
+        let stored = db.query_orders(&query).await.unwrap();
+        assert_eq!(stored.iter().filter(|r| r.id == order.id).count(), 1);
+
+        // Once the order has moved on, the stale letter changes nothing
+        db.store_order(&order, &executed(&order)).await.unwrap();
+        db.replay_dead_letters(&path).await.unwrap();
+        let stored = db.query_orders(&query).await.unwrap();
+        let record = stored.iter().find(|r| r.id == order.id).unwrap();
+        assert_eq!(record.status, "executed");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_database_decision_chain() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let key = SigningKey::generate();
+        let mut ids = Vec::new();
+        for action in ["buy", "sell"] {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Market,
+                0.1,
+            );
+            db.store_order(&order, &executed(&order)).await.unwrap();
+            let mut decision = Decision::new(order.id, serde_json::json!({ "action": action }));
+            decision.sign(&key);
+            db.store_decision(&decision, HashAlgorithm::Sha256)
+                .await
+                .unwrap();
+            ids.push(decision.decision_id);
+        }
+
+        let first = db.get_decision(ids[0]).await.unwrap().unwrap();
+        let second = db.get_decision(ids[1]).await.unwrap().unwrap();
+        assert!(first.proof_prev_hash.is_some());
+        assert_eq!(second.proof_prev_hash.as_ref(), Some(&first.proof_hash));
+        db.verify_decision_chain().await.unwrap();
+
+        // Tamper with the row only inside a transaction that is rolled back,
+        // so concurrent tests never see a broken chain
+        let mut tx = db.pool.begin().await.unwrap();
+        sqlx::query("UPDATE decisions SET decision_data = $2 WHERE id = $1")
+            .bind(ids[0])
+            .bind(serde_json::json!({"action": "hold"}))
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let tampered = verify_decision_chain(&mut *tx).await;
+        tx.rollback().await.unwrap();
+        let err = tampered.unwrap_err();
+        assert!(err.to_string().contains(&ids[0].to_string()));
+        db.verify_decision_chain().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decisions_seq_backfills_by_created_at() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        // A pre-seq table in a throwaway schema, dropped with the rollback
+        let mut tx = db.pool.begin().await.unwrap();
+        let schema = format!("legacy_{}", Uuid::new_v4().simple());
+        for statement in [
+            format!("CREATE SCHEMA {}", schema),
+            format!("SET LOCAL search_path = {}", schema),
+            "CREATE TABLE decisions (id UUID PRIMARY KEY, created_at TIMESTAMPTZ NOT NULL)"
+                .to_string(),
+        ] {
+            sqlx::query(&statement).execute(&mut *tx).await.unwrap();
+        }
+        // Inserted newest first, so physical order disagrees with the chain
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for (age, id) in ids.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO decisions (id, created_at) \
+                 VALUES ($1, NOW() - make_interval(secs => $2))",
+            )
+            .bind(id)
+            .bind(age as f64)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+
+        sqlx::query(DECISIONS_SEQ).execute(&mut *tx).await.unwrap();
+        let newest = Uuid::new_v4();
+        sqlx::query("INSERT INTO decisions (id, created_at) VALUES ($1, NOW())")
+            .bind(newest)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let seqs: Vec<(Uuid, i64)> = sqlx::query_as("SELECT id, seq FROM decisions ORDER BY seq")
+            .fetch_all(&mut *tx)
+            .await
+            .unwrap();
+        assert_eq!(
+            seqs,
+            vec![(ids[2], 1), (ids[1], 2), (ids[0], 3), (newest, 4)]
+        );
+
+        // Running it again is a no-op
+        sqlx::query(DECISIONS_SEQ).execute(&mut *tx).await.unwrap();
+        tx.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decision_records_hash_algorithm() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let key = SigningKey::generate();
+
+        for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+            let order = Order::new(
+                "BTC/USD".to_string(),
+                OrderSide::Buy,
+                OrderType::Limit { price: 50000.0 },
+                0.1,
+            );
+            db.store_order(&order, &executed(&order)).await.unwrap();
+
+            let mut decision = Decision::new(
+                order.id,
+                serde_json::json!({"action": "buy", "confidence": 0.8}),
+            );
+            assert!(db.store_decision(&decision, algorithm).await.is_err());
+            decision.sign(&key);
+            let proof_hash = db.store_decision(&decision, algorithm).await.unwrap();
+
+            let mut record = db
+                .get_decision(decision.decision_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(record.hash_algorithm, algorithm.as_str());
+            assert_eq!(record.proof_hash, proof_hash);
+            record.verify_proof().unwrap();
+
+            record.decision_data = serde_json::json!({"action": "sell"});
+            assert!(record.verify_proof().is_err());
+        }
+    }
 }
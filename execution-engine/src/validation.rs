@@ -0,0 +1,107 @@
+use crate::{execution::Order, ExecutionError, Result};
+
+/// One check an order must pass before submission
+///
+/// The engine runs its rules in order and stops at the first failure (see
+/// [`ExecutionEngine::with_validation_rule`](crate::ExecutionEngine::with_validation_rule)).
+/// Rules should report failures as [`ExecutionError::Validation`].
+pub trait ValidationRule: Send + Sync {
+    fn check(&self, order: &Order) -> Result<()>;
+}
+
+/// The quantity, or for quote-sized orders the amount, must be greater
+/// than zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositiveQuantity;
+
+impl ValidationRule for PositiveQuantity {
+    fn check(&self, order: &Order) -> Result<()> {
+        if order.quantity <= 0.0 {
+            let message = if order.quote_quantity {
+                "Quote quantity must be positive"
+            } else {
+                "Quantity must be positive"
+            };
+            return Err(ExecutionError::Validation(message.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// The symbol must not be empty
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonEmptySymbol;
+
+impl ValidationRule for NonEmptySymbol {
+    fn check(&self, order: &Order) -> Result<()> {
+        if order.symbol.is_empty() {
+            return Err(ExecutionError::Validation("Symbol cannot be empty".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// A limit price, if any, must be greater than zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositiveLimitPrice;
+
+impl ValidationRule for PositiveLimitPrice {
+    fn check(&self, order: &Order) -> Result<()> {
+        match order.order_type.limit_price() {
+            Some(price) if price <= 0.0 => {
+                Err(ExecutionError::Validation("Limit price must be positive".to_string()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The built-in rules, in the order the engine runs them by default
+pub fn default_rules() -> Vec<Box<dyn ValidationRule>> {
+    vec![
+        Box::new(PositiveQuantity),
+        Box::new(NonEmptySymbol),
+        Box::new(PositiveLimitPrice),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderQuantity, OrderSide, OrderType};
+
+    #[test]
+    fn test_builtin_rules() {
+        let order = |symbol: &str, order_type, quantity| {
+            Order::new(symbol.to_string(), OrderSide::Buy, order_type, quantity)
+        };
+        let valid = order("BTC/USD", OrderType::Limit { price: 100.0 }, 1.0);
+        for rule in default_rules() {
+            rule.check(&valid).unwrap();
+        }
+
+        assert!(PositiveQuantity
+            .check(&order("BTC/USD", OrderType::Market, 0.0))
+            .is_err());
+        assert!(NonEmptySymbol
+            .check(&order("", OrderType::Market, 1.0))
+            .is_err());
+        assert!(PositiveLimitPrice
+            .check(&order("BTC/USD", OrderType::Limit { price: -1.0 }, 1.0))
+            .is_err());
+        PositiveLimitPrice
+            .check(&order("BTC/USD", OrderType::Market, 1.0))
+            .unwrap();
+
+        // Quote-sized orders need a positive amount too
+        let mut quote = order("BTC/USD", OrderType::Market, 0.0);
+        quote.set_order_quantity(OrderQuantity::Quote(-100.0));
+        let err = PositiveQuantity.check(&quote).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: Quote quantity must be positive"
+        );
+        quote.set_order_quantity(OrderQuantity::Quote(100.0));
+        PositiveQuantity.check(&quote).unwrap();
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+use crate::{
+    crypto::{self, KeyRing, VerificationKey},
+    decision::Decision,
+    execution::{Order, SigningScheme},
+    Error, Result,
+};
+
+/// Signature checker that holds only public keys
+///
+/// Services that audit orders and decisions can use this in place of an
+/// [`ExecutionEngine`](crate::ExecutionEngine) so no secret key material is
+/// ever loaded.
+#[derive(Clone, Default)]
+pub struct Verifier {
+    keys: KeyRing,
+    scheme: SigningScheme,
+}
+
+impl Verifier {
+    pub fn new(keys: impl IntoIterator<Item = VerificationKey>) -> Self {
+        let mut ring = KeyRing::new();
+        for key in keys {
+            ring.add(key);
+        }
+        Self::from_keyring(ring)
+    }
+
+    pub fn from_keyring(keys: KeyRing) -> Self {
+        Self {
+            keys,
+            scheme: SigningScheme::default(),
+        }
+    }
+
+    /// Choose the encoding orders are expected to be signed over
+    pub fn with_signing_scheme(mut self, scheme: SigningScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    pub fn keyring(&self) -> &KeyRing {
+        &self.keys
+    }
+
+    /// Verify an order was signed by one of the trusted keys
+    ///
+    /// Returns the key id of the signer.
+    pub fn verify_order(&self, order: &Order) -> Result<String> {
+        self.find_signer(|key| order.verify_with(key, self.scheme))
+            .ok_or_else(|| Error::Crypto(format!("order {} has no trusted signature", order.id)))
+    }
+
+    /// Verify a decision was signed by one of the trusted keys
+    ///
+    /// Returns the key id of the signer.
+    pub fn verify_decision(&self, decision: &Decision) -> Result<String> {
+        self.find_signer(|key| decision.verify(key)).ok_or_else(|| {
+            Error::Crypto(format!(
+                "decision {} has no trusted signature",
+                decision.decision_id
+            ))
+        })
+    }
+
+    fn find_signer(&self, verify: impl Fn(&VerificationKey) -> Result<()>) -> Option<String> {
+        self.keys
+            .iter()
+            .find(|(_, key)| verify(key).is_ok())
+            .map(|(id, _)| id.to_string())
+    }
+}
+
+/// Entries a [`VerifiedCache`] holds before it is cleared
+const DEFAULT_VERIFIED_CACHE_CAPACITY: usize = 10_000;
+
+/// Order id and signature bytes
+type CacheKey = (Uuid, [u8; 64]);
+
+/// Remembers order signatures that already verified, so checking the same
+/// order again (retries, reconciliation) skips the Ed25519 verification
+///
+/// Entries are keyed by order id and signature and record a hash of the
+/// key and the signed bytes, so an order modified since it was verified is
+/// checked again. Only successful verifications are cached. Opt-in: nothing
+/// in the engine uses it implicitly.
+#[derive(Debug)]
+pub struct VerifiedCache {
+    entries: Mutex<HashMap<CacheKey, [u8; 32]>>,
+    capacity: usize,
+    verifications: AtomicU64,
+}
+
+impl VerifiedCache {
+    /// Cache up to `capacity` entries; when full it is cleared and refilled
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+            verifications: AtomicU64::new(0),
+        }
+    }
+
+    /// [`Order::verify_with`], skipped if this exact order already verified
+    /// against `key`
+    pub fn verify_order(
+        &self,
+        order: &Order,
+        key: &VerificationKey,
+        scheme: SigningScheme,
+    ) -> Result<()> {
+        let signature = order
+            .signature
+            .as_ref()
+            .ok_or_else(|| Error::Crypto("Order is not signed".to_string()))?;
+        let data = order.signing_bytes(scheme)?;
+        let mut keyed = key.to_bytes().to_vec();
+        keyed.extend_from_slice(&data);
+        let fingerprint = crypto::hash_data(&keyed);
+        let entry = (order.id, signature.to_bytes());
+
+        if self.entries.lock().unwrap().get(&entry) == Some(&fingerprint) {
+            return Ok(());
+        }
+
+        self.verifications.fetch_add(1, Ordering::Relaxed);
+        key.verify(&data, signature)?;
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.clear();
+        }
+        entries.insert(entry, fingerprint);
+        Ok(())
+    }
+
+    /// Signature verifications actually performed (cache misses)
+    pub fn verifications(&self) -> u64 {
+        self.verifications.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for VerifiedCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_VERIFIED_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderSide, OrderType};
+    use crate::SigningKey;
+
+    #[test]
+    fn test_verify_order_and_decision() {
+        let trusted = SigningKey::generate();
+        let other = SigningKey::generate();
+        let verifier = Verifier::new([trusted.verification_key()]);
+
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        assert!(verifier.verify_order(&order).is_err());
+        order.sign(&trusted).unwrap();
+        assert_eq!(verifier.verify_order(&order).unwrap(), trusted.key_id());
+        order.sign(&other).unwrap();
+        assert!(verifier.verify_order(&order).is_err());
+
+        let mut decision = Decision::new(order.id, serde_json::json!({"action": "buy"}));
+        decision.sign(&trusted);
+        assert_eq!(
+            verifier.verify_decision(&decision).unwrap(),
+            trusted.key_id()
+        );
+        decision.sign(&other);
+        assert!(verifier.verify_decision(&decision).is_err());
+    }
+
+    #[test]
+    fn test_verified_cache_skips_repeat_verification() {
+        let key = SigningKey::generate();
+        let cache = VerifiedCache::default();
+        let mut order = Order::new(
+            "BTC/USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+        );
+        order.sign(&key).unwrap();
+
+        let vk = key.verification_key();
+        cache
+            .verify_order(&order, &vk, SigningScheme::Binary)
+            .unwrap();
+        cache
+            .verify_order(&order, &vk, SigningScheme::Binary)
+            .unwrap();
+        assert_eq!(cache.verifications(), 1);
+
+        // A different key is not a cache hit
+        let other = SigningKey::generate().verification_key();
+        assert!(cache
+            .verify_order(&order, &other, SigningScheme::Binary)
+            .is_err());
+        assert_eq!(cache.verifications(), 2);
+
+        // Changing the signed bytes invalidates the entry
+        order.quantity = 2.0;
+        assert!(cache
+            .verify_order(&order, &vk, SigningScheme::Binary)
+            .is_err());
+        assert_eq!(cache.verifications(), 3);
+    }
+}
@@ -1,7 +1,7 @@
 //! Integration tests for execution engine
 
 use execution_engine::execution::{Order, OrderSide, OrderType};
-use execution_engine::{ExecutionEngine, SigningKey};
+use execution_engine::{Error, ExecutionEngine, ExecutionError, SigningKey};
 
 #[tokio::test]
 async fn test_full_execution_flow() {
@@ -43,7 +43,10 @@ async fn test_order_validation() {
         OrderType::Market,
         -0.1, // Invalid
     );
-    assert!(engine.validate_order(&order).is_err());
+    assert!(matches!(
+        engine.validate_order(&order),
+        Err(Error::Execution(ExecutionError::Validation(_)))
+    ));
 
     // Test empty symbol
     let order = Order::new("".to_string(), OrderSide::Buy, OrderType::Market, 0.1);